@@ -72,6 +72,17 @@ class SintBindings:
         raw_actions = sint_core.get_valid_actions(rust_state, player_id)
         return [Action.model_validate(cls._to_pydantic_friendly(a)) for a in cast(List[Dict[str, Any]], raw_actions)]
 
+    @classmethod
+    def explain_action(cls, state: GameState, player_id: str, action: GameAction) -> Dict[str, Any]:
+        rust_state = cls._to_rust_friendly(state)
+        rust_action = action.model_dump(mode='json')
+        return cast(Dict[str, Any], sint_core.explain_action(rust_state, player_id, rust_action))
+
+    @classmethod
+    def forecast(cls, state: GameState) -> Dict[str, Any]:
+        rust_state = cls._to_rust_friendly(state)
+        return cast(Dict[str, Any], sint_core.forecast(rust_state))
+
     @staticmethod
     def get_schema() -> str:
         return cast(str, sint_core.get_schema_json())
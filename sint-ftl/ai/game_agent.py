@@ -11,7 +11,10 @@ import sint_core # type: ignore
 from context import MemoryBank
 from tools import load_game_tools
 from bindings_wrapper import SintBindings
-from game_types import GameState, Action, GamePhase, Player, Room, Card, Enemy, ProposedAction, CardId
+from game_types import GameState, Action, ChatMessage, GameAction, GamePhase, Player, Room, Card, Enemy, ProposedAction, CardId
+
+# Must match sint_protocol::PROTOCOL_VERSION.
+PROTOCOL_VERSION = 2
 
 class GameAgent:
     def __init__(self, player_id: str, room_id: str, server_url: str, max_turns: int = 0, debug: bool = False) -> None:
@@ -36,17 +39,37 @@ class GameAgent:
         self.system_instr = self._load_system_prompt()
 
         self.websocket: Optional[Any] = None
+        # Issued by the server on Welcome; presented again to reclaim the seat.
+        self.session_token: Optional[str] = None
+        # Agreed with the server in the Hello handshake.
+        self.capabilities: List[str] = []
+        # Chat as recorded from sequenced events; the game state only holds the
+        # messages of the last action.
+        self.chat_log: List[ChatMessage] = []
 
     async def run(self) -> None:
         print(f"Agent {self.player_id} connecting to {self.server_url}...")
         try:
             async with websockets.connect(self.server_url) as ws:
                 self.websocket = ws
+
+                # Handshake; the agent only needs Resume
+                await ws.send(json.dumps({
+                    "type": "Hello",
+                    "payload": {
+                        "protocol_version": PROTOCOL_VERSION,
+                        "capabilities": ["Resume"],
+                    }
+                }))
                 
                 # Join (Network)
                 await ws.send(json.dumps({
                     "type": "Join", 
-                    "payload": { "room_id": self.room_id, "player_id": self.player_id }
+                    "payload": {
+                        "room_id": self.room_id,
+                        "player_id": self.player_id,
+                        "session_token": self.session_token,
+                    }
                 }))
                 
                 # Join (Game State)
@@ -96,8 +119,20 @@ class GameAgent:
         payload = data.get("payload")
         if payload is None: return
 
-        if msg_type == "Welcome":
+        if msg_type == "Hello":
+            self.capabilities = payload.get("capabilities", [])
+
+        elif msg_type == "HelloRejected":
+            print(f"Server rejected protocol {PROTOCOL_VERSION}: {payload.get('reason')}")
+            if self.websocket:
+                await self.websocket.close()
+
+        elif msg_type == "Welcome":
             print(f"Joined room: {payload.get('room_id')}")
+            self.session_token = payload.get("session_token")
+
+        elif msg_type == "JoinRejected":
+            print(f"Join rejected: {payload.get('reason')}")
             
         elif msg_type == "SyncRequest":
             req_id = payload.get("requestor_id")
@@ -123,6 +158,9 @@ class GameAgent:
 
                 action_model = Action.model_validate(action_dict)
                 self.state = SintBindings.apply_action(self.state, pid, action_model, None)
+                for msg in self.state.chat or []:
+                    msg.timestamp = payload.get("timestamp", 0)
+                    self.chat_log.append(msg)
                 
                 # Use model for type-safe access
                 # Action (Root) -> Union[GameAction, MetaAction] (Root) -> Variant
@@ -214,8 +252,7 @@ class GameAgent:
         memory_text = self.memory.get_full_context_text()
         
         # Chat Log (Source of Truth)
-        chat_log = state.chat_log
-        recent_chat = chat_log[-10:] # Last 10 messages
+        recent_chat = self.chat_log[-10:] # Last 10 messages
         chat_lines = []
         for msg in recent_chat:
             sender = msg.sender
@@ -278,6 +315,36 @@ class GameAgent:
         else:
             enemy_intent = "ENEMY INTENT: Unknown (Hidden or not yet revealed)."
 
+        # Why key ship systems are unavailable right now
+        blocked_desc = ""
+        if phase == GamePhase.TacticalPlanning:
+            for act_type in ["Shoot", "Bake", "RaiseShields", "EvasiveManeuvers"]:
+                explanation = SintBindings.explain_action(
+                    state, self.player_id, GameAction.model_validate({"type": act_type})
+                )
+                if explanation["allowed"]:
+                    continue
+                reasons = "; ".join(b["message"] for b in explanation["blockers"])
+                blocked_desc += f"- {act_type}: {reasons}"
+                if explanation["suggestions"]:
+                    blocked_desc += " -> " + ", then ".join(explanation["suggestions"])
+                blocked_desc += "\n"
+            if blocked_desc:
+                blocked_desc = "BLOCKED ACTIONS (and how to unblock them):\n" + blocked_desc
+
+        # Odds for the coming enemy phase if nobody does anything beyond the queue
+        forecast_desc = ""
+        if phase == GamePhase.TacticalPlanning:
+            forecast = SintBindings.forecast(state)
+            forecast_desc = (
+                f"FORECAST (if the crew stops now): expected hull loss {forecast['expected_hull_loss']:.1f}, "
+                f"game over risk {forecast['game_over_risk']:.0%}, "
+                f"expected enemy HP {forecast['expected_enemy_hp']:.1f}.\n"
+            )
+            at_risk = [f"{pid} {risk:.0%}" for pid, risk in forecast["faint_risk"].items() if risk > 0]
+            if at_risk:
+                forecast_desc += "FAINT RISK: " + ", ".join(at_risk) + "\n"
+
         # Map Topology
         map_desc = "SHIP LAYOUT:\n"
         try:
@@ -294,6 +361,8 @@ class GameAgent:
             ap_warning,
             situation_desc,
             enemy_intent,
+            blocked_desc,
+            forecast_desc,
             "",
             map_desc,
             "RECENT EVENTS:",
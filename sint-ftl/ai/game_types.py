@@ -102,10 +102,39 @@ class CardType(RootModel[Union[CardType1, CardType2]]):
     root: Union[CardType1, CardType2]
 
 
+class ChatChannel(Enum):
+    Crew = 'Crew'
+    Spectators = 'Spectators'
+    System = 'System'
+
+
+class ChatEntry(BaseModel):
+    channel: Optional[ChatChannel] = ChatChannel.Crew
+    sender: str
+    sequence_id: Annotated[
+        int, Field(description='The event that produced the message.', ge=0)
+    ]
+    text: str
+    timestamp: Annotated[
+        int,
+        Field(
+            description='Unix milliseconds at which the server sequenced the event that produced the\nmessage. 0 until the message is recorded in a `ChatLog`.',
+            ge=0,
+        ),
+    ]
+
+
 class ChatMessage(BaseModel):
+    channel: Optional[ChatChannel] = ChatChannel.Crew
     sender: str
     text: str
-    timestamp: Annotated[int, Field(ge=0)]
+    timestamp: Annotated[
+        int,
+        Field(
+            description='Unix milliseconds at which the server sequenced the event that produced the\nmessage. 0 until the message is recorded in a `ChatLog`.',
+            ge=0,
+        ),
+    ]
 
 
 class EffectType1(Enum):
@@ -516,10 +545,12 @@ class GameState(BaseModel):
         list[Card], Field(description='Active "Situation" cards')
     ]
     boss_level: Annotated[int, Field(description='Current Boss Level (0-3)', ge=0)]
-    chat_log: Annotated[
-        list[ChatMessage],
-        Field(description='Chat History (Event Sourcing derived or stored)'),
-    ]
+    chat: Annotated[
+        Optional[list[ChatMessage]],
+        Field(
+            description='Chat produced by the last applied action. Peers record it in their own\n`ChatLog` (see `crate::chat`), so history is not part of the state.'
+        ),
+    ] = None
     deck: Annotated[list[CardId], Field(description='The Draw Deck')]
     discard: Annotated[list[CardId], Field(description='The Discard Pile')]
     enemy: Annotated[Enemy, Field(description='The Enemy (Boss)')]
@@ -560,4 +591,5 @@ class GameState(BaseModel):
 
 class FullSchema(BaseModel):
     action: Action
+    chat: ChatEntry
     state: GameState
@@ -1,5 +1,6 @@
 use crate::game::GameView;
 use crate::lobby::LobbyBrowser;
+use crate::ws::{Credentials, password_key};
 use leptos::either::Either;
 use leptos::prelude::*;
 use leptos_router::hooks::use_query_map;
@@ -9,13 +10,23 @@ pub fn App() -> impl IntoView {
     let query_map = use_query_map();
     let room_param = query_map.get().get("room");
     let player_param = query_map.get().get("player");
+    let invite_param = query_map.get().get("invite");
 
     match room_param {
         Some(rid) => {
             let pid = player_param
                 .unwrap_or_else(|| format!("Player_{}", &uuid::Uuid::new_v4().to_string()[..5]));
 
-            Either::Left(view! { <GameView room_id=rid player_id=pid /> })
+            // The lobby leaves the password in session storage rather than the URL
+            let password = web_sys::window()
+                .and_then(|w| w.session_storage().ok().flatten())
+                .and_then(|s| s.get_item(&password_key(&rid)).ok().flatten());
+            let credentials = Credentials {
+                invite_token: invite_param,
+                password,
+            };
+
+            Either::Left(view! { <GameView room_id=rid player_id=pid credentials=credentials /> })
         }
         None => Either::Right(view! { <LobbyBrowser /> }),
     }
@@ -1,10 +1,18 @@
 use crate::state::GameContext;
 use leptos::prelude::*;
-use sint_core::{Action, GameAction};
+use sint_core::{Action, ChatChannel, GameAction};
+
+/// Local `HH:MM` of a server timestamp.
+fn clock(timestamp: u64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp as f64));
+    format!("{:02}:{:02}", date.get_hours(), date.get_minutes())
+}
 
 #[component]
 pub fn ChatView(ctx: GameContext) -> impl IntoView {
-    let state = ctx.state;
+    let chat = ctx.chat;
+    let has_older_chat = ctx.has_older_chat;
+    let load_older_chat = ctx.load_older_chat.clone();
     let pid = ctx.player_id.clone();
     let ctx_send = ctx.clone();
 
@@ -26,18 +34,45 @@ pub fn ChatView(ctx: GameContext) -> impl IntoView {
     view! {
         <div style="background: #222; border: 1px solid #444; border-radius: 8px; display: flex; flex-direction: column; height: 100%; box-sizing: border-box;">
             <div style="flex: 1; overflow-y: auto; padding: 10px; display: flex; flex-direction: column; gap: 8px;">
+                <Show when=move || has_older_chat.get()>
+                    <button
+                        style="align-self: center; padding: 4px 10px; background: none; border: 1px solid #555; color: #aaa; border-radius: 4px; cursor: pointer; font-size: 0.8em;"
+                        on:click={
+                            let load_older_chat = load_older_chat.clone();
+                            move |_| load_older_chat.call()
+                        }
+                    >
+                        "Load older messages"
+                    </button>
+                </Show>
                 {move || {
-                    state
-                        .get()
-                        .chat_log
-                        .into_iter()
-                        .map(|msg| {
+                    chat.get()
+                        .entries()
+                        .iter()
+                        .map(|entry| {
+                            let msg = entry.message.clone();
+                            let time = clock(msg.timestamp);
+                            if msg.channel == ChatChannel::System {
+                                return view! {
+                                    <div style="align-self: center; color: #ffb74d; font-size: 0.85em; font-style: italic; text-align: center;">
+                                        {format!("{} {}", time, msg.text)}
+                                    </div>
+                                }
+                                    .into_any();
+                            }
                             let is_me = msg.sender == pid;
                             let align = if is_me {
                                 "align-self: flex-end; background: #3f51b5;"
+                            } else if msg.channel == ChatChannel::Spectators {
+                                "align-self: flex-start; background: #333; border: 1px dashed #555;"
                             } else {
                                 "align-self: flex-start; background: #444;"
                             };
+                            let sender = if msg.channel == ChatChannel::Spectators {
+                                format!("{} (spectator) · {}", msg.sender, time)
+                            } else {
+                                format!("{} · {}", msg.sender, time)
+                            };
 
                             view! {
                                 <div style=format!(
@@ -45,11 +80,12 @@ pub fn ChatView(ctx: GameContext) -> impl IntoView {
                                     align,
                                 )>
                                     <div style="font-size: 0.7em; opacity: 0.7; margin-bottom: 2px;">
-                                        {msg.sender}
+                                        {sender}
                                     </div>
                                     <div>{msg.text}</div>
                                 </div>
                             }
+                                .into_any()
                         })
                         .collect::<Vec<_>>()
                 }}
@@ -1,9 +1,11 @@
 use crate::chat::ChatView;
 use crate::map::MapView;
 use crate::state::{GameContext, provide_game_context};
+use crate::ws::{AddBots, Credentials};
+use gloo_net::http::Request;
 use leptos::either::Either;
 use leptos::prelude::*;
-use sint_core::{Action, GameAction, GamePhase, MetaAction, types::MapLayout};
+use sint_core::{Action, GameAction, GamePhase, MetaAction, SystemType, types::MapLayout};
 
 #[component]
 fn PhaseTracker(phase: GamePhase) -> impl IntoView {
@@ -42,11 +44,43 @@ fn PhaseTracker(phase: GamePhase) -> impl IntoView {
 }
 
 #[component]
-pub fn GameView(room_id: String, player_id: String) -> impl IntoView {
-    let ctx = provide_game_context(room_id, player_id);
+pub fn GameView(room_id: String, player_id: String, credentials: Credentials) -> impl IntoView {
+    let bot_request = AddBots {
+        invite_token: credentials.invite_token.clone(),
+    };
+    let bots_url = format!("/api/rooms/{}/bots", room_id);
+    let ctx = provide_game_context(room_id, player_id, credentials);
     let state = ctx.state;
     let pid = ctx.player_id.clone();
     let is_connected = ctx.is_connected;
+    let join_error = ctx.join_error;
+    let system_message = ctx.system_message;
+    let (bot_error, set_bot_error) = signal(None::<String>);
+
+    let add_bot = move || {
+        let url = bots_url.clone();
+        let body = bot_request.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let resp = match Request::post(&url).json(&body) {
+                Ok(req) => req.send().await,
+                Err(e) => Err(e),
+            };
+            match resp {
+                Ok(resp) if resp.ok() => set_bot_error.set(None),
+                Ok(resp) if resp.status() == 409 => {
+                    set_bot_error.set(Some("Room is full".to_owned()))
+                }
+                Ok(resp) if resp.status() == 403 => {
+                    set_bot_error.set(Some("Only the room's creator can add bots".to_owned()))
+                }
+                Ok(resp) if resp.status() == 503 => {
+                    set_bot_error.set(Some("The server is running too many bots".to_owned()))
+                }
+                Ok(resp) => set_bot_error.set(Some(format!("Server returned {}", resp.status()))),
+                Err(e) => set_bot_error.set(Some(e.to_string())),
+            }
+        });
+    };
 
     view! {
         <div style="display: flex; flex-direction: column; height: 100vh; background: #222; color: #eee; font-family: monospace; overflow: hidden;">
@@ -65,11 +99,47 @@ pub fn GameView(room_id: String, player_id: String) -> impl IntoView {
                             }
                         }
                     }}
+                    {move || {
+                        join_error
+                            .get()
+                            .map(|e| {
+                                view! {
+                                    <span style="color: #f44336; font-size: 0.8em;">
+                                        {e} " " <a href="/" style="color: #90caf9;">"Back to lobby"</a>
+                                    </span>
+                                }
+                            })
+                    }}
+                    {move || {
+                        system_message
+                            .get()
+                            .map(|m| {
+                                view! {
+                                    <span style="color: #ffb300; font-size: 0.8em;">"📢 " {m}</span>
+                                }
+                            })
+                    }}
                 </div>
 
                 {move || view! { <PhaseTracker phase=state.get().phase /> }}
 
                 <div style="text-align: right; font-size: 0.9em;">
+                    {move || {
+                        bot_error
+                            .get()
+                            .map(|e| {
+                                view! {
+                                    <span style="margin-right: 10px; color: #f44336;">{e}</span>
+                                }
+                            })
+                    }}
+                    <button
+                        on:click=move |_| add_bot()
+                        title="Seat a solver-driven bot player"
+                        style="margin-right: 15px; padding: 2px 8px; background: #555; color: white; border: none; border-radius: 2px; cursor: pointer;"
+                    >
+                        "🤖 ADD BOT"
+                    </button>
                     <span style="margin-right: 15px; font-weight: bold; color: #81c784;">
                         {move || pid.clone()}
                     </span>
@@ -159,6 +229,12 @@ pub fn GameView(room_id: String, player_id: String) -> impl IntoView {
 
                 // RIGHT PANEL: Comms
                 <div style="background: #2a2a2a; border-left: 1px solid #444; display: flex; flex-direction: column;">
+                    <div style="flex: 0 0 auto; border-bottom: 1px solid #444;">
+                        <div style="padding: 10px; background: #1a1a1a; font-weight: bold; border-bottom: 1px solid #444;">
+                            "Crew"
+                        </div>
+                        <CrewView ctx=ctx.clone() />
+                    </div>
                     <div style="flex: 1; border-bottom: 1px solid #444; overflow: hidden; display: flex; flex-direction: column;">
                         <div style="padding: 10px; background: #1a1a1a; font-weight: bold; border-bottom: 1px solid #444;">
                             "Tactical Plan"
@@ -182,6 +258,49 @@ pub fn GameView(room_id: String, player_id: String) -> impl IntoView {
     }
 }
 
+/// Who is in the game and who is actually connected right now.
+#[component]
+fn CrewView(ctx: GameContext) -> impl IntoView {
+    let state = ctx.state;
+    let online = ctx.online;
+    let is_connected = ctx.is_connected;
+
+    view! {
+        <div style="padding: 10px; font-size: 0.9em;">
+            {move || {
+                let s = state.get();
+                let online = online.get();
+                let connected = is_connected.get();
+                s.players
+                    .values()
+                    .map(|p| {
+                        let here = connected && online.contains(&p.id);
+                        let (dot, color, title) = if here {
+                            ("●", "#4caf50", "Online")
+                        } else {
+                            ("○", "#777", "Offline")
+                        };
+                        view! {
+                            <div style="display: flex; justify-content: space-between; margin-bottom: 4px;">
+                                <span>
+                                    <span title=title style=format!("color: {};", color)>
+                                        {dot}
+                                    </span>
+                                    " "
+                                    {p.name.clone()}
+                                </span>
+                                <span style="color: #aaa;">
+                                    {if p.is_ready { "READY" } else { "" }}
+                                </span>
+                            </div>
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }}
+        </div>
+    }
+}
+
 #[component]
 fn ProposalQueueView(ctx: GameContext) -> impl IntoView {
     let state = ctx.state;
@@ -424,7 +543,7 @@ fn MyStatus(ctx: GameContext) -> impl IntoView {
                 if let Some(real_p) = s.players.get(&pid) {
                     let mut p = real_p.clone();
                     for prop in &s.proposal_queue {
-                        if prop.player_id == pid && let GameAction::Move { to_room } = prop.action {
+                        if prop.player_id == pid && let GameAction::Move { to_room, .. } = prop.action {
                             p.room_id = to_room;
                         }
                     }
@@ -706,6 +825,11 @@ fn Actions(ctx: GameContext) -> impl IntoView {
                             ),
                         ];
                     }
+                    // The local system's action, if blocked, is shown disabled with the reason.
+                    let blocked_action = room_system_action(&s, &pid_iter)
+                        .filter(|a| !valid_actions.contains(&Action::Game(a.clone())));
+                    let blocked_button = blocked_action
+                        .map(|a| Either::Right(Either::Right(render_blocked_button(&s, &pid_iter, a))));
                     valid_actions
                         .into_iter()
                         .filter_map(move |a| {
@@ -717,7 +841,9 @@ fn Actions(ctx: GameContext) -> impl IntoView {
                                     _ => {
                                         Some(
                                             Either::Right(
-                                                render_action_button(ctx_iter.clone(), &s, &pid_iter, ga),
+                                                Either::Left(
+                                                    render_action_button(ctx_iter.clone(), &s, &pid_iter, ga),
+                                                ),
                                             ),
                                         )
                                     }
@@ -726,6 +852,7 @@ fn Actions(ctx: GameContext) -> impl IntoView {
                                 None
                             }
                         })
+                        .chain(blocked_button)
                         .collect::<Vec<Either<_, _>>>()
                 }}
             </div>
@@ -733,6 +860,39 @@ fn Actions(ctx: GameContext) -> impl IntoView {
     }
 }
 
+/// The action offered by the system in the player's current room.
+fn room_system_action(state: &sint_core::GameState, pid: &str) -> Option<GameAction> {
+    let p = state.players.get(pid)?;
+    let room = state.map.rooms.get(&p.room_id)?;
+    match room.system? {
+        SystemType::Kitchen => Some(GameAction::Bake),
+        SystemType::Cannons => Some(GameAction::Shoot),
+        SystemType::Bridge => Some(GameAction::RaiseShields),
+        SystemType::Engine => Some(GameAction::EvasiveManeuvers),
+        SystemType::Bow => Some(GameAction::Lookout),
+        _ => None,
+    }
+}
+
+fn render_blocked_button(
+    state: &sint_core::GameState,
+    pid: &str,
+    action: GameAction,
+) -> impl IntoView + use<> {
+    let explanation = sint_core::logic::explain_action(state, pid, &action);
+    let label = format!("{:?} (blocked)", action);
+
+    view! {
+        <button
+            disabled=true
+            title=explanation.summary()
+            style="padding: 10px; background: #444; border: 1px dashed #777; color: #999; border-radius: 4px; cursor: help; margin-right: 5px; margin-bottom: 5px;"
+        >
+            {label}
+        </button>
+    }
+}
+
 fn render_action_button(
     ctx: GameContext,
     state: &sint_core::GameState,
@@ -744,7 +904,7 @@ fn render_action_button(
 
     // Determine Label & Style
     let (label, color, border) = match &action {
-        GameAction::Move { to_room } => {
+        GameAction::Move { to_room, .. } => {
             let room_name = state
                 .map
                 .rooms
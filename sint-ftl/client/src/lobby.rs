@@ -1,11 +1,72 @@
+use crate::ws::password_key;
 use gloo_net::http::Request;
 use leptos::either::Either;
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
+use sint_core::{GamePhase, MapLayout};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RoomInfo {
+    id: String,
+    private: bool,
+    players: Vec<String>,
+    player_count: usize,
+    connections: usize,
+    max_players: usize,
+    phase: GamePhase,
+    layout: MapLayout,
+    boss: String,
+    boss_level: u32,
+    created_at: u64,
+    last_activity: u64,
+}
+
+impl RoomInfo {
+    fn is_full(&self) -> bool {
+        self.connections >= self.max_players
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 struct RoomList {
-    rooms: Vec<String>,
+    rooms: Vec<RoomInfo>,
+}
+
+#[derive(Serialize)]
+struct CreateRoom {
+    room_id: String,
+    password: Option<String>,
+    unlisted: bool,
+}
+
+#[derive(Deserialize)]
+struct CreatedRoom {
+    room_id: String,
+    invite_token: String,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+fn remember_password(room_id: &str, password: &str) {
+    if let Some(storage) = web_sys::window().and_then(|w| w.session_storage().ok().flatten()) {
+        let _ = storage.set_item(&password_key(room_id), password);
+    }
+}
+
+/// "just now", "5m ago", "2h ago" for a unix timestamp in seconds.
+fn time_ago(unix_secs: u64) -> String {
+    let now = (js_sys::Date::now() / 1000.0) as u64;
+    let secs = now.saturating_sub(unix_secs);
+    if secs < 60 {
+        "just now".to_owned()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else {
+        format!("{}h ago", secs / 3600)
+    }
 }
 
 #[component]
@@ -18,6 +79,10 @@ pub fn LobbyBrowser() -> impl IntoView {
 
     // New Room State
     let (new_room_name, set_new_room_name) = signal("Room_A".to_owned());
+    let (private_room, set_private_room) = signal(false);
+    let (new_password, set_new_password) = signal(String::new());
+    let (unlisted, set_unlisted) = signal(false);
+    let (create_error, set_create_error) = signal(None::<String>);
 
     // Fetch Rooms
     let rooms_resource = LocalResource::new(move || async move {
@@ -35,14 +100,70 @@ pub fn LobbyBrowser() -> impl IntoView {
         }
     });
 
-    let join_game = move |room: String| {
+    let enter_room = move |room: String, invite: Option<String>| {
         let p = player_name.get();
         if !p.is_empty() {
-            let url = format!("?room={}&player={}", room, p);
+            let mut url = format!("?room={}&player={}", room, p);
+            if let Some(token) = invite {
+                url.push_str(&format!("&invite={}", token));
+            }
             let _ = web_sys::window().unwrap().location().set_href(&url);
         }
     };
 
+    let join_game = move |room: String, private: bool| {
+        if private {
+            let window = web_sys::window().unwrap();
+            let prompt = format!("Password for {}:", room);
+            match window.prompt_with_message(&prompt) {
+                Ok(Some(password)) => remember_password(&room, &password),
+                _ => return,
+            }
+        }
+        enter_room(room, None);
+    };
+
+    let create_game = move || {
+        let room_id = new_room_name.get();
+        if !private_room.get() {
+            // Public rooms are created by the first Join
+            enter_room(room_id, None);
+            return;
+        }
+        let password = Some(new_password.get()).filter(|p| !p.is_empty());
+        let body = CreateRoom {
+            room_id,
+            password: password.clone(),
+            unlisted: unlisted.get(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let resp = match Request::post("/api/rooms").json(&body) {
+                Ok(req) => req.send().await,
+                Err(e) => Err(e),
+            };
+            match resp {
+                Ok(resp) if resp.ok() => match resp.json::<CreatedRoom>().await {
+                    Ok(created) => {
+                        if let Some(password) = &password {
+                            remember_password(&created.room_id, password);
+                        }
+                        enter_room(created.room_id, Some(created.invite_token));
+                    }
+                    Err(e) => set_create_error.set(Some(e.to_string())),
+                },
+                Ok(resp) => {
+                    let msg = match resp.json::<ApiError>().await {
+                        Ok(e) if e.error == "RoomExists" => "That room name is taken.".to_owned(),
+                        Ok(e) => e.error,
+                        Err(_) => format!("Server returned {}", resp.status()),
+                    };
+                    set_create_error.set(Some(msg));
+                }
+                Err(e) => set_create_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
     view! {
         <div style="padding: 20px; font-family: monospace; color: white; background: #222; min-height: 100vh;">
             <h1>"Sint FTL - Lobby"</h1>
@@ -69,12 +190,44 @@ pub fn LobbyBrowser() -> impl IntoView {
                             style="padding: 8px; border-radius: 4px; border: 1px solid #555; background: #333; color: white;"
                         />
                         <button
-                            on:click=move |_| join_game(new_room_name.get())
+                            on:click=move |_| create_game()
                             style="padding: 8px 16px; background: #4caf50; color: white; border: none; border-radius: 4px; cursor: pointer;"
                         >
                             "CREATE & JOIN"
                         </button>
                     </div>
+                    <div style="margin-top: 10px; display: flex; gap: 15px; align-items: center; flex-wrap: wrap;">
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=private_room
+                                on:change=move |ev| set_private_room.set(event_target_checked(&ev))
+                            />
+                            " Private (invite link)"
+                        </label>
+                        <Show when=move || private_room.get()>
+                            <input
+                                type="password"
+                                placeholder="Password (optional)"
+                                prop:value=new_password
+                                on:input=move |ev| set_new_password.set(event_target_value(&ev))
+                                style="padding: 4px 8px; border-radius: 4px; border: 1px solid #555; background: #333; color: white;"
+                            />
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    prop:checked=unlisted
+                                    on:change=move |ev| set_unlisted.set(event_target_checked(&ev))
+                                />
+                                " Unlisted"
+                            </label>
+                        </Show>
+                    </div>
+                    {move || {
+                        create_error
+                            .get()
+                            .map(|e| view! { <div style="margin-top: 10px; color: #f44336;">{e}</div> })
+                    }}
                 </div>
 
                 // Join Existing
@@ -101,15 +254,46 @@ pub fn LobbyBrowser() -> impl IntoView {
                                                         .rooms
                                                         .into_iter()
                                                         .map(|r| {
-                                                            let r_clone = r.clone();
+                                                            let r_clone = r.id.clone();
+                                                            let private = r.private;
+                                                            let full = r.is_full();
+                                                            let players = if r.players.is_empty() {
+                                                                "no players yet".to_owned()
+                                                            } else {
+                                                                r.players.join(", ")
+                                                            };
                                                             view! {
                                                                 <div style="margin-bottom: 5px; display: flex; justify-content: space-between; align-items: center; background: #333; padding: 8px; border-radius: 4px;">
-                                                                    <span>{r.clone()}</span>
+                                                                    <div>
+                                                                        <div>
+                                                                            <b>{r.id.clone()}</b>
+                                                                            {if r.private { " 🔒" } else { "" }}
+                                                                            {format!(
+                                                                                " — {}/{} connected · {:?} · {:?}",
+                                                                                r.connections,
+                                                                                r.max_players,
+                                                                                r.phase,
+                                                                                r.layout,
+                                                                            )}
+                                                                        </div>
+                                                                        <div style="color: #aaa; font-size: 0.9em;">
+                                                                            {format!(
+                                                                                "{} ({}) · boss: {} (level {}) · created {} · active {}",
+                                                                                players,
+                                                                                r.player_count,
+                                                                                r.boss,
+                                                                                r.boss_level + 1,
+                                                                                time_ago(r.created_at),
+                                                                                time_ago(r.last_activity),
+                                                                            )}
+                                                                        </div>
+                                                                    </div>
                                                                     <button
-                                                                        on:click=move |_| join_game(r_clone.clone())
+                                                                        on:click=move |_| join_game(r_clone.clone(), private)
+                                                                        disabled=full
                                                                         style="padding: 4px 10px; background: #2196f3; color: white; border: none; border-radius: 4px; cursor: pointer;"
                                                                     >
-                                                                        "JOIN"
+                                                                        {if full { "FULL" } else { "JOIN" }}
                                                                     </button>
                                                                 </div>
                                                             }
@@ -3,7 +3,8 @@ use leptos::either::Either;
 use leptos::prelude::*;
 use sint_core::{
     Action, AttackEffect, GameAction, GamePhase, HazardType, ItemType, Player, Room,
-    logic::pathfinding::find_path, types::MapLayout,
+    logic::pathfinding::{RouteMode, find_route},
+    types::MapLayout,
 };
 
 #[derive(Clone, Copy, PartialEq)]
@@ -280,7 +281,7 @@ fn RoomCard(room: Room, ctx: GameContext, door_dir: Option<DoorDirection>) -> im
             // but we need to track position.
             for prop in &s.proposal_queue {
                 if prop.player_id == *my_pid
-                    && let GameAction::Move { to_room } = prop.action
+                    && let GameAction::Move { to_room, .. } = prop.action
                 {
                     predicted_room_id = Some(to_room);
                 }
@@ -291,24 +292,22 @@ fn RoomCard(room: Room, ctx: GameContext, door_dir: Option<DoorDirection>) -> im
         let mut path = None;
         let mut can_move = false;
 
+        // Clicking a neighbor is explicit enough to walk into fire; longer routes avoid it.
         if s.phase == GamePhase::TacticalPlanning
             && let Some(pred_rid) = predicted_room_id
             && pred_rid != room.id
-            && let Some(p) = find_path(&s.map, pred_rid, room.id)
+            && let Ok(route) = find_route(
+                &s,
+                my_pid,
+                pred_rid,
+                room.id,
+                RouteMode::Safest,
+                room.neighbors.contains(&pred_rid),
+            )
+            && route.ap_cost <= predicted_ap
         {
-            let mut cost = 0;
-            for step in &p {
-                cost += sint_core::logic::actions::action_cost(
-                    &s,
-                    my_pid,
-                    &GameAction::Move { to_room: *step },
-                );
-            }
-
-            if cost <= predicted_ap {
-                can_move = true;
-                path = Some(p);
-            }
+            can_move = true;
+            path = Some(route.steps);
         }
 
         // 4. Other Status
@@ -325,7 +324,7 @@ fn RoomCard(room: Room, ctx: GameContext, door_dir: Option<DoorDirection>) -> im
             .proposal_queue
             .iter()
             .filter_map(|prop| {
-                if let GameAction::Move { to_room } = prop.action
+                if let GameAction::Move { to_room, .. } = prop.action
                     && to_room == room.id
                 {
                     return Some(prop.player_id.clone());
@@ -400,12 +399,13 @@ fn RoomCard(room: Room, ctx: GameContext, door_dir: Option<DoorDirection>) -> im
                         hover_style,
                     )
                     on:click=move |_| {
-                        if can_move && let Some(steps) = path.clone() {
-                            for step in steps {
-                                ctx_click_inner
-                                    .perform_action
-                                    .call(Action::Game(GameAction::Move { to_room: step }));
-                            }
+                        // The core expands the move along the same safest route
+                        if can_move && let Some(&to_room) = path.as_ref().and_then(|p| p.last()) {
+                            ctx_click_inner.perform_action.call(Action::Game(GameAction::Move {
+                                to_room,
+                                mode: RouteMode::Safest,
+                                force: false,
+                            }));
                         }
                     }
                 >
@@ -1,10 +1,16 @@
-use crate::ws::{ClientMessage, ServerMessage};
+use crate::ws::{
+    Capability, ClientMessage, Credentials, PROTOCOL_VERSION, ServerMessage, chat_url,
+    initial_state, msgpack, session_key,
+};
 use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
+use gloo_net::http::Request;
 use gloo_net::websocket::{Message, futures::WebSocket};
 use leptos::prelude::*;
-use sint_core::{Action, GameLogic, GameState, MetaAction, PlayerEvent};
-use std::collections::VecDeque;
+use sint_core::chat::{ChatLog, ChatPage};
+use sint_core::{Action, GameState, MetaAction, PlayerEvent};
+use sint_protocol::replica::Replica;
+use std::collections::BTreeSet;
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
@@ -15,6 +21,21 @@ pub struct GameContext {
     pub player_id: String,
     pub perform_action: ActionCallback,
     pub is_connected: ReadSignal<bool>,
+    /// Why the server refused to let us in or removed us, if it did.
+    pub join_error: ReadSignal<Option<String>>,
+    /// The latest announcement from the server operators.
+    pub system_message: ReadSignal<Option<String>>,
+    /// Players with a live connection to the room.
+    pub online: ReadSignal<BTreeSet<String>>,
+    /// Confirmed chat, in server order.
+    pub chat: ReadSignal<ChatLog>,
+    /// Whether the server has chat from before what `chat` holds.
+    pub has_older_chat: Signal<bool>,
+    pub load_older_chat: LoadOlderChat,
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
 }
 
 #[derive(Clone)]
@@ -26,27 +47,62 @@ impl ActionCallback {
     }
 }
 
-pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
+#[derive(Clone)]
+pub struct LoadOlderChat(Arc<dyn Fn() + Send + Sync>);
+
+impl LoadOlderChat {
+    pub fn call(&self) {
+        (self.0)()
+    }
+}
+
+/// Fetches a page of the room's chat and puts it in front of `chat`. History the
+/// events themselves did not bring, after a snapshot sync or with "load older".
+fn fetch_chat(
+    url: String,
+    set_chat: WriteSignal<ChatLog>,
+    set_chat_before: WriteSignal<Option<u64>>,
+) {
+    spawn_local(async move {
+        let page = match Request::get(&url).send().await {
+            Ok(resp) if resp.ok() => resp.json::<ChatPage>().await,
+            Ok(resp) => {
+                leptos::logging::warn!("Chat history: server returned {}", resp.status());
+                return;
+            }
+            Err(e) => Err(e),
+        };
+        match page {
+            Ok(page) => {
+                set_chat.update(|chat| chat.prepend(&page.messages));
+                set_chat_before.set(page.before);
+            }
+            Err(e) => leptos::logging::warn!("Chat history unavailable: {:?}", e),
+        }
+    });
+}
+
+pub fn provide_game_context(
+    room_id: String,
+    player_id: String,
+    credentials: Credentials,
+) -> GameContext {
     // Start empty, let Join actions populate players
-    let initial_state = GameLogic::new_game(vec![], 12345);
-    let (state, set_state) = signal(initial_state.clone());
+    let (state, set_state) = signal(initial_state());
 
     // Channel for sending messages to WebSocket
     let (tx, mut rx) = mpsc::channel::<String>(100);
 
-    // Internal State for Rollback Logic
-    struct InternalState {
-        verified_state: GameState,
-        pending_events: VecDeque<PlayerEvent>,
-    }
-
-    let internal = Arc::new(Mutex::new(InternalState {
-        verified_state: initial_state.clone(),
-        pending_events: VecDeque::new(),
-    }));
+    // Verified state plus our unconfirmed events, for rollback
+    let internal = Arc::new(Mutex::new(Replica::new()));
 
     // Connection Status Signal
     let (is_connected, set_connected) = signal(false);
+    let (join_error, set_join_error) = signal(None::<String>);
+    let (system_message, set_system_message) = signal(None::<String>);
+    let (online, set_online) = signal(BTreeSet::<String>::new());
+    let (chat, set_chat) = signal(ChatLog::default());
+    let (chat_before, set_chat_before) = signal(None::<u64>);
 
     // Spawn WebSocket Task
     let internal_ws = internal.clone();
@@ -54,6 +110,7 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
     let rid_ws = room_id.clone();
     let set_state_ws = set_state;
     let mut tx_inner = tx.clone(); // Clone for internal use
+    let credentials_ws = credentials.clone();
 
     spawn_local(async move {
         let location = web_sys::window().unwrap().location();
@@ -78,15 +135,40 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
 
         set_connected.set(true);
 
+        // The server answers before it reads the Join, so there is no need to wait
+        let hello_msg = ClientMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: vec![
+                Capability::Resume,
+                Capability::Presence,
+                Capability::Delta,
+                Capability::MessagePack,
+            ],
+        };
+        let _ = write
+            .send(Message::Text(serde_json::to_string(&hello_msg).unwrap()))
+            .await;
+
         // Send Join (Network Room)
         let join_msg = ClientMessage::Join {
             room_id: rid_ws.clone(),
             player_id: pid_ws.clone(),
+            invite_token: credentials_ws.invite_token.clone(),
+            password: credentials_ws.password.clone(),
+            session_token: local_storage()
+                .and_then(|s| s.get_item(&session_key(&rid_ws, &pid_ws)).ok().flatten()),
         };
         let _ = write
             .send(Message::Text(serde_json::to_string(&join_msg).unwrap()))
             .await;
 
+        // Catch up on the room's history (snapshot or missed events) from the server
+        let last_sequence_id = internal_ws.lock().unwrap().last_sequence_id();
+        let resume_msg = ClientMessage::Resume { last_sequence_id };
+        let _ = write
+            .send(Message::Text(serde_json::to_string(&resume_msg).unwrap()))
+            .await;
+
         // Send Join Action (Game State)
         let join_action = PlayerEvent {
             id: Uuid::new_v4(),
@@ -104,14 +186,6 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
             .send(Message::Text(serde_json::to_string(&event_msg).unwrap()))
             .await;
 
-        // Request Sync from peers
-        let sync_req = ClientMessage::SyncRequest {
-            requestor_id: pid_ws.clone(),
-        };
-        let _ = write
-            .send(Message::Text(serde_json::to_string(&sync_req).unwrap()))
-            .await;
-
         loop {
             futures::select! {
                 // Outgoing (from Client UI)
@@ -122,101 +196,136 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
                 },
                 // Incoming (from Server)
                 msg = read.next() => {
-                    match msg {
+                    // MessagePack once negotiated, JSON before that
+                    let parsed = match msg {
                         Some(Ok(Message::Text(text))) => {
-                             match serde_json::from_str::<ServerMessage>(&text) {
-                                Ok(ServerMessage::Event { sequence_id, data }) => {
-                                    let mut guard = internal_ws.lock().unwrap();
-
-                                    if let Ok(event) = serde_json::from_value::<PlayerEvent>(data) {
-                                        leptos::logging::log!("Recv Seq: {}", sequence_id);
-
-                                        // 1. Apply to Verified
-                                        let res = GameLogic::apply_action(
-                                            guard.verified_state.clone(),
-                                            &event.player_id,
-                                            event.action.clone(),
-                                            None
-                                        );
-
-                                        match res {
-                                            Ok(new_state) => {
-                                                guard.verified_state = new_state;
-
-                                                // 2. Prune Pending (Match UUID)
-                                                if let Some(front) = guard.pending_events.front()
-                                                    && front.id == event.id {
-                                                        guard.pending_events.pop_front();
-                                                    }
-
-                                                // 3. Replay Pending
-                                                let mut predicted = guard.verified_state.clone();
-                                                let mut valid_pending = VecDeque::new();
-
-                                                for p in guard.pending_events.iter() {
-                                                    if let Ok(next) = GameLogic::apply_action(
-                                                        predicted.clone(),
-                                                        &p.player_id,
-                                                        p.action.clone(),
-                                                        None
-                                                    ) {
-                                                        predicted = next;
-                                                        valid_pending.push_back(p.clone());
-                                                    } else {
-                                                        leptos::logging::warn!("Replay invalid: {:?}", p.action);
-                                                    }
-                                                }
-                                                guard.pending_events = valid_pending;
-                                                set_state_ws.set(predicted);
-                                            }
-                                            Err(e) => {
-                                                leptos::logging::error!("Sync Error: {:?}", e);
-                                            }
-                                        }
+                            serde_json::from_str::<ServerMessage>(&text).map_err(|e| e.to_string())
+                        }
+                        Some(Ok(Message::Bytes(bytes))) => {
+                            msgpack::from_slice::<ServerMessage>(&bytes).map_err(|e| e.to_string())
+                        }
+                        None => {
+                            leptos::logging::warn!("WS Closed");
+                            set_connected.set(false);
+                            break;
+                        }
+                        Some(Err(e)) => {
+                            leptos::logging::error!("WS Error: {:?}", e);
+                            set_connected.set(false);
+                            break;
+                        }
+                    };
+                    match parsed {
+                        Ok(ServerMessage::Event { sequence_id, timestamp, data }) => {
+                            let mut guard = internal_ws.lock().unwrap();
+
+                            if let Ok(event) = serde_json::from_value::<PlayerEvent>(data) {
+                                leptos::logging::log!("Recv Seq: {}", sequence_id);
+                                match guard.apply_event(sequence_id, &event) {
+                                    Ok(()) => {
+                                        set_chat.update(|chat| {
+                                            chat.record(sequence_id, timestamp, &guard.verified().chat);
+                                        });
+                                        set_state_ws.set(guard.predict());
                                     }
-                                }
-                                Ok(ServerMessage::Welcome { room_id: _ }) => {
-                                    // leptos::logging::log!("Welcome to {}", room_id);
-                                }
-                                Ok(ServerMessage::SyncRequest { requestor_id }) => {
-                                    if requestor_id != pid_ws {
-                                        let guard = internal_ws.lock().unwrap();
-                                        if guard.verified_state.sequence_id > 0 {
-                                            // leptos::logging::log!("Providing Sync State");
-                                            let sync_action = PlayerEvent {
-                                                id: Uuid::new_v4(),
-                                                player_id: pid_ws.clone(),
-                                                action: Action::Meta(MetaAction::FullSync {
-                                                    state_json: serde_json::to_string(&guard.verified_state).unwrap()
-                                                }),
-                                            };
-                                            let msg = ClientMessage::Event {
-                                                sequence_id: guard.verified_state.sequence_id,
-                                                data: serde_json::to_value(&sync_action).unwrap(),
-                                            };
-                                            let _ = tx_inner.try_send(serde_json::to_string(&msg).unwrap());
-                                        }
+                                    Err(e) => {
+                                        leptos::logging::error!("Sync Error: {:?}", e);
                                     }
                                 }
-                                Ok(ServerMessage::Error { msg }) => {
-                                    leptos::logging::error!("Server Error: {:?}", msg);
+                            }
+                        }
+                        Ok(ServerMessage::Sync { sequence_id, delta }) => {
+                            let mut guard = internal_ws.lock().unwrap();
+                            match guard.apply_sync(sequence_id, &delta) {
+                                Ok(()) => {
+                                    set_state_ws.set(guard.predict());
+                                    // Snapshots carry no chat history
+                                    fetch_chat(chat_url(&rid_ws, &credentials_ws, None), set_chat, set_chat_before);
                                 }
                                 Err(e) => {
-                                    leptos::logging::error!("Parse error: {:?}", e);
+                                    leptos::logging::error!("Delta failed, asking for full state: {:?}", e);
+                                    let msg = ClientMessage::SyncRequest { requestor_id: pid_ws.clone() };
+                                    let _ = tx_inner.try_send(serde_json::to_string(&msg).unwrap());
                                 }
-                             }
+                            }
                         }
-                        None => {
-                            leptos::logging::warn!("WS Closed");
+                        Ok(ServerMessage::Snapshot { sequence_id, hash }) => {
+                            internal_ws.lock().unwrap().keep_snapshot(sequence_id, hash);
+                        }
+                        Ok(ServerMessage::Hello { protocol_version, capabilities }) => {
+                            leptos::logging::log!("Protocol {} with {:?}", protocol_version, capabilities);
+                        }
+                        Ok(ServerMessage::HelloRejected { reason }) => {
+                            leptos::logging::error!("Handshake rejected: {:?}", reason);
+                            set_join_error.set(Some(reason.describe()));
                             set_connected.set(false);
                             break;
                         }
-                        Some(Err(e)) => {
-                            leptos::logging::error!("WS Error: {:?}", e);
+                        Ok(ServerMessage::Welcome { room_id, player_id, session_token }) => {
+                            // The server follows up with everyone who is online
+                            set_online.set(BTreeSet::new());
+                            // Kept across refreshes so this tab can reclaim the seat
+                            if let Some(storage) = local_storage() {
+                                let _ = storage.set_item(&session_key(&room_id, &player_id), &session_token);
+                            }
+                        }
+                        Ok(ServerMessage::SyncRequest { requestor_id }) => {
+                            if requestor_id != pid_ws {
+                                let guard = internal_ws.lock().unwrap();
+                                if guard.verified().sequence_id > 0 {
+                                    // leptos::logging::log!("Providing Sync State");
+                                    let sync_action = PlayerEvent {
+                                        id: Uuid::new_v4(),
+                                        player_id: pid_ws.clone(),
+                                        action: Action::Meta(MetaAction::FullSync {
+                                            state_json: serde_json::to_string(guard.verified()).unwrap()
+                                        }),
+                                    };
+                                    let msg = ClientMessage::Event {
+                                        sequence_id: guard.verified().sequence_id,
+                                        data: serde_json::to_value(&sync_action).unwrap(),
+                                    };
+                                    let _ = tx_inner.try_send(serde_json::to_string(&msg).unwrap());
+                                }
+                            }
+                        }
+                        Ok(ServerMessage::Error { msg }) => {
+                            leptos::logging::error!("Server Error: {:?}", msg);
+                        }
+                        Ok(ServerMessage::Rejected { event_id, reason }) => {
+                            leptos::logging::warn!("Event rejected: {:?}", reason);
+                            let mut guard = internal_ws.lock().unwrap();
+                            if guard.reject(event_id) {
+                                set_state_ws.set(guard.predict());
+                            }
+                        }
+                        Ok(ServerMessage::JoinRejected { room_id, reason }) => {
+                            leptos::logging::error!("Join to {} rejected: {:?}", room_id, reason);
+                            set_join_error.set(Some(reason.describe().to_owned()));
+                            set_connected.set(false);
+                            break;
+                        }
+                        Ok(ServerMessage::Kicked { reason }) => {
+                            leptos::logging::warn!("Disconnected by server: {:?}", reason);
+                            set_join_error.set(Some(reason.describe().to_owned()));
                             set_connected.set(false);
                             break;
                         }
-                        _ => {}
+                        Ok(ServerMessage::System { message }) => {
+                            set_system_message.set(Some(message));
+                        }
+                        Ok(ServerMessage::PresenceChanged { player_id, connected }) => {
+                            set_online.update(|online| {
+                                if connected {
+                                    online.insert(player_id);
+                                } else {
+                                    online.remove(&player_id);
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            leptos::logging::error!("Parse error: {:?}", e);
+                        }
                     }
                 }
             }
@@ -229,31 +338,11 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
     let tx_cell = Arc::new(Mutex::new(tx)); // Clone tx for action callback
 
     let perform_action = ActionCallback(Arc::new(move |action: Action| {
-        let mut guard = internal_action.lock().unwrap();
-
-        // 1. Optimistic Apply to CURRENT Predicted State
-        let mut current_predicted = guard.verified_state.clone();
-        for p in &guard.pending_events {
-            current_predicted =
-                GameLogic::apply_action(current_predicted, &p.player_id, p.action.clone(), None)
-                    .unwrap();
-        }
-
-        // Now try new action
-        match GameLogic::apply_action(current_predicted.clone(), &pid_action, action.clone(), None)
-        {
-            Ok(new_predicted) => {
-                // Success
+        // Optimistic apply on top of the current prediction
+        match internal_action.lock().unwrap().perform(&pid_action, action) {
+            Ok((event, new_predicted)) => {
                 set_state.set(new_predicted);
 
-                let event = PlayerEvent {
-                    id: Uuid::new_v4(),
-                    player_id: pid_action.clone(),
-                    action: action.clone(),
-                };
-
-                guard.pending_events.push_back(event.clone());
-
                 let msg = ClientMessage::Event {
                     sequence_id: 0,
                     data: serde_json::to_value(&event).unwrap(),
@@ -270,10 +359,26 @@ pub fn provide_game_context(room_id: String, player_id: String) -> GameContext {
         }
     }));
 
+    let load_older_chat = LoadOlderChat(Arc::new(move || {
+        if let Some(before) = chat_before.get_untracked() {
+            fetch_chat(
+                chat_url(&room_id, &credentials, Some(before)),
+                set_chat,
+                set_chat_before,
+            );
+        }
+    }));
+
     GameContext {
         state,
         player_id,
         perform_action,
         is_connected,
+        join_error,
+        system_message,
+        online,
+        chat,
+        has_older_chat: Signal::derive(move || chat_before.get().is_some()),
+        load_older_chat,
     }
 }
@@ -1,19 +1,45 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::Serialize;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "type", content = "payload")]
-pub enum ClientMessage {
-    Join { room_id: String, player_id: String },
-    Event { sequence_id: u64, data: Value },
-    SyncRequest { requestor_id: String },
+pub use sint_protocol::{
+    Capability, ClientMessage, PROTOCOL_VERSION, ServerMessage, initial_state, msgpack,
+};
+
+/// Credentials for a private room. Both empty for public rooms.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub invite_token: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Body of `POST /api/rooms/{room_id}/bots`; the server picks the strategy and budget.
+/// Only the room's invite token (its owner's credential) may add bots.
+#[derive(Debug, Clone, Serialize)]
+pub struct AddBots {
+    pub invite_token: Option<String>,
+}
+
+/// `GET /api/rooms/{room_id}/chat`: the newest page, or the one before `before`.
+pub fn chat_url(room_id: &str, credentials: &Credentials, before: Option<u64>) -> String {
+    let encode = |s: &str| String::from(js_sys::encode_uri_component(s));
+    let mut url = format!("/api/rooms/{}/chat?limit=100", encode(room_id));
+    if let Some(before) = before {
+        url.push_str(&format!("&before={}", before));
+    }
+    if let Some(token) = &credentials.invite_token {
+        url.push_str(&format!("&invite_token={}", encode(token)));
+    }
+    if let Some(password) = &credentials.password {
+        url.push_str(&format!("&password={}", encode(password)));
+    }
+    url
+}
+
+/// Session storage key for a room's password, set by the lobby before joining.
+pub fn password_key(room_id: &str) -> String {
+    format!("sint_password_{}", room_id)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "type", content = "payload")]
-pub enum ServerMessage {
-    Welcome { room_id: String },
-    Event { sequence_id: u64, data: Value },
-    SyncRequest { requestor_id: String },
-    Error { msg: String },
+/// Local storage key for the session token that holds a player's seat in a room.
+pub fn session_key(room_id: &str, player_id: &str) -> String {
+    format!("sint_session_{}_{}", room_id, player_id)
 }
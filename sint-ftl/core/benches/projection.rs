@@ -0,0 +1,116 @@
+//! Cost of validating actions against a queued plan, with and without the
+//! projection cache. Run with `cargo bench -p sint-core --bench projection`.
+
+use sint_core::{
+    GameLogic,
+    logic::actions::{clear_projection_cache, get_valid_actions},
+    types::*,
+};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 2000;
+
+/// A planning state with a few actions already queued, like the solver sees mid-round.
+fn planning_state() -> GameState {
+    let players: Vec<String> = (1..=4).map(|i| format!("P{}", i)).collect();
+    let mut state = GameLogic::new_game(players, 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    for p in state.players.values_mut() {
+        p.ap = 6;
+    }
+
+    for pid in ["P1", "P2", "P3"] {
+        for to_room in [0, 9, 0] {
+            state = GameLogic::apply_action(
+                state,
+                pid,
+                Action::Game(GameAction::move_to(to_room)),
+                None,
+            )
+            .expect("Setup move should be valid");
+        }
+    }
+    state
+}
+
+/// Mirrors a solver expansion: list the actions, then try each of them.
+fn expand(state: &GameState, cold: bool) -> usize {
+    if cold {
+        clear_projection_cache();
+    }
+    let actions = get_valid_actions(state, "P4");
+    let mut applied = 0;
+    for action in actions {
+        if cold {
+            clear_projection_cache();
+        }
+        if GameLogic::apply_action(state.clone(), "P4", action, None).is_ok() {
+            applied += 1;
+        }
+    }
+    applied
+}
+
+/// Mirrors a search descending one line: queue an action, then list the actions
+/// of the resulting state, until P4 runs out of AP.
+fn descend(state: &GameState, cold: bool) -> usize {
+    let mut state = state.clone();
+    let mut depth = 0;
+    loop {
+        if cold {
+            clear_projection_cache();
+        }
+        let Some(action) = get_valid_actions(&state, "P4")
+            .into_iter()
+            .find(|a| matches!(a, Action::Game(GameAction::Move { .. })))
+        else {
+            return depth;
+        };
+        if cold {
+            clear_projection_cache();
+        }
+        match GameLogic::apply_action(state.clone(), "P4", action, None) {
+            Ok(next) => state = next,
+            Err(_) => return depth,
+        }
+        depth += 1;
+    }
+}
+
+fn measure(name: &str, mut run: impl FnMut(bool) -> usize) {
+    let mut uncached = Duration::ZERO;
+    for (label, cold) in [("uncached", true), ("cached", false)] {
+        let start = Instant::now();
+        let mut work = 0;
+        for _ in 0..ITERATIONS {
+            work += run(cold);
+        }
+        let per_run = start.elapsed() / ITERATIONS;
+        if cold {
+            uncached = per_run;
+            println!(
+                "{:>10} {:>8}: {:?} ({} each)",
+                name,
+                label,
+                per_run,
+                work / ITERATIONS as usize
+            );
+        } else {
+            println!(
+                "{:>10} {:>8}: {:?} ({:.1}x)",
+                name,
+                label,
+                per_run,
+                uncached.as_secs_f64() / per_run.as_secs_f64()
+            );
+        }
+    }
+}
+
+fn main() {
+    let state = planning_state();
+    println!("Queue length: {}", state.proposal_queue.len());
+
+    measure("expand", |cold| expand(&state, cold));
+    measure("descend", |cold| descend(&state, cold));
+}
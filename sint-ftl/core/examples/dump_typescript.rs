@@ -0,0 +1,5 @@
+use sint_core::export_typescript;
+
+fn main() {
+    print!("{}", export_typescript());
+}
@@ -1,9 +1,17 @@
 #[cfg(feature = "python")]
-use crate::{logic::GameLogic, types::*};
+use crate::logic::GameLogic;
+#[cfg(any(feature = "python", test))]
+use crate::types::*;
+#[cfg(feature = "python")]
+use pyo3::IntoPyObjectExt;
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 #[cfg(feature = "python")]
+use pyo3::types::{PyBool, PyList, PyTuple};
+#[cfg(feature = "python")]
 use pythonize::{depythonize, pythonize};
+#[cfg(feature = "python")]
+use std::collections::BTreeMap;
 
 #[cfg(feature = "python")]
 #[pyfunction]
@@ -67,6 +75,452 @@ fn get_valid_actions(
     Ok(py_actions)
 }
 
+#[cfg(feature = "python")]
+#[pyfunction]
+fn explain_action(
+    py: Python,
+    state_dict: &Bound<'_, PyAny>,
+    player_id: String,
+    action_dict: &Bound<'_, PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let state: GameState = depythonize(state_dict)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    let action: GameAction = depythonize(action_dict)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+    let explanation = GameLogic::explain_action(&state, &player_id, &action);
+    let py_explanation = pythonize(py, &explanation)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(py_explanation.into())
+}
+
+#[cfg(feature = "python")]
+#[pyfunction]
+fn forecast(py: Python, state_dict: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+    let state: GameState = depythonize(state_dict)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+    let forecast = GameLogic::forecast(&state);
+    let py_forecast = pythonize(py, &forecast)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(py_forecast.into())
+}
+
+#[cfg(feature = "python")]
+fn value_error(msg: impl ToString) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(msg.to_string())
+}
+
+/// Parses a unit enum (`ItemType`, `MapLayout`, ...) from its variant name.
+#[cfg(any(feature = "python", test))]
+fn parse_variant<T: serde::de::DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_owned())).map_err(|e| e.to_string())
+}
+
+#[cfg(any(feature = "python", test))]
+fn variant_name<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(any(feature = "python", test))]
+fn variant_tag(value: serde_json::Result<serde_json::Value>) -> String {
+    value
+        .ok()
+        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// An argument of a compact action tuple, after the action name.
+#[cfg(any(feature = "python", test))]
+#[derive(Debug, Clone, PartialEq)]
+enum ActionArg {
+    Int(i64),
+    Str(String),
+    Bool(bool),
+}
+
+#[cfg(any(feature = "python", test))]
+impl ActionArg {
+    fn int<T: TryFrom<i64>>(&self) -> Result<T, String> {
+        match self {
+            ActionArg::Int(i) => T::try_from(*i).map_err(|_| format!("{} is out of range", i)),
+            other => Err(format!("Expected an integer, got {:?}", other)),
+        }
+    }
+
+    fn str(&self) -> Result<String, String> {
+        match self {
+            ActionArg::Str(s) => Ok(s.clone()),
+            other => Err(format!("Expected a string, got {:?}", other)),
+        }
+    }
+
+    fn bool(&self) -> Result<bool, String> {
+        match self {
+            ActionArg::Bool(b) => Ok(*b),
+            other => Err(format!("Expected a bool, got {:?}", other)),
+        }
+    }
+}
+
+/// Builds an `Action` from a compact tuple's name and arguments. Arguments follow
+/// the payload field order.
+#[cfg(any(feature = "python", test))]
+fn action_from_parts(name: &str, args: &[ActionArg]) -> Result<Action, String> {
+    let arg = |i: usize| {
+        args.get(i)
+            .ok_or_else(|| format!("{} expects more arguments", name))
+    };
+
+    let action = match name {
+        "Move" => GameAction::Move {
+            to_room: arg(0)?.int()?,
+            mode: match args.get(1) {
+                Some(mode) => parse_variant(&mode.str()?)?,
+                None => RouteMode::Shortest,
+            },
+            force: match args.get(2) {
+                Some(force) => force.bool()?,
+                None => false,
+            },
+        },
+        "Bake" => GameAction::Bake,
+        "Shoot" => GameAction::Shoot,
+        "RaiseShields" => GameAction::RaiseShields,
+        "EvasiveManeuvers" => GameAction::EvasiveManeuvers,
+        "Interact" => GameAction::Interact,
+        "Extinguish" => GameAction::Extinguish,
+        "Repair" => GameAction::Repair,
+        "Throw" => GameAction::Throw {
+            target_player: arg(0)?.str()?,
+            item_index: arg(1)?.int()?,
+        },
+        "PickUp" => GameAction::PickUp {
+            item_type: parse_variant(&arg(0)?.str()?)?,
+        },
+        "Drop" => GameAction::Drop {
+            item_index: arg(0)?.int()?,
+        },
+        "Revive" => GameAction::Revive {
+            target_player: arg(0)?.str()?,
+        },
+        "Lookout" => GameAction::Lookout,
+        "FirstAid" => GameAction::FirstAid {
+            target_player: arg(0)?.str()?,
+        },
+        "Chat" => GameAction::Chat {
+            message: arg(0)?.str()?,
+        },
+        "VoteReady" => GameAction::VoteReady {
+            ready: arg(0)?.bool()?,
+        },
+        "Pass" => GameAction::Pass,
+        "Undo" => GameAction::Undo {
+            action_id: arg(0)?.str()?.parse().map_err(|e| format!("{}", e))?,
+        },
+        "Join" => {
+            return Ok(Action::Meta(MetaAction::Join {
+                name: arg(0)?.str()?,
+            }));
+        }
+        "SetName" => {
+            return Ok(Action::Meta(MetaAction::SetName {
+                name: arg(0)?.str()?,
+            }));
+        }
+        "SetMapLayout" => {
+            return Ok(Action::Meta(MetaAction::SetMapLayout {
+                layout: parse_variant(&arg(0)?.str()?)?,
+            }));
+        }
+        "FullSync" => {
+            return Ok(Action::Meta(MetaAction::FullSync {
+                state_json: arg(0)?.str()?,
+            }));
+        }
+        _ => return Err(format!("Unknown action: {}", name)),
+    };
+    Ok(Action::Game(action))
+}
+
+/// The inverse of `action_from_parts`.
+#[cfg(any(feature = "python", test))]
+fn action_to_parts(action: &Action) -> (String, Vec<ActionArg>) {
+    use ActionArg::{Bool, Int, Str};
+
+    let name = match action {
+        Action::Game(a) => variant_tag(serde_json::to_value(a)),
+        Action::Meta(a) => variant_tag(serde_json::to_value(a)),
+    };
+    let args = match action {
+        // Route options only when they differ from the defaults
+        Action::Game(GameAction::Move {
+            to_room,
+            mode,
+            force,
+        }) => {
+            let mut args = vec![Int(*to_room as i64)];
+            if *mode != RouteMode::Shortest || *force {
+                args.push(Str(variant_name(mode)));
+            }
+            if *force {
+                args.push(Bool(true));
+            }
+            args
+        }
+        Action::Game(GameAction::Throw {
+            target_player,
+            item_index,
+        }) => vec![Str(target_player.clone()), Int(*item_index as i64)],
+        Action::Game(GameAction::PickUp { item_type }) => vec![Str(variant_name(item_type))],
+        Action::Game(GameAction::Drop { item_index }) => vec![Int(*item_index as i64)],
+        Action::Game(GameAction::Revive { target_player })
+        | Action::Game(GameAction::FirstAid { target_player }) => {
+            vec![Str(target_player.clone())]
+        }
+        Action::Game(GameAction::Chat { message }) => vec![Str(message.clone())],
+        Action::Game(GameAction::VoteReady { ready }) => vec![Bool(*ready)],
+        Action::Game(GameAction::Undo { action_id }) => vec![Str(action_id.to_string())],
+        Action::Meta(MetaAction::Join { name } | MetaAction::SetName { name }) => {
+            vec![Str(name.clone())]
+        }
+        Action::Meta(MetaAction::SetMapLayout { layout }) => vec![Str(variant_name(layout))],
+        Action::Meta(MetaAction::FullSync { state_json }) => vec![Str(state_json.clone())],
+        Action::Game(_) => vec![],
+    };
+    (name, args)
+}
+
+/// Converts a compact action tuple such as `("Move", 3)`, `("PickUp", "Peppernut")`
+/// or `("Throw", "P2", 0)` into an `Action` (see `action_from_parts`). Dicts in the
+/// serde format are accepted as well.
+#[cfg(feature = "python")]
+pub fn action_from_py(obj: &Bound<'_, PyAny>) -> PyResult<Action> {
+    let items: Vec<Bound<'_, PyAny>> = if let Ok(t) = obj.cast::<PyTuple>() {
+        t.iter().collect()
+    } else if let Ok(l) = obj.cast::<PyList>() {
+        l.iter().collect()
+    } else if let Ok(name) = obj.extract::<String>() {
+        vec![name.into_pyobject(obj.py())?.into_any()]
+    } else {
+        return depythonize(obj).map_err(value_error);
+    };
+
+    let Some((name, args)) = items.split_first() else {
+        return Err(value_error("Empty action tuple"));
+    };
+    let name: String = name.extract()?;
+    let args = args
+        .iter()
+        .map(|arg| {
+            if let Ok(b) = arg.cast::<PyBool>() {
+                Ok(ActionArg::Bool(b.is_true()))
+            } else if let Ok(i) = arg.extract::<i64>() {
+                Ok(ActionArg::Int(i))
+            } else {
+                Ok(ActionArg::Str(arg.extract()?))
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    action_from_parts(&name, &args).map_err(value_error)
+}
+
+/// The inverse of `action_from_py`.
+#[cfg(feature = "python")]
+fn action_to_py<'py>(py: Python<'py>, action: &Action) -> PyResult<Bound<'py, PyTuple>> {
+    let (name, args) = action_to_parts(action);
+    let mut items = vec![name.into_bound_py_any(py)?];
+    for arg in args {
+        items.push(match arg {
+            ActionArg::Int(i) => i.into_bound_py_any(py)?,
+            ActionArg::Str(s) => s.into_bound_py_any(py)?,
+            ActionArg::Bool(b) => b.into_bound_py_any(py)?,
+        });
+    }
+    PyTuple::new(py, items)
+}
+
+/// A game whose state stays in Rust. Unlike the dict functions, nothing is converted
+/// to Python objects unless asked for (`to_dict`, `snapshot_json`).
+///
+/// Every extension module that registers `Game` gets its own copy of the class, so a
+/// `sint_core.Game` cannot be passed to `sint_solver` functions; use `sint_solver.Game`
+/// there.
+#[cfg(feature = "python")]
+#[pyclass(module = "sint_core")]
+#[derive(Clone)]
+pub struct Game {
+    state: GameState,
+}
+
+#[cfg(feature = "python")]
+impl Game {
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn set_state(&mut self, state: GameState) {
+        self.state = state;
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Game {
+    #[new]
+    #[pyo3(signature = (player_ids, seed, layout = None))]
+    fn new(player_ids: Vec<String>, seed: u64, layout: Option<String>) -> PyResult<Self> {
+        let layout = match layout {
+            Some(name) => parse_variant(&name).map_err(value_error)?,
+            None => MapLayout::default(),
+        };
+        Ok(Game {
+            state: GameLogic::new_game_with_layout(player_ids, seed, layout),
+        })
+    }
+
+    #[staticmethod]
+    fn from_dict(state_dict: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Game {
+            state: depythonize(state_dict).map_err(value_error)?,
+        })
+    }
+
+    #[staticmethod]
+    fn from_json(state_json: &str) -> PyResult<Self> {
+        Ok(Game {
+            state: serde_json::from_str(state_json).map_err(value_error)?,
+        })
+    }
+
+    /// Applies an action (tuple or dict). On error the state is left unchanged.
+    fn apply(&mut self, player_id: &str, action: &Bound<'_, PyAny>) -> PyResult<()> {
+        let action = action_from_py(action)?;
+        self.state = GameLogic::apply_action(self.state.clone(), player_id, action, None)
+            .map_err(value_error)?;
+        Ok(())
+    }
+
+    /// Valid actions for `player_id` as compact tuples.
+    fn valid_actions<'py>(
+        &self,
+        py: Python<'py>,
+        player_id: &str,
+    ) -> PyResult<Vec<Bound<'py, PyTuple>>> {
+        GameLogic::get_valid_actions(&self.state, player_id)
+            .iter()
+            .map(|a| action_to_py(py, a))
+            .collect()
+    }
+
+    fn action_cost(&self, player_id: &str, action: &Bound<'_, PyAny>) -> PyResult<i32> {
+        match action_from_py(action)? {
+            Action::Game(a) => Ok(crate::logic::actions::action_cost(
+                &self.state,
+                player_id,
+                &a,
+            )),
+            Action::Meta(_) => Ok(0),
+        }
+    }
+
+    fn clone(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __copy__(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        Clone::clone(self)
+    }
+
+    fn snapshot_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.state)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+    }
+
+    /// The state in the dict format used by the module-level functions.
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let py_state = pythonize(py, &self.state)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        Ok(py_state.into())
+    }
+
+    #[getter]
+    fn hull(&self) -> i32 {
+        self.state.hull_integrity
+    }
+
+    #[getter]
+    fn phase(&self) -> String {
+        variant_name(&self.state.phase)
+    }
+
+    #[getter]
+    fn turn(&self) -> u32 {
+        self.state.turn_count
+    }
+
+    #[getter]
+    fn enemy_hp(&self) -> i32 {
+        self.state.enemy.hp
+    }
+
+    #[getter]
+    fn sequence_id(&self) -> u64 {
+        self.state.sequence_id
+    }
+
+    #[getter]
+    fn zobrist(&self) -> u64 {
+        self.state.zobrist()
+    }
+
+    #[getter]
+    fn is_over(&self) -> bool {
+        matches!(self.state.phase, GamePhase::GameOver | GamePhase::Victory)
+    }
+
+    fn player_rooms(&self) -> BTreeMap<String, RoomId> {
+        self.state
+            .players
+            .values()
+            .map(|p| (p.id.clone(), p.room_id))
+            .collect()
+    }
+
+    fn player_ap(&self) -> BTreeMap<String, i32> {
+        self.state
+            .players
+            .values()
+            .map(|p| (p.id.clone(), p.ap))
+            .collect()
+    }
+
+    fn player_hp(&self) -> BTreeMap<String, i32> {
+        self.state
+            .players
+            .values()
+            .map(|p| (p.id.clone(), p.hp))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Game(phase={}, turn={}, hull={}, enemy_hp={})",
+            self.phase(),
+            self.state.turn_count,
+            self.state.hull_integrity,
+            self.state.enemy.hp
+        )
+    }
+}
+
 #[cfg(feature = "python")]
 #[pymodule]
 fn sint_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -74,5 +528,124 @@ fn sint_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(apply_action_with_id, m)?)?;
     m.add_function(wrap_pyfunction!(get_schema_json, m)?)?;
     m.add_function(wrap_pyfunction!(get_valid_actions, m)?)?;
+    m.add_function(wrap_pyfunction!(explain_action, m)?)?;
+    m.add_function(wrap_pyfunction!(forecast, m)?)?;
+    m.add_class::<Game>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ActionArg::{Bool, Int, Str};
+
+    fn every_action() -> Vec<Action> {
+        let game = |a| Action::Game(a);
+        vec![
+            game(GameAction::move_to(3)),
+            game(GameAction::Move {
+                to_room: 4,
+                mode: RouteMode::Safest,
+                force: false,
+            }),
+            game(GameAction::Move {
+                to_room: 5,
+                mode: RouteMode::Shortest,
+                force: true,
+            }),
+            game(GameAction::Bake),
+            game(GameAction::Shoot),
+            game(GameAction::RaiseShields),
+            game(GameAction::EvasiveManeuvers),
+            game(GameAction::Interact),
+            game(GameAction::Extinguish),
+            game(GameAction::Repair),
+            game(GameAction::Throw {
+                target_player: "P2".to_owned(),
+                item_index: 1,
+            }),
+            game(GameAction::PickUp {
+                item_type: ItemType::Peppernut,
+            }),
+            game(GameAction::Drop { item_index: 0 }),
+            game(GameAction::Revive {
+                target_player: "P3".to_owned(),
+            }),
+            game(GameAction::Lookout),
+            game(GameAction::FirstAid {
+                target_player: "P1".to_owned(),
+            }),
+            game(GameAction::Chat {
+                message: "hi".to_owned(),
+            }),
+            game(GameAction::VoteReady { ready: false }),
+            game(GameAction::Pass),
+            game(GameAction::Undo {
+                action_id: uuid::Uuid::from_u128(7),
+            }),
+            Action::Meta(MetaAction::Join {
+                name: "Alice".to_owned(),
+            }),
+            Action::Meta(MetaAction::SetName {
+                name: "Bob".to_owned(),
+            }),
+            Action::Meta(MetaAction::SetMapLayout {
+                layout: MapLayout::Torus,
+            }),
+            Action::Meta(MetaAction::FullSync {
+                state_json: "{}".to_owned(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_action_parts_round_trip() {
+        for action in every_action() {
+            let (name, args) = action_to_parts(&action);
+            assert_eq!(action_from_parts(&name, &args), Ok(action));
+        }
+    }
+
+    #[test]
+    fn test_action_parts_follow_payload_order() {
+        assert_eq!(
+            action_from_parts("Throw", &[Str("P2".to_owned()), Int(0)]),
+            Ok(Action::Game(GameAction::Throw {
+                target_player: "P2".to_owned(),
+                item_index: 0,
+            }))
+        );
+        assert_eq!(
+            action_to_parts(&Action::Game(GameAction::PickUp {
+                item_type: ItemType::Extinguisher,
+            })),
+            ("PickUp".to_owned(), vec![Str("Extinguisher".to_owned())])
+        );
+        assert_eq!(
+            action_to_parts(&Action::Game(GameAction::VoteReady { ready: true })),
+            ("VoteReady".to_owned(), vec![Bool(true)])
+        );
+        assert_eq!(
+            action_to_parts(&Action::Game(GameAction::move_to(3))),
+            ("Move".to_owned(), vec![Int(3)])
+        );
+        assert_eq!(
+            action_from_parts("Move", &[Int(3), Str("Safest".to_owned()), Bool(true)]),
+            Ok(Action::Game(GameAction::Move {
+                to_room: 3,
+                mode: RouteMode::Safest,
+                force: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_bad_action_parts_are_rejected() {
+        assert!(action_from_parts("Fly", &[]).is_err());
+        assert!(action_from_parts("Move", &[]).is_err());
+        assert!(action_from_parts("Move", &[Int(-1)]).is_err());
+        assert!(action_from_parts("Move", &[Str("3".to_owned())]).is_err());
+        assert!(action_from_parts("PickUp", &[Str("Banana".to_owned())]).is_err());
+        assert!(action_from_parts("Undo", &[Str("not-a-uuid".to_owned())]).is_err());
+    }
+}
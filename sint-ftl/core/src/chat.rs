@@ -0,0 +1,134 @@
+//! Chat history, kept beside the game rather than in `GameState`.
+//!
+//! Applying an action leaves the messages it produced in `GameState::chat`. After
+//! applying a sequenced event, each peer records them in its `ChatLog` under the
+//! event's sequence number and server timestamp, so peers that apply the same
+//! events build the same log. Pages are addressed by sequence number, which lets a
+//! page fetched from the server line up with what a peer recorded itself.
+
+use crate::types::{ChatChannel, ChatMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ChatEntry {
+    /// The event that produced the message.
+    pub sequence_id: u64,
+    #[serde(flatten)]
+    pub message: ChatMessage,
+}
+
+/// A slice of a `ChatLog`, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatPage {
+    pub messages: Vec<ChatEntry>,
+    /// `before` for the next older page; `None` at the start of the log.
+    pub before: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatLog {
+    entries: Vec<ChatEntry>,
+}
+
+impl ChatLog {
+    /// A log holding `entries`, which must be in sequence order (as written to disk).
+    pub fn from_entries(entries: Vec<ChatEntry>) -> Self {
+        ChatLog { entries }
+    }
+
+    pub fn entries(&self) -> &[ChatEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sequence number of the newest entry, or 0.
+    pub fn last_sequence_id(&self) -> u64 {
+        self.entries.last().map_or(0, |e| e.sequence_id)
+    }
+
+    /// Records the chat of event `sequence_id` (`GameState::chat` right after
+    /// applying it), stamped with the event's `timestamp`. Events at or before the
+    /// newest entry are skipped, so replaying events is harmless. Returns the new
+    /// entries.
+    pub fn record(
+        &mut self,
+        sequence_id: u64,
+        timestamp: u64,
+        messages: &[ChatMessage],
+    ) -> &[ChatEntry] {
+        let start = self.entries.len();
+        if sequence_id > self.last_sequence_id() {
+            self.entries.extend(messages.iter().map(|m| ChatEntry {
+                sequence_id,
+                message: ChatMessage {
+                    timestamp,
+                    ..m.clone()
+                },
+            }));
+        }
+        &self.entries[start..]
+    }
+
+    /// Drops the oldest entries beyond `keep`. 0 keeps everything.
+    pub fn trim(&mut self, keep: usize) {
+        if keep > 0 && self.entries.len() > keep {
+            self.entries.drain(..self.entries.len() - keep);
+        }
+    }
+
+    /// Puts older history (a `page` from another peer) in front of the log, skipping
+    /// entries for events this log already covers.
+    pub fn prepend(&mut self, older: &[ChatEntry]) {
+        let first = self.entries.first().map_or(u64::MAX, |e| e.sequence_id);
+        let older: Vec<_> = older
+            .iter()
+            .filter(|e| e.sequence_id < first)
+            .cloned()
+            .collect();
+        self.entries.splice(0..0, older);
+    }
+
+    /// The newest `limit` messages from events before `before` (or from the end),
+    /// only those on `channel` if one is given. A page never splits the messages of
+    /// one event, so it can run over `limit`.
+    pub fn page(
+        &self,
+        channel: Option<ChatChannel>,
+        before: Option<u64>,
+        limit: usize,
+    ) -> ChatPage {
+        let end = before.map_or(self.entries.len(), |before| {
+            self.entries.partition_point(|e| e.sequence_id < before)
+        });
+        let mut rest = self.entries[..end]
+            .iter()
+            .rev()
+            .filter(|e| channel.is_none_or(|c| e.message.channel == c))
+            .peekable();
+        let mut messages: Vec<ChatEntry> = vec![];
+        while let Some(entry) = rest.next() {
+            messages.push(entry.clone());
+            if messages.len() >= limit
+                && rest
+                    .peek()
+                    .is_none_or(|next| next.sequence_id != entry.sequence_id)
+            {
+                break;
+            }
+        }
+        let before = rest
+            .peek()
+            .and(messages.last())
+            .map(|oldest| oldest.sequence_id);
+        messages.reverse();
+        ChatPage { messages, before }
+    }
+}
@@ -0,0 +1,209 @@
+//! Structural diffs between game states, so a peer that holds one state can be
+//! brought to another without shipping the whole thing.
+//!
+//! Deltas work on the JSON form of the state, as a list of operations addressed by
+//! JSON Pointer paths (RFC 6901), much like JSON Patch. An array that only grew,
+//! such as the proposal queue, becomes a single `Append`. Every delta names the snapshot
+//! hash of the state it applies to and of the state it produces, so a peer holding
+//! a different state rejects it instead of drifting apart.
+
+use crate::logic::GameError;
+use crate::types::GameState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PatchOp {
+    /// Replaces the value at `path`, adding it if it is a missing object member.
+    Set { path: String, value: Value },
+    /// Removes an object member.
+    Remove { path: String },
+    /// Pushes values onto the end of an array.
+    Append { path: String, values: Vec<Value> },
+    /// Shortens an array to `len` elements.
+    Truncate { path: String, len: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateDelta {
+    /// `snapshot_hash` of the state the delta applies to.
+    pub base: u64,
+    /// `snapshot_hash` of the result.
+    pub target: u64,
+    pub ops: Vec<PatchOp>,
+}
+
+impl StateDelta {
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl GameState {
+    /// Hash of the whole state, unlike `zobrist` including the sequence id and
+    /// `chat`. Two peers with the same hash hold the same snapshot.
+    pub fn snapshot_hash(&self) -> u64 {
+        let json = serde_json::to_vec(&to_json(self)).unwrap();
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in json {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    /// The changes that turn this state into `target`.
+    pub fn diff(&self, target: &GameState) -> StateDelta {
+        let mut ops = vec![];
+        diff_value(
+            &mut String::new(),
+            &to_json(self),
+            &to_json(target),
+            &mut ops,
+        );
+        StateDelta {
+            base: self.snapshot_hash(),
+            target: target.snapshot_hash(),
+            ops,
+        }
+    }
+
+    /// Applies a delta made by `diff` from a state equal to this one.
+    pub fn apply_delta(&self, delta: &StateDelta) -> Result<GameState, GameError> {
+        let bad_sync = |msg: &str| GameError::InvalidAction(format!("Bad Sync: {}", msg));
+        if self.snapshot_hash() != delta.base {
+            return Err(bad_sync("delta is for a different state"));
+        }
+        let mut doc = to_json(self);
+        for op in &delta.ops {
+            apply_op(&mut doc, op).map_err(|e| bad_sync(&e))?;
+        }
+        let state: GameState = serde_json::from_value(doc).map_err(|e| bad_sync(&e.to_string()))?;
+        if state.snapshot_hash() != delta.target {
+            return Err(bad_sync("result does not match the sender's state"));
+        }
+        Ok(state)
+    }
+}
+
+fn to_json(state: &GameState) -> Value {
+    serde_json::to_value(state).expect("GameState serializes to JSON")
+}
+
+fn diff_value(path: &mut String, from: &Value, to: &Value, ops: &mut Vec<PatchOp>) {
+    if from == to {
+        return;
+    }
+    let len = path.len();
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for key in from.keys().filter(|k| !to.contains_key(*k)) {
+                push_token(path, key);
+                ops.push(PatchOp::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+            for (key, value) in to {
+                push_token(path, key);
+                match from.get(key) {
+                    Some(old) => diff_value(path, old, value, ops),
+                    None => ops.push(PatchOp::Set {
+                        path: path.clone(),
+                        value: value.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            let common = from.len().min(to.len());
+            let changed = (0..common).filter(|&i| from[i] != to[i]).count();
+            // A shifted or reshuffled array (the deck) is cheaper to send whole.
+            if changed > 0 && changed * 2 > common {
+                ops.push(PatchOp::Set {
+                    path: path.clone(),
+                    value: Value::Array(to.clone()),
+                });
+                return;
+            }
+            for i in 0..common {
+                push_token(path, &i.to_string());
+                diff_value(path, &from[i], &to[i], ops);
+                path.truncate(len);
+            }
+            if to.len() > common {
+                ops.push(PatchOp::Append {
+                    path: path.clone(),
+                    values: to[common..].to_vec(),
+                });
+            } else if from.len() > common {
+                ops.push(PatchOp::Truncate {
+                    path: path.clone(),
+                    len: common,
+                });
+            }
+        }
+        _ => ops.push(PatchOp::Set {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    path.push_str(&token.replace('~', "~0").replace('/', "~1"));
+}
+
+fn apply_op(doc: &mut Value, op: &PatchOp) -> Result<(), String> {
+    match op {
+        PatchOp::Set { path, value } if path.is_empty() => {
+            *doc = value.clone();
+        }
+        PatchOp::Set { path, value } => match parent(doc, path)? {
+            (Value::Object(map), key) => {
+                map.insert(key, value.clone());
+            }
+            (Value::Array(items), key) => {
+                let slot = key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| items.get_mut(i))
+                    .ok_or_else(|| format!("no element at {}", path))?;
+                *slot = value.clone();
+            }
+            _ => return Err(format!("cannot set {}", path)),
+        },
+        PatchOp::Remove { path } => match parent(doc, path)? {
+            (Value::Object(map), key) => {
+                map.remove(&key)
+                    .ok_or_else(|| format!("nothing to remove at {}", path))?;
+            }
+            _ => return Err(format!("cannot remove {}", path)),
+        },
+        PatchOp::Append { path, values } => {
+            array_at(doc, path)?.extend(values.iter().cloned());
+        }
+        PatchOp::Truncate { path, len } => {
+            array_at(doc, path)?.truncate(*len);
+        }
+    }
+    Ok(())
+}
+
+/// The container holding `path` and the unescaped last token of `path`.
+fn parent<'a>(doc: &'a mut Value, path: &str) -> Result<(&'a mut Value, String), String> {
+    let (parent, token) = path
+        .rsplit_once('/')
+        .ok_or_else(|| format!("invalid path {}", path))?;
+    let container = doc
+        .pointer_mut(parent)
+        .ok_or_else(|| format!("no value at {}", parent))?;
+    Ok((container, token.replace("~1", "/").replace("~0", "~")))
+}
+
+fn array_at<'a>(doc: &'a mut Value, path: &str) -> Result<&'a mut Vec<Value>, String> {
+    doc.pointer_mut(path)
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| format!("no array at {}", path))
+}
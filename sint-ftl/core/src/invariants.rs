@@ -0,0 +1,227 @@
+//! Structural invariants every reachable `GameState` must satisfy.
+//!
+//! With the `check-invariants` feature, `apply_action` panics as soon as an action
+//! produces a state that breaks one of them.
+
+use crate::logic::MAX_PLAYER_HP;
+use crate::types::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Violation {
+    NegativeAp {
+        player: PlayerId,
+        ap: i32,
+    },
+    HpOutOfRange {
+        player: PlayerId,
+        hp: i32,
+    },
+    /// `Fainted` status does not match `hp <= 0`.
+    FaintedMismatch {
+        player: PlayerId,
+        hp: i32,
+        fainted: bool,
+    },
+    FaintedWithAp {
+        player: PlayerId,
+        ap: i32,
+    },
+    InventoryOverCapacity {
+        player: PlayerId,
+        peppernuts: usize,
+        special_items: usize,
+    },
+    PlayerInMissingRoom {
+        player: PlayerId,
+        room: RoomId,
+    },
+    MissingNeighbor {
+        room: RoomId,
+        neighbor: RoomId,
+    },
+    HullOutOfRange {
+        hull: i32,
+    },
+    /// A card is in more than one place (or twice in one) across deck, discard and active situations.
+    DuplicateCard {
+        card: CardId,
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::NegativeAp { player, ap } => {
+                write!(f, "{} has negative AP ({})", player, ap)
+            }
+            Violation::HpOutOfRange { player, hp } => {
+                write!(f, "{} has {} HP (max {})", player, hp, MAX_PLAYER_HP)
+            }
+            Violation::FaintedMismatch {
+                player,
+                hp,
+                fainted,
+            } => {
+                if *fainted {
+                    write!(f, "{} is Fainted with {} HP", player, hp)
+                } else {
+                    write!(f, "{} has {} HP but is not Fainted", player, hp)
+                }
+            }
+            Violation::FaintedWithAp { player, ap } => {
+                write!(f, "{} is Fainted but has {} AP", player, ap)
+            }
+            Violation::InventoryOverCapacity {
+                player,
+                peppernuts,
+                special_items,
+            } => write!(
+                f,
+                "{} carries too much ({} Peppernuts, {} special items)",
+                player, peppernuts, special_items
+            ),
+            Violation::PlayerInMissingRoom { player, room } => {
+                write!(f, "{} is in room {}, which does not exist", player, room)
+            }
+            Violation::MissingNeighbor { room, neighbor } => {
+                write!(f, "Room {} lists missing neighbor {}", room, neighbor)
+            }
+            Violation::HullOutOfRange { hull } => {
+                write!(f, "Hull is {} (max {})", hull, MAX_HULL)
+            }
+            Violation::DuplicateCard { card, count } => {
+                write!(
+                    f,
+                    "{:?} appears {} times in deck, discard and play",
+                    card, count
+                )
+            }
+        }
+    }
+}
+
+/// Returns every invariant the state breaks. Empty means the state is consistent.
+pub fn check(state: &GameState) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    if state.hull_integrity > MAX_HULL {
+        violations.push(Violation::HullOutOfRange {
+            hull: state.hull_integrity,
+        });
+    }
+
+    for p in state.players.values() {
+        let player = || p.id.clone();
+        let fainted = p.status.contains(&PlayerStatus::Fainted);
+
+        if p.ap < 0 {
+            violations.push(Violation::NegativeAp {
+                player: player(),
+                ap: p.ap,
+            });
+        }
+        if p.hp < 0 || p.hp > MAX_PLAYER_HP {
+            violations.push(Violation::HpOutOfRange {
+                player: player(),
+                hp: p.hp,
+            });
+        }
+        if fainted != (p.hp <= 0) {
+            violations.push(Violation::FaintedMismatch {
+                player: player(),
+                hp: p.hp,
+                fainted,
+            });
+        }
+        if fainted && p.ap > 0 {
+            violations.push(Violation::FaintedWithAp {
+                player: player(),
+                ap: p.ap,
+            });
+        }
+
+        let peppernuts = p.peppernut_count();
+        let special_items = p.special_item_count();
+        if !fits_inventory(p) {
+            violations.push(Violation::InventoryOverCapacity {
+                player: player(),
+                peppernuts,
+                special_items,
+            });
+        }
+
+        if !state.map.rooms.contains_key(&p.room_id) {
+            violations.push(Violation::PlayerInMissingRoom {
+                player: player(),
+                room: p.room_id,
+            });
+        }
+    }
+
+    for room in state.map.rooms.values() {
+        for &neighbor in &room.neighbors {
+            if !state.map.rooms.contains_key(&neighbor) {
+                violations.push(Violation::MissingNeighbor {
+                    room: room.id,
+                    neighbor,
+                });
+            }
+        }
+    }
+
+    let mut cards: BTreeMap<CardId, usize> = BTreeMap::new();
+    let all_cards = state
+        .deck
+        .iter()
+        .chain(&state.discard)
+        .chain(state.active_situations.iter().map(|c| &c.id));
+    for &card in all_cards {
+        *cards.entry(card).or_default() += 1;
+    }
+    for (card, count) in cards {
+        if count > 1 {
+            violations.push(Violation::DuplicateCard { card, count });
+        }
+    }
+
+    violations
+}
+
+/// Whether the player could have picked up their inventory one item at a time under
+/// `Player::can_add_item`. The Wheelbarrow goes first and Peppernuts last, since nuts
+/// only fill the slots the special items leave free.
+fn fits_inventory(p: &Player) -> bool {
+    let mut items = p.inventory.clone();
+    items.sort_by_key(|&item| match item {
+        ItemType::Wheelbarrow => 0,
+        ItemType::Peppernut => 2,
+        _ => 1,
+    });
+    let mut holder = Player {
+        inventory: Default::default(),
+        ..p.clone()
+    };
+    items.into_iter().all(|item| {
+        let fits = holder.can_add_item(item);
+        holder.inventory.push(item);
+        fits
+    })
+}
+
+/// Panics with every violation if the state is inconsistent.
+pub fn assert_valid(state: &GameState) {
+    let violations = check(state);
+    if !violations.is_empty() {
+        let list: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        panic!(
+            "Game state invariants violated (sequence {}, {:?}):\n  {}",
+            state.sequence_id,
+            state.phase,
+            list.join("\n  ")
+        );
+    }
+}
@@ -1,20 +1,39 @@
 mod bindings;
+pub mod chat;
+pub mod delta;
 pub mod field_map;
+pub mod invariants;
 pub mod logic;
+pub mod scenario;
 pub mod small_map;
 pub mod types;
+pub mod typescript;
+#[cfg(feature = "wasm")]
+mod wasm;
+pub mod zobrist;
 
+#[cfg(feature = "python")]
+pub use bindings::{Game, action_from_py};
 pub use logic::{GameError, GameLogic};
 pub use small_map::{SmallMap, SmallSet};
 pub use types::*;
 
-pub fn export_schema() -> String {
+fn full_schema() -> schemars::Schema {
     #[derive(schemars::JsonSchema)]
     #[allow(dead_code)]
     struct FullSchema {
         state: types::GameState,
         action: types::Action,
+        chat: chat::ChatEntry,
     }
-    let schema = schemars::schema_for!(FullSchema);
-    serde_json::to_string_pretty(&schema).unwrap()
+    schemars::schema_for!(FullSchema)
+}
+
+pub fn export_schema() -> String {
+    serde_json::to_string_pretty(&full_schema()).unwrap()
+}
+
+/// TypeScript declarations for the types in `export_schema`.
+pub fn export_typescript() -> String {
+    typescript::from_schema(full_schema().as_value())
 }
@@ -1,11 +1,15 @@
 pub mod actions;
 pub mod cards;
+pub mod explain;
+pub mod forecast;
 pub mod handlers;
 pub mod map_gen;
 pub mod pathfinding;
 pub mod resolution;
 
 pub use actions::apply_action;
+pub use explain::explain_action;
+pub use forecast::forecast;
 
 use crate::types::*;
 use rand::{Rng, SeedableRng, rngs::StdRng};
@@ -80,7 +84,7 @@ impl GameLogic {
             map,
             players,
             enemy: get_boss(0),
-            chat_log: vec![],
+            chat: vec![],
             shields_active: false,
             evasion_active: false,
             is_resting: false,
@@ -104,6 +108,18 @@ impl GameLogic {
     pub fn get_valid_actions(state: &GameState, player_id: &str) -> Vec<Action> {
         actions::get_valid_actions(state, player_id)
     }
+
+    pub fn explain_action(
+        state: &GameState,
+        player_id: &str,
+        action: &GameAction,
+    ) -> explain::ActionExplanation {
+        explain::explain_action(state, player_id, action)
+    }
+
+    pub fn forecast(state: &GameState) -> forecast::Forecast {
+        forecast::forecast(state)
+    }
 }
 
 pub fn find_room_with_system(state: &GameState, sys: SystemType) -> Option<RoomId> {
@@ -2,12 +2,18 @@ use super::{
     MAX_PLAYER_AP, MAX_PLAYER_HP,
     cards::{self, get_behavior},
     find_room_with_system_in_map,
-    pathfinding::find_path,
+    pathfinding::find_route,
     resolution,
 };
 use crate::logic::handlers::get_handler;
+use crate::zobrist;
 use crate::{logic::GameError, types::*};
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
 use uuid::Uuid;
 
 fn deterministic_uuid(state: &mut GameState) -> Uuid {
@@ -42,10 +48,18 @@ pub fn apply_action(
     player_id: &str,
     action: Action,
 ) -> Result<GameState, GameError> {
-    match action {
+    // `chat` only holds what this action says.
+    let mut state = state;
+    state.chat.clear();
+    let result = match action {
         Action::Meta(meta_action) => apply_meta_action(state, player_id, meta_action),
         Action::Game(game_action) => apply_game_action(state, player_id, game_action),
+    };
+    #[cfg(feature = "check-invariants")]
+    if let Ok(next) = &result {
+        crate::invariants::assert_valid(next);
     }
+    result
 }
 
 fn apply_meta_action(
@@ -139,19 +153,9 @@ fn apply_game_action(
 ) -> Result<GameState, GameError> {
     // Player Validation: Ensure the player exists for most actions.
     // We check this early to return a clear PlayerNotFound error.
-    match &action {
-        GameAction::Chat { .. } | GameAction::VoteReady { .. } => {
-            // These actions might be used by spectators or have different validation.
-            // For now, we still require a valid player ID.
-            if !state.players.contains_key(player_id) {
-                return Err(GameError::PlayerNotFound);
-            }
-        }
-        _ => {
-            if !state.players.contains_key(player_id) {
-                return Err(GameError::PlayerNotFound);
-            }
-        }
+    // Spectators (connections that never joined the game) may still chat.
+    if !matches!(action, GameAction::Chat { .. }) && !state.players.contains_key(player_id) {
+        return Err(GameError::PlayerNotFound);
     }
 
     // Phase Restriction: Gameplay actions only in TacticalPlanning
@@ -180,15 +184,6 @@ fn apply_game_action(
         }
     }
 
-    // --- ROBUST PROJECTION START ---
-    // For validation, we project the state forward by executing the current queue.
-    let mut projected_state = state.clone();
-    super::resolution::resolve_proposal_queue(&mut projected_state, true);
-
-    // CRITICAL FIX: Restore proposal_queue to projected_state so cost calculations
-    // (like Sugar Rush counting moves) can see what has already been proposed.
-    projected_state.proposal_queue = state.proposal_queue.clone();
-
     // 0. Handle Immediate Actions (Bypass Projection)
     match &action {
         GameAction::Chat { message } => {
@@ -197,10 +192,16 @@ fn apply_game_action(
                 get_behavior(card.id).validate_action(&state, player_id, &action)?;
             }
 
-            state.chat_log.push(ChatMessage {
+            let channel = if state.players.contains_key(player_id) {
+                ChatChannel::Crew
+            } else {
+                ChatChannel::Spectators
+            };
+            state.chat.push(ChatMessage {
                 sender: player_id.to_owned(),
                 text: message.clone(),
                 timestamp: 0,
+                channel,
             });
             state.sequence_id += 1;
             return Ok(state);
@@ -269,6 +270,11 @@ fn apply_game_action(
         _ => {} // Fallthrough
     }
 
+    // --- ROBUST PROJECTION START ---
+    // For validation, we project the state forward by executing the current queue.
+    let projected_state = project_queue(&state);
+    let queued = state.proposal_queue.len();
+
     // 1. CARD VALIDATION (Projected)
     for card in &projected_state.active_situations {
         get_behavior(card.id).validate_action(&projected_state, player_id, &action)?;
@@ -295,7 +301,11 @@ fn apply_game_action(
 
     // 3. Queue Logic (using Projected Context)
     match &action {
-        GameAction::Move { to_room } => {
+        GameAction::Move {
+            to_room,
+            mode,
+            force,
+        } => {
             let start_room = p_proj.room_id;
 
             // Check if any card allows "Leaping" / "Teleporting" directly (1 AP total)
@@ -316,36 +326,50 @@ fn apply_game_action(
                 state.proposal_queue.push(ProposedAction {
                     id,
                     player_id: player_id.to_owned(),
-                    action: GameAction::Move { to_room: *to_room },
+                    action: GameAction::move_to(*to_room),
                 });
                 let p = state
                     .players
                     .get_mut(player_id)
                     .ok_or(GameError::PlayerNotFound)?;
                 p.ap -= base_cost;
-            } else if let Some(path) = find_path(&state.map, start_room, *to_room) {
-                let step_cost = base_cost; // Per step
-                let total_cost = step_cost * (path.len() as i32);
-
-                if current_ap < total_cost {
+            } else {
+                // A single step is always explicit; only auto-pathing avoids ending in fire.
+                let adjacent = projected_state
+                    .map
+                    .rooms
+                    .get(&start_room)
+                    .is_some_and(|r| r.neighbors.contains(to_room));
+                let route = find_route(
+                    &projected_state,
+                    player_id,
+                    start_room,
+                    *to_room,
+                    *mode,
+                    *force || adjacent,
+                )
+                .map_err(|e| match e {
+                    GameError::RoomNotFound => GameError::InvalidMove,
+                    e => e,
+                })?;
+
+                if current_ap < route.ap_cost {
                     return Err(GameError::NotEnoughAP);
                 }
 
-                for step_room in path {
+                for step_room in route.steps {
                     let id = deterministic_uuid(&mut state);
                     state.proposal_queue.push(ProposedAction {
                         id,
                         player_id: player_id.to_owned(),
-                        action: GameAction::Move { to_room: step_room },
+                        action: GameAction::move_to(step_room),
                     });
                 }
                 let p = state
                     .players
                     .get_mut(player_id)
                     .ok_or(GameError::PlayerNotFound)?;
-                p.ap -= total_cost;
-            } else {
-                return Err(GameError::InvalidMove);
+                p.ap -= route.ap_cost;
             }
         }
         _ => {
@@ -369,10 +393,122 @@ fn apply_game_action(
     }
 
     state.sequence_id += 1;
+    extend_projection(&state, &projected_state, player_id, current_ap, queued);
     Ok(state)
 }
 
-fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
+/// States whose projection is cached per thread. Search keeps many states alive at
+/// once, so this holds more than the last one, oldest evicted first.
+const PROJECTION_CACHE_SIZE: usize = 256;
+
+#[derive(Default)]
+struct ProjectionCache {
+    entries: HashMap<u64, Rc<GameState>>,
+    order: VecDeque<u64>,
+}
+
+impl ProjectionCache {
+    fn insert(&mut self, key: u64, projected: Rc<GameState>) {
+        if self.entries.insert(key, projected).is_none() {
+            self.order.push_back(key);
+        }
+        if self.order.len() > PROJECTION_CACHE_SIZE
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+thread_local! {
+    /// Projections by the Zobrist key of the state they project.
+    static PROJECTION_CACHE: RefCell<ProjectionCache> = RefCell::default();
+}
+
+/// Projects the state forward by simulating the current proposal queue.
+///
+/// The proposal queue is restored on the result so cost calculations and card
+/// validation (like Seasick or Sugar Rush) can see what has already been proposed.
+///
+/// The solver validates many actions against the same state (`get_valid_actions`
+/// followed by `apply_action` for each candidate), so projections are cached by the
+/// state's Zobrist key. The key is computed from the state on every lookup, so a
+/// state edited in place never gets a stale projection. It leaves out the sequence
+/// id, the chat and the proposals' ids, which validation does not read. Queuing an
+/// action extends the projection it was validated against (`extend_projection`)
+/// rather than replaying the whole queue again.
+pub(crate) fn project_queue(state: &GameState) -> Rc<GameState> {
+    let key = zobrist::state_key(state);
+    if let Some(projected) = PROJECTION_CACHE.with_borrow(|cache| cache.entries.get(&key).cloned())
+    {
+        return projected;
+    }
+
+    let mut projected_state = state.clone();
+
+    // Replay the queue using the official resolution logic to determine
+    // the final position, inventory, and status of the player.
+    resolution::resolve_proposal_queue(&mut projected_state, true);
+
+    projected_state.proposal_queue = state.proposal_queue.clone();
+    // Nothing has been said yet; leave out what the simulation reported
+    projected_state.chat.clear();
+    let projected = Rc::new(projected_state);
+    PROJECTION_CACHE.with_borrow_mut(|cache| cache.insert(key, projected.clone()));
+    projected
+}
+
+/// Caches the projection of `state`, in which `player_id` has just queued
+/// `state.proposal_queue[queued..]` and paid for it out of `ap_before`. `projected`
+/// is the projection of the state before.
+///
+/// Simulated resolution neither reads AP nor draws from the RNG, so the queued
+/// prefix projects the same apart from the AP just spent, and only the new
+/// proposals need resolving.
+fn extend_projection(
+    state: &GameState,
+    projected: &GameState,
+    player_id: &str,
+    ap_before: i32,
+    queued: usize,
+) {
+    let mut extended = projected.clone();
+    extended.sequence_id = state.sequence_id;
+    extended.proposal_queue.clear();
+    if let (Some(p), Some(now)) = (
+        extended.players.get_mut(player_id),
+        state.players.get(player_id),
+    ) {
+        p.ap -= ap_before - now.ap;
+    }
+    for proposal in &state.proposal_queue[queued..] {
+        resolution::resolve_proposal(&mut extended, proposal, true);
+    }
+    extended.proposal_queue = state.proposal_queue.clone();
+    extended.chat.clear();
+
+    #[cfg(feature = "check-invariants")]
+    {
+        let mut replayed = state.clone();
+        resolution::resolve_proposal_queue(&mut replayed, true);
+        replayed.proposal_queue = state.proposal_queue.clone();
+        replayed.chat.clear();
+        assert_eq!(
+            extended, replayed,
+            "Extended projection differs from replaying the queue"
+        );
+    }
+    let key = zobrist::state_key(state);
+    PROJECTION_CACHE.with_borrow_mut(|cache| cache.insert(key, Rc::new(extended)));
+}
+
+/// Drops the cached projections of this thread. Only needed to measure uncached
+/// performance.
+pub fn clear_projection_cache() {
+    PROJECTION_CACHE.with_borrow_mut(|cache| *cache = ProjectionCache::default());
+}
+
+pub(crate) fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
     if state.phase == GamePhase::Victory || state.phase == GamePhase::GameOver {
         return Ok(state);
     }
@@ -406,11 +542,9 @@ fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
             state.latest_event = None;
 
             if state.is_resting {
-                state.chat_log.push(ChatMessage {
-                    sender: "SYSTEM".to_owned(),
-                    text: "Rest Round: The horizon is clear.".to_owned(),
-                    timestamp: 0,
-                });
+                state
+                    .chat
+                    .push(ChatMessage::system("Rest Round: The horizon is clear."));
                 state.enemy.next_attack = None;
             } else {
                 // Generate telegraph normally
@@ -513,19 +647,16 @@ fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
                     state.is_resting = false;
                     state.boss_level += 1;
                     state.enemy = crate::logic::get_boss(state.boss_level);
-                    state.chat_log.push(ChatMessage {
-                        sender: "SYSTEM".to_owned(),
-                        text: format!("Rest Over! Approaching: {}", state.enemy.name),
-                        timestamp: 0,
-                    });
+                    state.chat.push(ChatMessage::system(format!(
+                        "Rest Over! Approaching: {}",
+                        state.enemy.name
+                    )));
                 } else {
                     // Start Rest (1 round)
                     state.is_resting = true;
-                    state.chat_log.push(ChatMessage {
-                        sender: "SYSTEM".to_owned(),
-                        text: "Victory! Taking a rest round...".to_owned(),
-                        timestamp: 0,
-                    });
+                    state
+                        .chat
+                        .push(ChatMessage::system("Victory! Taking a rest round..."));
                 }
             }
 
@@ -535,8 +666,13 @@ fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
             } else {
                 MAX_PLAYER_AP
             };
+            // Fainted players get theirs when they respawn
             for p in state.players.values_mut() {
-                p.ap = base_ap;
+                p.ap = if p.status.contains(&PlayerStatus::Fainted) {
+                    0
+                } else {
+                    base_ap
+                };
             }
 
             if !state.is_resting {
@@ -571,6 +707,7 @@ fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
                 if p.status.contains(&PlayerStatus::Fainted) {
                     p.status.retain(|s| *s != PlayerStatus::Fainted);
                     p.hp = MAX_PLAYER_HP;
+                    p.ap = base_ap;
                     p.room_id = dormitory_id;
                 }
             }
@@ -585,8 +722,17 @@ fn advance_phase(mut state: GameState) -> Result<GameState, GameError> {
 }
 
 pub fn action_cost(state: &GameState, player_id: &str, action: &GameAction) -> i32 {
+    let mut cost = base_action_cost(state, player_id, action);
+    for card in &state.active_situations {
+        cost = get_behavior(card.id).modify_action_cost(state, player_id, action, cost);
+    }
+    cost
+}
+
+/// AP cost of an action before any card modifiers are applied.
+pub fn base_action_cost(state: &GameState, player_id: &str, action: &GameAction) -> i32 {
     // Immediate actions that don't have handlers or have special logic in handlers
-    let base = match action {
+    match action {
         GameAction::Chat { .. }
         | GameAction::VoteReady { .. }
         | GameAction::Pass
@@ -604,13 +750,7 @@ pub fn action_cost(state: &GameState, player_id: &str, action: &GameAction) -> i
             }
         }
         _ => get_handler(action).base_cost(),
-    };
-
-    let mut cost = base;
-    for card in &state.active_situations {
-        cost = get_behavior(card.id).modify_action_cost(state, player_id, action, cost);
     }
-    cost
 }
 
 pub fn get_valid_actions(state: &GameState, player_id: &str) -> Vec<Action> {
@@ -618,16 +758,7 @@ pub fn get_valid_actions(state: &GameState, player_id: &str) -> Vec<Action> {
     // We must validate actions against the *projected* state (after the queue executes),
     // not the current state. This allows chaining (e.g., Move -> Shoot).
 
-    let mut projected_state = state.clone();
-
-    // Replay the queue using the official resolution logic to determine
-    // the final position, inventory, and status of the player.
-    // This correctly updates room_id, inventory, etc. based on the queued actions.
-    resolution::resolve_proposal_queue(&mut projected_state, true);
-
-    // CRITICAL FIX: Restore proposal_queue to projected_state so card validation
-    // (like Seasick or Sugar Rush) can see what has already been proposed.
-    projected_state.proposal_queue = state.proposal_queue.clone();
+    let projected_state = project_queue(state);
 
     let mut actions = Vec::new();
 
@@ -709,7 +840,7 @@ pub fn get_valid_actions(state: &GameState, player_id: &str) -> Vec<Action> {
         }
 
         for to_room in target_rooms {
-            let action = GameAction::Move { to_room };
+            let action = GameAction::move_to(to_room);
             if current_ap >= action_cost(&projected_state, player_id, &action) {
                 actions.push(Action::Game(action));
             }
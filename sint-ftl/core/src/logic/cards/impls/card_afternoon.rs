@@ -43,11 +43,10 @@ impl CardBehavior for AfternoonNapCard {
             card.affected_player = Some(reader_id.clone());
         }
 
-        state.chat_log.push(ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: format!("{} is the Reader and falls asleep!", reader_id),
-            timestamp: 0,
-        });
+        state.chat.push(ChatMessage::system(format!(
+            "{} is the Reader and falls asleep!",
+            reader_id
+        )));
     }
 
     fn validate_action(
@@ -31,7 +31,7 @@ impl CardBehavior for BlockadeCard {
         action: &GameAction,
     ) -> Result<(), GameError> {
         if let Some(cannons_id) = find_room_with_system(state, SystemType::Cannons)
-            && let GameAction::Move { to_room } = action
+            && let GameAction::Move { to_room, .. } = action
         {
             if *to_room == cannons_id {
                 return Err(GameError::InvalidAction(
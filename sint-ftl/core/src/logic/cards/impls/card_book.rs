@@ -31,11 +31,9 @@ impl CardBehavior for TheBookCard {
 
     fn on_solved(&self, state: &mut GameState) {
         state.enemy.next_attack = None;
-        state.chat_log.push(ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: "The Book is recovered! The enemy is confused and skips their attack.".to_owned(),
-            timestamp: 0,
-        });
+        state.chat.push(ChatMessage::system(
+            "The Book is recovered! The enemy is confused and skips their attack.",
+        ));
     }
 
     fn on_trigger(&self, state: &mut GameState) {
@@ -32,29 +32,24 @@ impl CardBehavior for GoldenNutCard {
 
     fn on_solved(&self, state: &mut GameState) {
         state.enemy.hp -= 1;
-        state.chat_log.push(ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: "Golden Nut used! 1 Damage dealt to the Enemy.".to_owned(),
-            timestamp: 0,
-        });
+        state.chat.push(ChatMessage::system(
+            "Golden Nut used! 1 Damage dealt to the Enemy.",
+        ));
 
         // Check for Boss Death
         if state.enemy.hp <= 0 {
             if state.boss_level >= crate::logic::MAX_BOSS_LEVEL - 1 {
                 state.phase = GamePhase::Victory;
-                state.chat_log.push(ChatMessage {
-                    sender: "SYSTEM".to_owned(),
-                    text: "VICTORY! All bosses defeated!".to_owned(),
-                    timestamp: 0,
-                });
+                state
+                    .chat
+                    .push(ChatMessage::system("VICTORY! All bosses defeated!"));
             } else {
                 // Mark defeated to trigger rest round in advance_phase
                 state.enemy.state = EnemyState::Defeated;
-                state.chat_log.push(ChatMessage {
-                    sender: "SYSTEM".to_owned(),
-                    text: format!("{} Defeated! Taking a breather...", state.enemy.name),
-                    timestamp: 0,
-                });
+                state.chat.push(ChatMessage::system(format!(
+                    "{} Defeated! Taking a breather...",
+                    state.enemy.name
+                )));
             }
         }
     }
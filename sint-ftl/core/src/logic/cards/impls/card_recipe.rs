@@ -33,11 +33,9 @@ impl CardBehavior for RecipeCard {
             p.inventory.push(ItemType::Peppernut);
             p.inventory.push(ItemType::Peppernut);
         }
-        state.chat_log.push(crate::types::ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: "Recipe found! Everyone receives Super Peppernuts (2x Ammo).".to_owned(),
-            timestamp: 0,
-        });
+        state.chat.push(crate::types::ChatMessage::system(
+            "Recipe found! Everyone receives Super Peppernuts (2x Ammo).",
+        ));
     }
 
     fn on_trigger(&self, state: &mut GameState) {
@@ -46,7 +46,7 @@ impl CardBehavior for SlipperyDeckCard {
     ) -> i32 {
         match action {
             // Moving into a Hallway (Room with no system) is free
-            GameAction::Move { to_room } => {
+            GameAction::Move { to_room, .. } => {
                 if let Some(room) = state.map.rooms.get(to_room)
                     && room.system.is_none()
                 {
@@ -34,12 +34,9 @@ impl CardBehavior for TheStaffCard {
             p.hp = MAX_PLAYER_HP;
             p.status.retain(|s| *s != PlayerStatus::Fainted);
         }
-        state.chat_log.push(crate::types::ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: "The Staff is activated! The crew is fully healed via Magical Recovery."
-                .to_owned(),
-            timestamp: 0,
-        });
+        state.chat.push(crate::types::ChatMessage::system(
+            "The Staff is activated! The crew is fully healed via Magical Recovery.",
+        ));
     }
 
     fn on_trigger(&self, state: &mut GameState) {
@@ -30,7 +30,7 @@ impl CardBehavior for StickyFloorCard {
         action: &GameAction,
         current_cost: i32,
     ) -> i32 {
-        if let GameAction::Move { to_room } = action
+        if let GameAction::Move { to_room, .. } = action
             && let Some(kitchen_id) = find_room_with_system(state, SystemType::Kitchen)
             && *to_room == kitchen_id
         {
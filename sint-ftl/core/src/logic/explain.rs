@@ -0,0 +1,524 @@
+use super::{
+    MAX_PLAYER_AP,
+    actions::{action_cost, base_action_cost, project_queue},
+    cards::get_behavior,
+    find_room_with_system_in_map,
+    handlers::get_handler,
+    pathfinding::{RouteMode, find_path, find_route},
+};
+use crate::{logic::GameError, types::*};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Fixes `relax` can apply to one scratch state, each at most once: travel to the
+/// action's system room, clear the fire, clear the leak, fetch a Peppernut and
+/// empty the hands. After a fix its precondition holds, so it cannot fire again.
+const MAX_RELAXATIONS: usize = 5;
+
+/// Validations run by `explain_rules`: one before each relaxation, and one more
+/// that either passes or reports a failure `relax` has no fix for. Reaching the
+/// bound means a handler rejects a state `relax` already fixed, so stop rather
+/// than loop.
+const MAX_RULE_STEPS: usize = MAX_RELAXATIONS + 1;
+
+/// Where a blocking reason comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum BlockerSource {
+    /// The acting player is unknown or fainted.
+    Player,
+    /// The action is not allowed in the current phase.
+    Phase,
+    /// An active situation card forbids the action.
+    Card(CardId),
+    /// The action's own rules (location, hazards, items, targets).
+    Rules,
+    /// Not enough AP once card cost modifiers are applied.
+    ActionPoints,
+    /// The action is valid right now, but not after the player's queued actions resolve.
+    Queue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Blocker {
+    pub source: BlockerSource,
+    pub message: String,
+}
+
+/// Why an action is or isn't possible for a player, with a suggested way to fix it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ActionExplanation {
+    pub action: GameAction,
+    /// True when `apply_action` would accept the action.
+    pub allowed: bool,
+    /// AP the action costs after card modifiers (total path cost for a multi-step Move).
+    pub ap_cost: i32,
+    /// AP the player actually has (queued actions are already paid for).
+    pub ap_available: i32,
+    /// Every reason the action is blocked. Empty when allowed.
+    pub blockers: Vec<Blocker>,
+    /// Ordered steps that would make the action possible.
+    pub suggestions: Vec<String>,
+}
+
+impl ActionExplanation {
+    /// One-line text form for tooltips and LLM prompts.
+    pub fn summary(&self) -> String {
+        if self.allowed {
+            return format!("Allowed ({} AP)", self.ap_cost);
+        }
+        let reasons: Vec<&str> = self.blockers.iter().map(|b| b.message.as_str()).collect();
+        if self.suggestions.is_empty() {
+            format!("Blocked: {}", reasons.join("; "))
+        } else {
+            format!(
+                "Blocked: {}. Fix: {}",
+                reasons.join("; "),
+                self.suggestions.join(", then ")
+            )
+        }
+    }
+
+    fn block(&mut self, source: BlockerSource, message: impl Into<String>) {
+        self.blockers.push(Blocker {
+            source,
+            message: message.into(),
+        });
+    }
+}
+
+/// Ordering of suggested fixes: fetch items before walking to the target room,
+/// and clear the room once there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FixStage {
+    Wait,
+    Free,
+    Acquire,
+    Travel,
+    Clear,
+    Resolve,
+}
+
+/// Explains whether `player_id` can perform `action`, listing every blocking reason.
+///
+/// Mirrors the checks of `apply_action` (card validation, handler validation,
+/// AP after `modify_action_cost`, and the projection of the proposal queue),
+/// but keeps going after the first failure.
+pub fn explain_action(
+    state: &GameState,
+    player_id: &str,
+    action: &GameAction,
+) -> ActionExplanation {
+    let mut ex = ActionExplanation {
+        action: action.clone(),
+        allowed: false,
+        ap_cost: 0,
+        ap_available: 0,
+        blockers: vec![],
+        suggestions: vec![],
+    };
+    let mut fixes: Vec<(FixStage, String)> = vec![];
+
+    let Some(player) = state.players.get(player_id) else {
+        ex.block(BlockerSource::Player, GameError::PlayerNotFound.to_string());
+        return ex;
+    };
+    ex.ap_available = player.ap;
+
+    let always_allowed = matches!(
+        action,
+        GameAction::Chat { .. } | GameAction::VoteReady { .. }
+    );
+    if !always_allowed {
+        if player.status.contains(&PlayerStatus::Fainted) {
+            ex.block(BlockerSource::Player, "You are fainted and cannot act!");
+            fixes.push((
+                FixStage::Wait,
+                "Wait for a crewmate to Revive you, or respawn next round".to_owned(),
+            ));
+        }
+        if state.phase != GamePhase::TacticalPlanning {
+            ex.block(
+                BlockerSource::Phase,
+                format!("Cannot act during {:?}", state.phase),
+            );
+            fixes.push((
+                FixStage::Wait,
+                "Wait for the Tactical Planning phase".to_owned(),
+            ));
+        }
+    }
+
+    match action {
+        GameAction::Chat { .. }
+        | GameAction::VoteReady { .. }
+        | GameAction::Pass
+        | GameAction::Undo { .. } => {
+            explain_cards(state, player_id, action, &mut ex, &mut fixes);
+            explain_immediate(state, player_id, action, &mut ex);
+        }
+        _ => {
+            let projected = project_queue(state);
+            explain_cards(&projected, player_id, action, &mut ex, &mut fixes);
+
+            let needed = match action {
+                GameAction::Move {
+                    to_room,
+                    mode,
+                    force,
+                } => explain_move(
+                    &projected, player_id, *to_room, *mode, *force, &mut ex, &mut fixes,
+                ),
+                _ => {
+                    explain_rules(state, &projected, player_id, action, &mut ex, &mut fixes);
+                    action_cost(&projected, player_id, action)
+                }
+            };
+            ex.ap_cost = needed;
+            explain_ap(&projected, player_id, action, needed, &mut ex, &mut fixes);
+        }
+    }
+
+    ex.allowed = ex.blockers.is_empty();
+    fixes.sort_by_key(|(stage, _)| *stage);
+    for (_, fix) in fixes {
+        if !ex.suggestions.contains(&fix) {
+            ex.suggestions.push(fix);
+        }
+    }
+    ex
+}
+
+fn explain_cards(
+    state: &GameState,
+    player_id: &str,
+    action: &GameAction,
+    ex: &mut ActionExplanation,
+    fixes: &mut Vec<(FixStage, String)>,
+) {
+    for card in &state.active_situations {
+        if let Err(e) = get_behavior(card.id).validate_action(state, player_id, action) {
+            ex.block(BlockerSource::Card(card.id), e.to_string());
+            if let Some(fix) = describe_card_fix(state, card) {
+                fixes.push((FixStage::Resolve, fix));
+            }
+        }
+    }
+}
+
+fn explain_immediate(
+    state: &GameState,
+    player_id: &str,
+    action: &GameAction,
+    ex: &mut ActionExplanation,
+) {
+    match action {
+        GameAction::Pass if state.players.get(player_id).is_some_and(|p| p.ap == 0) => {
+            ex.block(
+                BlockerSource::ActionPoints,
+                "Cannot Pass with 0 AP. You are already out of actions for this round.",
+            );
+        }
+        GameAction::Undo { action_id } => {
+            match state.proposal_queue.iter().find(|p| p.id == *action_id) {
+                Some(proposal) if proposal.player_id != player_id => {
+                    ex.block(BlockerSource::Rules, "Cannot undo another player's action");
+                }
+                Some(_) => {}
+                None => ex.block(BlockerSource::Rules, "Action not found to undo"),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the AP a Move needs, following the same leap/path rules as `apply_action`.
+fn explain_move(
+    projected: &GameState,
+    player_id: &str,
+    to_room: RoomId,
+    mode: RouteMode,
+    force: bool,
+    ex: &mut ActionExplanation,
+    fixes: &mut Vec<(FixStage, String)>,
+) -> i32 {
+    let step_cost = action_cost(projected, player_id, &GameAction::move_to(to_room));
+    let Some(start_room) = projected.players.get(player_id).map(|p| p.room_id) else {
+        return step_cost;
+    };
+
+    let can_leap = projected
+        .active_situations
+        .iter()
+        .any(|card| get_behavior(card.id).can_reach(projected, player_id, to_room));
+    if can_leap {
+        return step_cost;
+    }
+
+    let adjacent = projected
+        .map
+        .rooms
+        .get(&start_room)
+        .is_some_and(|r| r.neighbors.contains(&to_room));
+    match find_route(
+        projected,
+        player_id,
+        start_room,
+        to_room,
+        mode,
+        force || adjacent,
+    ) {
+        Ok(route) => route.ap_cost,
+        Err(e) => {
+            if let GameError::InvalidAction(_) = e {
+                fixes.push((
+                    FixStage::Travel,
+                    "Force the move, or go one room at a time, to enter the burning room anyway"
+                        .to_owned(),
+                ));
+            }
+            ex.block(BlockerSource::Rules, e.to_string());
+            step_cost
+        }
+    }
+}
+
+/// Peels off handler validation failures one at a time, relaxing a scratch copy of the
+/// projected state after each so later prerequisites are reported too.
+fn explain_rules(
+    state: &GameState,
+    projected: &GameState,
+    player_id: &str,
+    action: &GameAction,
+    ex: &mut ActionExplanation,
+    fixes: &mut Vec<(FixStage, String)>,
+) {
+    let handler = get_handler(action);
+    if handler.validate(projected, player_id).is_ok() {
+        return;
+    }
+
+    // Queued actions (e.g. moving away) can invalidate an action that works right now.
+    let caused_by_queue =
+        !state.proposal_queue.is_empty() && handler.validate(state, player_id).is_ok();
+
+    let origin = projected
+        .players
+        .get(player_id)
+        .map(|p| p.room_id)
+        .unwrap_or_default();
+    let mut scratch = projected.clone();
+    let mut seen: Vec<String> = vec![];
+
+    for step in 0..MAX_RULE_STEPS {
+        let Err(err) = handler.validate(&scratch, player_id) else {
+            break;
+        };
+        let message = err.to_string();
+        if seen.contains(&message) {
+            break;
+        }
+        seen.push(message.clone());
+
+        if step == 0 && caused_by_queue {
+            ex.block(
+                BlockerSource::Queue,
+                format!("{} (after your queued actions resolve)", message),
+            );
+            fixes.push((
+                FixStage::Free,
+                "Undo the queued actions that change your position or inventory".to_owned(),
+            ));
+        } else {
+            ex.block(BlockerSource::Rules, message);
+        }
+
+        match relax(&mut scratch, player_id, action, &err, origin) {
+            Some(fix) => fixes.push(fix),
+            None => break,
+        }
+    }
+}
+
+/// Applies the most direct fix for `err` to `scratch` and describes it, or returns
+/// `None` if there is none. Fixes are tried in the order a player would do them:
+/// walking somewhere else changes which hazards and items matter, so location
+/// comes first, then the room's hazards, then what the player carries.
+fn relax(
+    scratch: &mut GameState,
+    player_id: &str,
+    action: &GameAction,
+    err: &GameError,
+    origin: RoomId,
+) -> Option<(FixStage, String)> {
+    let here = scratch.players.get(player_id)?.room_id;
+
+    if let Some(sys) = required_system(action)
+        && let Some(target) = find_room_with_system_in_map(&scratch.map, sys)
+        && target != here
+    {
+        let fix = format!("Move to {}", describe_room(scratch, target));
+        scratch.players.get_mut(player_id)?.room_id = target;
+        return Some((FixStage::Travel, fix));
+    }
+
+    if let GameError::RoomBlocked = err {
+        let room = scratch.map.rooms.get_mut(&here)?;
+        // Clear one hazard per step so both are reported when a room has both
+        let (hazard, verb) = if room.hazards.contains(&HazardType::Fire) {
+            (HazardType::Fire, "Extinguish the fire")
+        } else {
+            (HazardType::Water, "Repair the leak")
+        };
+        room.hazards.retain(|h| *h != hazard);
+        let fix = format!("{} in {} ({})", verb, room.name, room.id);
+        return Some((FixStage::Clear, fix));
+    }
+
+    if let GameAction::Shoot = action {
+        let p = scratch.players.get(player_id)?;
+        if !p.inventory.contains(&ItemType::Peppernut) {
+            let fix = describe_peppernut_source(scratch, origin);
+            scratch
+                .players
+                .get_mut(player_id)?
+                .inventory
+                .push(ItemType::Peppernut);
+            return Some((FixStage::Acquire, fix));
+        }
+    }
+
+    if let (GameAction::PickUp { .. }, GameError::InventoryFull) = (action, err) {
+        // Which item to drop is the player's call; an empty inventory fits anything
+        scratch.players.get_mut(player_id)?.inventory.clear();
+        return Some((FixStage::Free, "Drop an item to free a slot".to_owned()));
+    }
+
+    None
+}
+
+fn explain_ap(
+    projected: &GameState,
+    player_id: &str,
+    action: &GameAction,
+    needed: i32,
+    ex: &mut ActionExplanation,
+    fixes: &mut Vec<(FixStage, String)>,
+) {
+    if ex.ap_available >= needed {
+        return;
+    }
+
+    // Name the cards that changed the per-action cost.
+    let mut cost = base_action_cost(projected, player_id, action);
+    let mut modifiers = vec![];
+    for card in &projected.active_situations {
+        let next = get_behavior(card.id).modify_action_cost(projected, player_id, action, cost);
+        if next != cost {
+            modifiers.push(format!("{} {:+}", card.title, next - cost));
+            if next > cost
+                && let Some(fix) = describe_card_fix(projected, card)
+            {
+                fixes.push((FixStage::Resolve, fix));
+            }
+        }
+        cost = next;
+    }
+
+    let mut message = format!("Needs {} AP but only {} AP left", needed, ex.ap_available);
+    if !modifiers.is_empty() {
+        message.push_str(&format!(" ({})", modifiers.join(", ")));
+    }
+    ex.block(BlockerSource::ActionPoints, message);
+
+    if projected
+        .proposal_queue
+        .iter()
+        .any(|p| p.player_id == player_id)
+    {
+        fixes.push((
+            FixStage::Free,
+            "Undo some of your queued actions to free AP".to_owned(),
+        ));
+    } else if needed <= MAX_PLAYER_AP {
+        fixes.push((
+            FixStage::Wait,
+            "Wait for next round when AP refills".to_owned(),
+        ));
+    }
+}
+
+fn required_system(action: &GameAction) -> Option<SystemType> {
+    match action {
+        GameAction::Bake => Some(SystemType::Kitchen),
+        GameAction::Shoot => Some(SystemType::Cannons),
+        GameAction::RaiseShields => Some(SystemType::Bridge),
+        GameAction::EvasiveManeuvers => Some(SystemType::Engine),
+        GameAction::Lookout => Some(SystemType::Bow),
+        GameAction::FirstAid { .. } => Some(SystemType::Sickbay),
+        _ => None,
+    }
+}
+
+fn describe_room(state: &GameState, room_id: RoomId) -> String {
+    match state.map.rooms.get(&room_id) {
+        Some(room) => format!("{} (Room {})", room.name, room.id),
+        None => format!("Room {}", room_id),
+    }
+}
+
+/// Nearest place to get a Peppernut: a pile on the floor, otherwise the Kitchen.
+fn describe_peppernut_source(state: &GameState, origin: RoomId) -> String {
+    let nearest = state
+        .map
+        .rooms
+        .values()
+        .filter(|r| r.items.contains(&ItemType::Peppernut))
+        .filter_map(|r| find_path(&state.map, origin, r.id).map(|path| (path.len(), r.id)))
+        .min();
+
+    match nearest {
+        Some((0, _)) => "Pick up a Peppernut here first".to_owned(),
+        Some((_, room_id)) => format!(
+            "Move to {} and pick up a Peppernut first",
+            describe_room(state, room_id)
+        ),
+        None => match find_room_with_system_in_map(&state.map, SystemType::Kitchen) {
+            Some(kitchen) => format!(
+                "Bake Peppernuts in {} and pick one up first",
+                describe_room(state, kitchen)
+            ),
+            None => "Get a Peppernut first".to_owned(),
+        },
+    }
+}
+
+fn describe_card_fix(state: &GameState, card: &Card) -> Option<String> {
+    if let Some(sol) = &card.solution {
+        let place = match sol
+            .target_system
+            .and_then(|sys| find_room_with_system_in_map(&state.map, sys))
+        {
+            Some(room_id) => format!("in {}", describe_room(state, room_id)),
+            None => "anywhere".to_owned(),
+        };
+        let mut fix = format!(
+            "Resolve '{}' with Interact {} ({} AP",
+            card.title, place, sol.ap_cost
+        );
+        if let Some(item) = sol.item_cost {
+            fix.push_str(&format!(", costs a {:?}", item));
+        }
+        if sol.required_players > 1 {
+            fix.push_str(&format!(", needs {} players", sol.required_players));
+        }
+        fix.push(')');
+        Some(fix)
+    } else if let CardType::Timebomb { rounds_left } = card.card_type {
+        Some(format!(
+            "Wait {} round(s) for '{}' to expire",
+            rounds_left, card.title
+        ))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,149 @@
+use super::{GameError, actions::advance_phase};
+use crate::types::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of sampled rounds used by `forecast`.
+pub const FORECAST_SAMPLES: usize = 500;
+
+/// Safety bound on phase transitions per sample (Lobby needs five to reach the enemy phase).
+const MAX_PHASE_STEPS: usize = 8;
+
+/// Outcome distribution for the coming enemy phase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Forecast {
+    pub samples: usize,
+    /// `hull_loss[n]` is the probability of losing exactly `n` hull points.
+    pub hull_loss: Vec<f64>,
+    pub expected_hull_loss: f64,
+    /// Probability that each conscious player faints.
+    pub faint_risk: BTreeMap<PlayerId, f64>,
+    /// Probability that each working system breaks, by room.
+    pub break_risk: BTreeMap<RoomId, f64>,
+    pub expected_systems_broken: f64,
+    /// `enemy_hp[n]` is the probability that the enemy ends the round with `n` HP.
+    pub enemy_hp: Vec<f64>,
+    pub expected_enemy_hp: f64,
+    /// Probability that the round ends the game (hull destroyed or crew wiped).
+    pub game_over_risk: f64,
+}
+
+/// Samples the rest of the round `FORECAST_SAMPLES` times.
+pub fn forecast(state: &GameState) -> Forecast {
+    forecast_with_samples(state, FORECAST_SAMPLES)
+}
+
+/// Forecasts the coming enemy phase by Monte-Carlo sampling the remaining randomness.
+///
+/// Each sample resolves the current proposal queue (shot rolls), assumes everyone
+/// passes with their remaining AP, and then runs the enemy attack (including hidden
+/// Fog Bank attacks) and hazard resolution (fire spreads). Only the RNG seed differs
+/// between samples, so the result is deterministic for a given state.
+pub fn forecast_with_samples(state: &GameState, samples: usize) -> Forecast {
+    let samples = samples.max(1);
+    let mut rng = StdRng::seed_from_u64(state.rng_seed);
+
+    let mut result = Forecast {
+        samples,
+        hull_loss: vec![],
+        expected_hull_loss: 0.0,
+        faint_risk: state
+            .players
+            .values()
+            .filter(|p| !p.status.contains(&PlayerStatus::Fainted))
+            .map(|p| (p.id.clone(), 0.0))
+            .collect(),
+        break_risk: state
+            .map
+            .rooms
+            .values()
+            .filter(|r| r.system.is_some() && !r.is_broken)
+            .map(|r| (r.id, 0.0))
+            .collect(),
+        expected_systems_broken: 0.0,
+        enemy_hp: vec![0.0; state.enemy.max_hp.max(0) as usize + 1],
+        expected_enemy_hp: 0.0,
+        game_over_risk: 0.0,
+    };
+
+    for _ in 0..samples {
+        let mut sample = state.clone();
+        sample.rng_seed = rng.random();
+        let Ok(end) = run_to_enemy_phase(sample) else {
+            continue;
+        };
+
+        let hull_loss = (state.hull_integrity - end.hull_integrity).max(0) as usize;
+        if result.hull_loss.len() <= hull_loss {
+            result.hull_loss.resize(hull_loss + 1, 0.0);
+        }
+        result.hull_loss[hull_loss] += 1.0;
+        result.expected_hull_loss += hull_loss as f64;
+
+        for (pid, risk) in result.faint_risk.iter_mut() {
+            if end
+                .players
+                .get(pid)
+                .is_none_or(|p| p.status.contains(&PlayerStatus::Fainted))
+            {
+                *risk += 1.0;
+            }
+        }
+
+        for (room_id, risk) in result.break_risk.iter_mut() {
+            if end.map.rooms.get(room_id).is_some_and(|r| r.is_broken) {
+                *risk += 1.0;
+                result.expected_systems_broken += 1.0;
+            }
+        }
+
+        let enemy_hp = end.enemy.hp.max(0) as usize;
+        if result.enemy_hp.len() <= enemy_hp {
+            result.enemy_hp.resize(enemy_hp + 1, 0.0);
+        }
+        result.enemy_hp[enemy_hp] += 1.0;
+        result.expected_enemy_hp += enemy_hp as f64;
+
+        if end.phase == GamePhase::GameOver {
+            result.game_over_risk += 1.0;
+        }
+    }
+
+    // Counts to probabilities
+    let n = samples as f64;
+    let probabilities = result
+        .hull_loss
+        .iter_mut()
+        .chain(result.enemy_hp.iter_mut())
+        .chain(result.faint_risk.values_mut())
+        .chain(result.break_risk.values_mut());
+    for p in probabilities {
+        *p /= n;
+    }
+    result.expected_hull_loss /= n;
+    result.expected_systems_broken /= n;
+    result.expected_enemy_hp /= n;
+    result.game_over_risk /= n;
+
+    result
+}
+
+/// Advances a sample until the enemy phase has resolved (or the game ended).
+fn run_to_enemy_phase(mut state: GameState) -> Result<GameState, GameError> {
+    for _ in 0..MAX_PHASE_STEPS {
+        match state.phase {
+            GamePhase::EnemyAction | GamePhase::GameOver | GamePhase::Victory => break,
+            GamePhase::Execution => {
+                // Everyone passes after the queued actions.
+                for p in state.players.values_mut() {
+                    p.ap = 0;
+                }
+            }
+            _ => {}
+        }
+        state = advance_phase(state)?;
+    }
+    Ok(state)
+}
@@ -21,7 +21,9 @@ pub mod system_actions;
 
 pub fn get_handler(action: &GameAction) -> Box<dyn ActionHandler> {
     match action {
-        GameAction::Move { to_room } => Box::new(move_action::MoveHandler { to_room: *to_room }),
+        GameAction::Move { to_room, .. } => {
+            Box::new(move_action::MoveHandler { to_room: *to_room })
+        }
         GameAction::Bake => Box::new(system_actions::BakeHandler),
         GameAction::Shoot => Box::new(system_actions::ShootHandler),
         GameAction::RaiseShields => Box::new(system_actions::RaiseShieldsHandler),
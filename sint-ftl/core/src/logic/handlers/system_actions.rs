@@ -125,11 +125,10 @@ impl ActionHandler for ShootHandler {
                 }
             }
             if roll < threshold {
-                state.chat_log.push(ChatMessage {
-                    sender: "SYSTEM".to_owned(),
-                    text: format!("{} missed the shot! (Rolled {})", player_id, roll),
-                    timestamp: 0,
-                });
+                state.chat.push(ChatMessage::system(format!(
+                    "{} missed the shot! (Rolled {})",
+                    player_id, roll
+                )));
             }
             roll >= threshold
         };
@@ -140,18 +139,15 @@ impl ActionHandler for ShootHandler {
             if state.enemy.hp <= 0 {
                 if state.boss_level >= crate::logic::MAX_BOSS_LEVEL - 1 {
                     state.phase = crate::types::GamePhase::Victory;
-                    state.chat_log.push(ChatMessage {
-                        sender: "SYSTEM".to_owned(),
-                        text: "VICTORY! All bosses defeated!".to_owned(),
-                        timestamp: 0,
-                    });
+                    state
+                        .chat
+                        .push(ChatMessage::system("VICTORY! All bosses defeated!"));
                 } else {
                     state.enemy.state = EnemyState::Defeated;
-                    state.chat_log.push(ChatMessage {
-                        sender: "SYSTEM".to_owned(),
-                        text: format!("{} Defeated! Taking a breather...", state.enemy.name),
-                        timestamp: 0,
-                    });
+                    state.chat.push(ChatMessage::system(format!(
+                        "{} Defeated! Taking a breather...",
+                        state.enemy.name
+                    )));
                 }
             }
         }
@@ -313,11 +309,7 @@ impl ActionHandler for LookoutHandler {
             "LOOKOUT REPORT: The horizon is clear (Deck Empty).".to_owned()
         };
 
-        state.chat_log.push(ChatMessage {
-            sender: "SYSTEM".to_owned(),
-            text: msg,
-            timestamp: 0,
-        });
+        state.chat.push(ChatMessage::system(msg));
         Ok(())
     }
 }
@@ -1,6 +1,11 @@
+use super::{GameError, actions::action_cost};
 use crate::small_map::SmallSet;
-use crate::types::{GameMap, RoomId};
-use std::collections::VecDeque;
+pub use crate::types::RouteMode;
+use crate::types::{AttackEffect, GameAction, GameMap, GameState, HazardType, RoomId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 
 pub struct MapDistances {
     distances: Vec<Vec<u32>>,
@@ -93,3 +98,173 @@ pub fn find_path(map: &GameMap, start: RoomId, end: RoomId) -> Option<Vec<RoomId
     }
     None
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Route {
+    /// Rooms entered in order, excluding the start room.
+    pub steps: Vec<RoomId>,
+    /// AP of each step after card modifiers.
+    pub step_costs: Vec<i32>,
+    pub ap_cost: i32,
+    /// Summed `room_danger` of the rooms entered.
+    pub danger: u32,
+}
+
+/// How dangerous it is to pass through a room. Fire hurts at the end of the round,
+/// the telegraphed target is about to catch fire, and water only gets in the way.
+pub fn room_danger(state: &GameState, room_id: RoomId) -> u32 {
+    let Some(room) = state.map.rooms.get(&room_id) else {
+        return 0;
+    };
+    let mut danger = room
+        .hazards
+        .iter()
+        .map(|h| match h {
+            HazardType::Fire => 3,
+            HazardType::Water => 1,
+        })
+        .sum();
+    if state
+        .enemy
+        .next_attack
+        .as_ref()
+        .is_some_and(|a| a.target_room == Some(room_id) && a.effect != AttackEffect::Miss)
+    {
+        danger += 2;
+    }
+    danger
+}
+
+/// Weighted route from `start` to `end` for `player_id`.
+///
+/// Each step costs what `action_cost` charges for that single `Move`, so card
+/// modifiers like Slippery Deck or Sticky Floor are accounted for. Those modifiers
+/// only look at the destination room, so a room costs the same to enter from any
+/// neighbour and the state is never walked around.
+///
+/// Unless `force` is set, a route is refused if it ends in a burning room *and*
+/// spends the player's remaining AP, since only then are they stuck there when the
+/// round ends. With AP to spare they can still put the fire out or walk on.
+///
+/// `apply_action` routes a `Move` with the action's own `mode` and `force`.
+pub fn find_route(
+    state: &GameState,
+    player_id: &str,
+    start: RoomId,
+    end: RoomId,
+    mode: RouteMode,
+    force: bool,
+) -> Result<Route, GameError> {
+    let player = state
+        .players
+        .get(player_id)
+        .ok_or(GameError::PlayerNotFound)?;
+    let Some(end_room) = state.map.rooms.get(&end) else {
+        return Err(GameError::RoomNotFound);
+    };
+
+    let size = state
+        .map
+        .rooms
+        .keys()
+        .map(|id| id as usize)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut enter_cost = vec![0i32; size];
+    let mut danger_of = vec![0u32; size];
+    for id in state.map.rooms.keys() {
+        enter_cost[id as usize] = action_cost(state, player_id, &GameAction::move_to(id)).max(0);
+        danger_of[id as usize] = room_danger(state, id);
+    }
+
+    // With every step alike, the fewest rooms is also the fewest AP and danger.
+    let uniform = state.map.rooms.keys().all(|id| {
+        enter_cost[id as usize] == enter_cost[end as usize] && danger_of[id as usize] == 0
+    });
+    let steps = if mode == RouteMode::Shortest && uniform {
+        find_path(&state.map, start, end).ok_or(GameError::InvalidMove)?
+    } else {
+        weighted_path(state, start, end, mode, &enter_cost, &danger_of)?
+    };
+
+    let step_costs: Vec<i32> = steps.iter().map(|&r| enter_cost[r as usize]).collect();
+    let ap_cost = step_costs.iter().sum();
+    let danger = steps.iter().map(|&r| danger_of[r as usize]).sum();
+
+    if !force
+        && !steps.is_empty()
+        && end_room.hazards.contains(&HazardType::Fire)
+        && player.ap == ap_cost
+    {
+        return Err(GameError::InvalidAction(format!(
+            "Route ends the round in a burning room ({}). Force the move or go one room at a time",
+            end_room.name
+        )));
+    }
+
+    Ok(Route {
+        steps,
+        step_costs,
+        ap_cost,
+        danger,
+    })
+}
+
+/// Dijkstra over (AP, danger, length) in `mode`'s order. Returns the rooms entered.
+fn weighted_path(
+    state: &GameState,
+    start: RoomId,
+    end: RoomId,
+    mode: RouteMode,
+    enter_cost: &[i32],
+    danger_of: &[u32],
+) -> Result<Vec<RoomId>, GameError> {
+    let key = |(ap, danger, len): (i32, u32, usize)| match mode {
+        RouteMode::Shortest => (ap as u32, danger, len),
+        RouteMode::Safest => (danger, ap as u32, len),
+    };
+
+    let mut best: Vec<Option<(i32, u32, usize)>> = vec![None; enter_cost.len()];
+    let mut parents: Vec<RoomId> = vec![start; enter_cost.len()];
+    let mut heap = BinaryHeap::new();
+    best[start as usize] = Some((0, 0, 0));
+    heap.push(Reverse((key((0, 0, 0)), start)));
+
+    while let Some(Reverse((k, current))) = heap.pop() {
+        let Some(reached) = best[current as usize] else {
+            continue;
+        };
+        if k != key(reached) {
+            continue;
+        }
+        if current == end {
+            break;
+        }
+        let Some(room) = state.map.rooms.get(&current) else {
+            continue;
+        };
+        let (ap, danger, len) = reached;
+        for &neighbor in &room.neighbors {
+            let n = neighbor as usize;
+            let next = (ap + enter_cost[n], danger + danger_of[n], len + 1);
+            if best[n].is_none_or(|old| key(next) < key(old)) {
+                best[n] = Some(next);
+                parents[n] = current;
+                heap.push(Reverse((key(next), neighbor)));
+            }
+        }
+    }
+
+    if best[end as usize].is_none() {
+        return Err(GameError::InvalidMove);
+    }
+    let mut steps = vec![];
+    let mut curr = end;
+    while curr != start {
+        steps.push(curr);
+        curr = parents[curr as usize];
+    }
+    steps.reverse();
+    Ok(steps)
+}
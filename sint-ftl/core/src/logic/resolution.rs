@@ -188,55 +188,61 @@ pub fn process_round_end(state: &mut GameState) {
 pub fn resolve_proposal_queue(state: &mut GameState, simulation: bool) {
     let queue = std::mem::take(&mut state.proposal_queue);
 
-    for proposal in queue {
-        let player_id = &proposal.player_id;
+    for proposal in &queue {
+        resolve_proposal(state, proposal, simulation);
+    }
+}
 
-        if !state.players.contains_key(player_id) {
-            continue;
-        }
+/// Resolves a single proposal that has already been taken off the queue.
+/// Proposals resolve in order, so a queue can be resolved one proposal at a time.
+pub(crate) fn resolve_proposal(state: &mut GameState, proposal: &ProposedAction, simulation: bool) {
+    let player_id = &proposal.player_id;
 
-        // 1. Card Resolution Hook (RNG / Dynamic Blocks)
-        let mut blocked_by_card = false;
-        let active_ids: Vec<CardId> = state.active_situations.iter().map(|c| c.id).collect();
+    if !state.players.contains_key(player_id) {
+        return;
+    }
 
-        if !simulation {
-            for card_id in active_ids {
-                if let Err(e) =
-                    get_behavior(card_id).check_resolution(state, player_id, &proposal.action)
-                {
-                    debug!("Action Skipped: Blocked by card {:?}: {}", card_id, e);
-                    blocked_by_card = true;
-                    break;
-                }
+    // 1. Card Resolution Hook (RNG / Dynamic Blocks)
+    let mut blocked_by_card = false;
+    let active_ids: Vec<CardId> = state.active_situations.iter().map(|c| c.id).collect();
+
+    if !simulation {
+        for card_id in active_ids {
+            if let Err(e) =
+                get_behavior(card_id).check_resolution(state, player_id, &proposal.action)
+            {
+                debug!("Action Skipped: Blocked by card {:?}: {}", card_id, e);
+                blocked_by_card = true;
+                break;
             }
         }
+    }
 
-        if blocked_by_card {
-            // Refund Logic (Generic)
+    if blocked_by_card {
+        // Refund Logic (Generic)
+        let cost = action_cost(state, player_id, &proposal.action);
+        if let Some(p) = state.players.get_mut(player_id) {
+            p.ap += cost;
+        }
+        return;
+    }
+
+    // 2. Handler Execution
+    let handler = get_handler(&proposal.action);
+    match handler.execute(state, player_id, simulation) {
+        Ok(_) => {
+            // Success
+        }
+        Err(e) => {
+            debug!(
+                "Action Skipped: Player {} failed to execute {:?}: {}",
+                player_id, proposal.action, e
+            );
+            // Refund
             let cost = action_cost(state, player_id, &proposal.action);
             if let Some(p) = state.players.get_mut(player_id) {
                 p.ap += cost;
             }
-            continue;
-        }
-
-        // 2. Handler Execution
-        let handler = get_handler(&proposal.action);
-        match handler.execute(state, player_id, simulation) {
-            Ok(_) => {
-                // Success
-            }
-            Err(e) => {
-                debug!(
-                    "Action Skipped: Player {} failed to execute {:?}: {}",
-                    player_id, proposal.action, e
-                );
-                // Refund
-                let cost = action_cost(state, player_id, &proposal.action);
-                if let Some(p) = state.players.get_mut(player_id) {
-                    p.ap += cost;
-                }
-            }
         }
     }
 }
@@ -0,0 +1,332 @@
+//! Fluent construction of mid-game states for tests, puzzles and tutorials.
+//!
+//! ```
+//! use sint_core::{scenario::ScenarioBuilder, types::*};
+//!
+//! let state = ScenarioBuilder::new(&["P1", "P2"], 12345)
+//!     .player_at_system("P1", SystemType::Kitchen)
+//!     .fire_at_system(SystemType::Kitchen)
+//!     .ap("P2", 1)
+//!     .timebomb(CardId::Stowaway, 1)
+//!     .telegraph_system(SystemType::Cannons)
+//!     .build();
+//! assert_eq!(state.phase, GamePhase::TacticalPlanning);
+//! ```
+//!
+//! Builder methods panic on unknown players, rooms or systems, like a failed
+//! assertion in the test that wrote them.
+
+use crate::logic::{GameLogic, cards::get_behavior, find_room_with_system_in_map};
+use crate::types::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A named state, stored as a JSON fixture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub state: GameState,
+}
+
+impl Scenario {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+pub struct ScenarioBuilder {
+    name: String,
+    description: String,
+    state: GameState,
+}
+
+impl ScenarioBuilder {
+    /// A fresh game on the Star layout, already in `TacticalPlanning`.
+    pub fn new(player_ids: &[&str], seed: u64) -> Self {
+        Self::with_layout(player_ids, seed, MapLayout::Star)
+    }
+
+    pub fn with_layout(player_ids: &[&str], seed: u64, layout: MapLayout) -> Self {
+        let ids = player_ids.iter().map(|&id| id.to_owned()).collect();
+        let mut state = GameLogic::new_game_with_layout(ids, seed, layout);
+        state.phase = GamePhase::TacticalPlanning;
+        Self::from_state(state)
+    }
+
+    pub fn from_state(state: GameState) -> Self {
+        ScenarioBuilder {
+            name: String::new(),
+            description: String::new(),
+            state,
+        }
+    }
+
+    /// Continues from a saved fixture.
+    pub fn from_scenario(scenario: Scenario) -> Self {
+        ScenarioBuilder {
+            name: scenario.name,
+            description: scenario.description,
+            state: scenario.state,
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    /// The room holding `system` on this layout.
+    pub fn room_of(&self, system: SystemType) -> RoomId {
+        find_room_with_system_in_map(&self.state.map, system)
+            .unwrap_or_else(|| panic!("No {:?} room on this layout", system))
+    }
+
+    fn player(&mut self, player_id: &str) -> &mut Player {
+        self.state
+            .players
+            .get_mut(player_id)
+            .unwrap_or_else(|| panic!("Unknown player {}", player_id))
+    }
+
+    fn room(&mut self, room_id: RoomId) -> &mut Room {
+        self.state
+            .map
+            .rooms
+            .get_mut(&room_id)
+            .unwrap_or_else(|| panic!("Unknown room {}", room_id))
+    }
+
+    // --- Game ---
+
+    pub fn phase(mut self, phase: GamePhase) -> Self {
+        self.state.phase = phase;
+        self
+    }
+
+    pub fn hull(mut self, hull: i32) -> Self {
+        self.state.hull_integrity = hull;
+        self
+    }
+
+    pub fn enemy_hp(mut self, hp: i32) -> Self {
+        self.state.enemy.hp = hp;
+        self
+    }
+
+    pub fn shields(mut self, active: bool) -> Self {
+        self.state.shields_active = active;
+        self
+    }
+
+    pub fn evasion(mut self, active: bool) -> Self {
+        self.state.evasion_active = active;
+        self
+    }
+
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.state.rng_seed = seed;
+        self
+    }
+
+    // --- Players ---
+
+    pub fn player_at(mut self, player_id: &str, room_id: RoomId) -> Self {
+        self.room(room_id);
+        self.player(player_id).room_id = room_id;
+        self
+    }
+
+    pub fn player_at_system(self, player_id: &str, system: SystemType) -> Self {
+        let room = self.room_of(system);
+        self.player_at(player_id, room)
+    }
+
+    /// Sets HP and keeps the Fainted status consistent with it.
+    pub fn hp(mut self, player_id: &str, hp: i32) -> Self {
+        let p = self.player(player_id);
+        p.hp = hp;
+        p.status.retain(|s| *s != PlayerStatus::Fainted);
+        if hp <= 0 {
+            p.status.push(PlayerStatus::Fainted);
+            p.ap = 0;
+        }
+        self
+    }
+
+    pub fn ap(mut self, player_id: &str, ap: i32) -> Self {
+        self.player(player_id).ap = ap;
+        self
+    }
+
+    pub fn give(mut self, player_id: &str, item: ItemType) -> Self {
+        self.player(player_id).inventory.push(item);
+        self
+    }
+
+    pub fn status(mut self, player_id: &str, status: PlayerStatus) -> Self {
+        self.player(player_id).status.push(status);
+        self
+    }
+
+    pub fn ready(mut self, player_id: &str, ready: bool) -> Self {
+        self.player(player_id).is_ready = ready;
+        self
+    }
+
+    // --- Rooms ---
+
+    pub fn hazard(mut self, room_id: RoomId, hazard: HazardType) -> Self {
+        self.room(room_id).add_hazard(hazard);
+        self
+    }
+
+    pub fn fire(self, room_id: RoomId) -> Self {
+        self.hazard(room_id, HazardType::Fire)
+    }
+
+    pub fn water(self, room_id: RoomId) -> Self {
+        self.hazard(room_id, HazardType::Water)
+    }
+
+    pub fn fire_at_system(self, system: SystemType) -> Self {
+        let room = self.room_of(system);
+        self.fire(room)
+    }
+
+    pub fn water_at_system(self, system: SystemType) -> Self {
+        let room = self.room_of(system);
+        self.water(room)
+    }
+
+    pub fn item(mut self, room_id: RoomId, item: ItemType) -> Self {
+        self.room(room_id).add_item(item);
+        self
+    }
+
+    pub fn system_health(mut self, room_id: RoomId, health: u32) -> Self {
+        let room = self.room(room_id);
+        room.system_health = health;
+        if health == 0 {
+            room.is_broken = true;
+        }
+        self
+    }
+
+    // --- Cards ---
+
+    /// Puts a Situation or Timebomb into play without running its activation hook.
+    /// The card is taken out of the deck and discard pile.
+    pub fn card(mut self, card_id: CardId) -> Self {
+        self.take_card(card_id);
+        let card = get_behavior(card_id).get_struct();
+        self.state.active_situations.push(card);
+        self
+    }
+
+    /// Like `card`, but runs `on_activate` as if the card had just been drawn.
+    pub fn activate(mut self, card_id: CardId) -> Self {
+        self = self.card(card_id);
+        get_behavior(card_id).on_activate(&mut self.state);
+        self
+    }
+
+    /// Puts a Timebomb into play with a custom countdown.
+    pub fn timebomb(mut self, card_id: CardId, rounds_left: u32) -> Self {
+        self = self.card(card_id);
+        let card = self.state.active_situations.last_mut().unwrap();
+        match &mut card.card_type {
+            CardType::Timebomb { rounds_left: r } => *r = rounds_left,
+            other => panic!("{:?} is not a Timebomb ({:?})", card_id, other),
+        }
+        self
+    }
+
+    /// Replaces the draw pile. The last card is drawn first.
+    pub fn deck(mut self, cards: &[CardId]) -> Self {
+        for &card in cards {
+            self.take_card(card);
+        }
+        self.state.deck = cards.to_vec();
+        self
+    }
+
+    /// Empties the draw pile so no new events come up.
+    pub fn no_events(mut self) -> Self {
+        self.state.deck.clear();
+        self
+    }
+
+    fn take_card(&mut self, card_id: CardId) {
+        self.state.deck.retain(|c| *c != card_id);
+        self.state.discard.retain(|c| *c != card_id);
+        self.state.active_situations.retain(|c| c.id != card_id);
+    }
+
+    // --- Enemy ---
+
+    /// Telegraphs a Fireball at `room_id`.
+    pub fn telegraph(mut self, room_id: RoomId) -> Self {
+        let system = self.room(room_id).system;
+        self.telegraph_attack(EnemyAttack {
+            target_room: Some(room_id),
+            target_system: system,
+            effect: AttackEffect::Fireball,
+        })
+    }
+
+    pub fn telegraph_system(self, system: SystemType) -> Self {
+        let room = self.room_of(system);
+        self.telegraph(room)
+    }
+
+    pub fn telegraph_attack(mut self, attack: EnemyAttack) -> Self {
+        self.state.enemy.next_attack = Some(attack);
+        self
+    }
+
+    pub fn no_telegraph(mut self) -> Self {
+        self.state.enemy.next_attack = None;
+        self
+    }
+
+    // --- Output ---
+
+    pub fn build(self) -> GameState {
+        self.state
+    }
+
+    pub fn build_scenario(self) -> Scenario {
+        Scenario {
+            name: self.name,
+            description: self.description,
+            state: self.state,
+        }
+    }
+
+    pub fn save(self, path: impl AsRef<Path>) -> std::io::Result<Scenario> {
+        let scenario = self.build_scenario();
+        scenario.save(path)?;
+        Ok(scenario)
+    }
+}
@@ -52,8 +52,10 @@ pub struct GameState {
     /// The Enemy (Boss)
     pub enemy: Enemy,
 
-    /// Chat History (Event Sourcing derived or stored)
-    pub chat_log: Vec<ChatMessage>,
+    /// Chat produced by the last applied action. Peers record it in their own
+    /// `ChatLog` (see `crate::chat`), so history is not part of the state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chat: Vec<ChatMessage>,
 
     // --- Temporary Status Flags (Reset each round) ---
     pub shields_active: bool,
@@ -421,11 +423,37 @@ pub enum AttackEffect {
 
 // --- Actions & Events ---
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default, Hash)]
+pub enum ChatChannel {
+    /// Players in the game.
+    #[default]
+    Crew,
+    /// Connections in the room that have not joined the game.
+    Spectators,
+    /// Messages from the rules themselves (sender `SYSTEM`).
+    System,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 pub struct ChatMessage {
     pub sender: PlayerId,
     pub text: String,
+    /// Unix milliseconds at which the server sequenced the event that produced the
+    /// message. 0 until the message is recorded in a `ChatLog`.
     pub timestamp: u64,
+    #[serde(default)]
+    pub channel: ChatChannel,
+}
+
+impl ChatMessage {
+    pub fn system(text: impl Into<String>) -> Self {
+        ChatMessage {
+            sender: "SYSTEM".to_owned(),
+            text: text.into(),
+            timestamp: 0,
+            channel: ChatChannel::System,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
@@ -452,8 +480,16 @@ pub enum Action {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 #[serde(tag = "type", content = "payload")]
 pub enum GameAction {
-    /// Move to an adjacent room (Costs 1 AP)
-    Move { to_room: RoomId },
+    /// Move to a room (Costs 1 AP per room entered). Distant rooms are reached
+    /// along the route `mode` picks, which may not end the round in a burning
+    /// room unless `force` is set (see `logic::pathfinding::find_route`).
+    Move {
+        to_room: RoomId,
+        #[serde(default, skip_serializing_if = "RouteMode::is_shortest")]
+        mode: RouteMode,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        force: bool,
+    },
 
     /// Kitchen: Create Peppernuts (Costs 1 AP)
     Bake,
@@ -498,6 +534,33 @@ pub enum GameAction {
     Undo { action_id: Uuid },
 }
 
+impl GameAction {
+    /// A `Move` along the shortest route, without `force`.
+    pub fn move_to(to_room: RoomId) -> Self {
+        GameAction::Move {
+            to_room,
+            mode: RouteMode::Shortest,
+            force: false,
+        }
+    }
+}
+
+/// Which criterion `find_route` optimizes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Hash)]
+pub enum RouteMode {
+    /// Fewest AP, then least hazard exposure.
+    #[default]
+    Shortest,
+    /// Least hazard exposure, then fewest AP.
+    Safest,
+}
+
+impl RouteMode {
+    fn is_shortest(&self) -> bool {
+        *self == RouteMode::Shortest
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Hash)]
 #[serde(tag = "type", content = "payload")]
 pub enum MetaAction {
@@ -0,0 +1,145 @@
+//! TypeScript declarations generated from the JSON schema, for the `wasm` bindings.
+
+use serde_json::{Map, Value};
+
+/// Renders every schema definition as an exported TypeScript type.
+///
+/// 64-bit integers (`rng_seed`, `sequence_id`) are declared as `number`, which is
+/// what `JSON.parse` produces. They lose precision above 2^53, so states should be
+/// passed back to the bindings as the JSON text they returned.
+pub fn from_schema(schema: &Value) -> String {
+    let mut out = String::from("// Generated from the sint-core JSON schema. Do not edit.\n");
+    let Some(defs) = schema.get("$defs").and_then(Value::as_object) else {
+        return out;
+    };
+    for (name, def) in defs {
+        out.push('\n');
+        push_doc(&mut out, def, "");
+        out.push_str(&format!("export type {} = {};\n", name, render(def, "")));
+    }
+    out
+}
+
+fn push_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(doc) = schema.get("description").and_then(Value::as_str) {
+        out.push_str(&format!("{}/** {} */\n", indent, doc.replace("*/", "*\\/")));
+    }
+}
+
+fn render(schema: &Value, indent: &str) -> String {
+    let Some(obj) = schema.as_object() else {
+        // `true` accepts anything, `false` nothing.
+        return if schema == &Value::Bool(false) {
+            "never".to_owned()
+        } else {
+            "unknown".to_owned()
+        };
+    };
+
+    if let Some(target) = obj.get("$ref").and_then(Value::as_str) {
+        return target.rsplit('/').next().unwrap_or(target).to_owned();
+    }
+    if let Some(value) = obj.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = obj.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string));
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = obj.get(key).and_then(Value::as_array) {
+            return union(variants.iter().map(|v| render(v, indent)));
+        }
+    }
+
+    match obj.get("type") {
+        Some(Value::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|t| render_type(t, obj, indent)),
+        ),
+        Some(Value::String(t)) => render_type(t, obj, indent),
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn render_type(ty: &str, obj: &Map<String, Value>, indent: &str) -> String {
+    match ty {
+        "string" => "string".to_owned(),
+        "integer" | "number" => "number".to_owned(),
+        "boolean" => "boolean".to_owned(),
+        "null" => "null".to_owned(),
+        "array" => {
+            if let Some(items) = obj.get("prefixItems").and_then(Value::as_array) {
+                let items: Vec<_> = items.iter().map(|i| render(i, indent)).collect();
+                format!("[{}]", items.join(", "))
+            } else {
+                let item = obj
+                    .get("items")
+                    .map(|i| render(i, indent))
+                    .unwrap_or_else(|| "unknown".to_owned());
+                if item.contains(' ') {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+        }
+        "object" => render_object(obj, indent),
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn render_object(obj: &Map<String, Value>, indent: &str) -> String {
+    if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+        let required: Vec<&str> = obj
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let inner = format!("{}  ", indent);
+        let mut out = String::from("{\n");
+        for (name, prop) in props {
+            push_doc(&mut out, prop, &inner);
+            let optional = if required.contains(&name.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            out.push_str(&format!(
+                "{}{}{}: {};\n",
+                inner,
+                name,
+                optional,
+                render(prop, &inner)
+            ));
+        }
+        out.push_str(indent);
+        out.push('}');
+        return out;
+    }
+
+    // Maps: `FieldMap` (keyed by id) and `SmallMap` (keyed by stringified room id).
+    let value = obj
+        .get("additionalProperties")
+        .filter(|v| v.is_object())
+        .or_else(|| {
+            obj.get("patternProperties")
+                .and_then(Value::as_object)
+                .and_then(|p| p.values().next())
+        });
+    match value {
+        Some(value) => format!("Record<string, {}>", render(value, indent)),
+        None => "Record<string, unknown>".to_owned(),
+    }
+}
+
+fn union(parts: impl Iterator<Item = String>) -> String {
+    let mut parts: Vec<String> = parts.collect();
+    parts.dedup();
+    if parts.is_empty() {
+        "never".to_owned()
+    } else {
+        parts.join(" | ")
+    }
+}
@@ -0,0 +1,60 @@
+//! JavaScript bindings (`wasm` feature).
+//!
+//! States and actions cross the boundary as JSON text. `rng_seed` is a full 64-bit
+//! value, so front-ends should keep the string they got back and only `JSON.parse`
+//! it for display; the shapes are described by `getTypescriptDeclarations()`.
+
+use crate::{logic::GameLogic, types::*};
+use wasm_bindgen::prelude::*;
+
+fn parse<T: serde::de::DeserializeOwned>(json: &str, what: &str) -> Result<T, JsError> {
+    serde_json::from_str(json).map_err(|e| JsError::new(&format!("Invalid {}: {}", what, e)))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = newGame)]
+pub fn new_game(player_ids: Vec<String>, seed: u64) -> Result<String, JsError> {
+    to_json(&GameLogic::new_game(player_ids, seed))
+}
+
+#[wasm_bindgen(js_name = applyAction)]
+pub fn apply_action(
+    state_json: &str,
+    player_id: &str,
+    action_json: &str,
+    seed: Option<u64>,
+) -> Result<String, JsError> {
+    let state: GameState = parse(state_json, "state")?;
+    let action: Action = parse(action_json, "action")?;
+    let new_state = GameLogic::apply_action(state, player_id, action, seed)
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    to_json(&new_state)
+}
+
+#[wasm_bindgen(js_name = getValidActions)]
+pub fn get_valid_actions(state_json: &str, player_id: &str) -> Result<String, JsError> {
+    let state: GameState = parse(state_json, "state")?;
+    to_json(&GameLogic::get_valid_actions(&state, player_id))
+}
+
+#[wasm_bindgen(js_name = actionCost)]
+pub fn action_cost(state_json: &str, player_id: &str, action_json: &str) -> Result<i32, JsError> {
+    let state: GameState = parse(state_json, "state")?;
+    let action: GameAction = parse(action_json, "action")?;
+    Ok(crate::logic::actions::action_cost(
+        &state, player_id, &action,
+    ))
+}
+
+#[wasm_bindgen(js_name = getSchemaJson)]
+pub fn get_schema_json() -> String {
+    crate::export_schema()
+}
+
+#[wasm_bindgen(js_name = getTypescriptDeclarations)]
+pub fn get_typescript_declarations() -> String {
+    crate::export_typescript()
+}
@@ -0,0 +1,236 @@
+//! Zobrist keys for game states.
+//!
+//! A state's key is the XOR of one key per feature (a player's room, the n-th
+//! fire in a room, the card at deck position 3, the RNG seed, ...). Feature keys
+//! are derived with SplitMix64 instead of a stored random table, so they are the
+//! same on every platform and in every build. Because XOR is its own inverse, a
+//! key can be updated when one feature changes:
+//!
+//! ```
+//! # use sint_core::{GameLogic, zobrist};
+//! let mut state = GameLogic::new_game(vec!["P1".to_owned()], 7);
+//! let before = state.zobrist();
+//! let old = zobrist::player_key(0, &state.players["P1"]);
+//! state.players.get_mut("P1").unwrap().ap -= 1;
+//! let new = zobrist::player_key(0, &state.players["P1"]);
+//! assert_eq!(before ^ old ^ new, state.zobrist());
+//! ```
+//!
+//! `sequence_id` and the chat log are not part of the key: two states reached by
+//! different move orders (or with different chatter) hash the same, which is what
+//! transposition tables want. Desync checks compare the sequence id separately.
+//!
+//! The key is not stored on the state: `GameState::zobrist` computes it on each
+//! call, so it is right however the state was edited.
+
+use crate::types::*;
+use std::hash::{Hash, Hasher};
+
+const TAG_PHASE: u64 = 1;
+const TAG_TURN: u64 = 2;
+const TAG_HULL: u64 = 3;
+const TAG_BOSS: u64 = 4;
+const TAG_LAYOUT: u64 = 5;
+const TAG_RNG: u64 = 6;
+const TAG_FLAGS: u64 = 7;
+const TAG_ENEMY: u64 = 8;
+const TAG_ENEMY_HP: u64 = 9;
+const TAG_ATTACK: u64 = 10;
+const TAG_EVENT: u64 = 11;
+const TAG_PLAYER: u64 = 12;
+const TAG_PLAYER_ROOM: u64 = 13;
+const TAG_PLAYER_HP: u64 = 14;
+const TAG_PLAYER_AP: u64 = 15;
+const TAG_PLAYER_READY: u64 = 16;
+const TAG_INVENTORY: u64 = 17;
+const TAG_STATUS: u64 = 18;
+const TAG_ROOM_HEALTH: u64 = 19;
+const TAG_ROOM_BROKEN: u64 = 20;
+const TAG_HAZARD: u64 = 21;
+const TAG_ITEM: u64 = 22;
+const TAG_SITUATION: u64 = 23;
+const TAG_DECK: u64 = 24;
+const TAG_DISCARD: u64 = 25;
+const TAG_PROPOSAL: u64 = 26;
+const TAG_ROOM: u64 = 27;
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The random key for `(tag, a, b)`.
+fn key(tag: u64, a: u64, b: u64) -> u64 {
+    splitmix64(splitmix64(splitmix64(tag) ^ a) ^ b)
+}
+
+/// FNV-1a, for features that are not plain integers (strings, actions, cards).
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+fn fnv<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv(0xcbf29ce484222325);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Key for the global scalars: phase, turn, hull, boss, layout, flags, enemy and latest event.
+pub fn globals_key(state: &GameState) -> u64 {
+    let flags = state.shields_active as u64
+        | (state.evasion_active as u64) << 1
+        | (state.is_resting as u64) << 2;
+    key(TAG_PHASE, fnv(&state.phase), 0)
+        ^ key(TAG_TURN, state.turn_count as u64, 0)
+        ^ key(TAG_HULL, state.hull_integrity as i64 as u64, 0)
+        ^ key(TAG_BOSS, state.boss_level as u64, 0)
+        ^ key(TAG_LAYOUT, fnv(&state.layout), 0)
+        ^ key(TAG_FLAGS, flags, 0)
+        ^ enemy_key(&state.enemy)
+        ^ key(
+            TAG_EVENT,
+            fnv(&state.latest_event.as_ref().map(|c| c.id)),
+            0,
+        )
+}
+
+/// Key for the RNG seed, so states with different futures never collide.
+pub fn rng_key(rng_seed: u64) -> u64 {
+    key(TAG_RNG, rng_seed, 0)
+}
+
+pub fn enemy_key(enemy: &Enemy) -> u64 {
+    key(
+        TAG_ENEMY,
+        fnv(&(&enemy.name, enemy.max_hp, &enemy.state)),
+        0,
+    ) ^ key(TAG_ENEMY_HP, enemy.hp as i64 as u64, 0)
+        ^ key(TAG_ATTACK, fnv(&enemy.next_attack), 0)
+}
+
+/// Key for the player at `index` in the (id-sorted) player list.
+pub fn player_key(index: usize, player: &Player) -> u64 {
+    let i = index as u64;
+    let mut k = key(TAG_PLAYER, i, fnv(&(&player.id, &player.name)))
+        ^ key(TAG_PLAYER_ROOM, i, player.room_id as u64)
+        ^ key(TAG_PLAYER_HP, i, player.hp as i64 as u64)
+        ^ key(TAG_PLAYER_AP, i, player.ap as i64 as u64)
+        ^ key(TAG_PLAYER_READY, i, player.is_ready as u64);
+    // Inventory order matters: Drop and Throw refer to items by slot.
+    for (slot, item) in player.inventory.iter().enumerate() {
+        k ^= key(TAG_INVENTORY, i << 8 | slot as u64, item.as_usize() as u64);
+    }
+    // Statuses are unordered, but keyed by occurrence so duplicates do not cancel out.
+    for (n, status) in player.status.iter().enumerate() {
+        let occurrence = occurrences_before(&player.status, n) as u64;
+        k ^= key(TAG_STATUS, i << 8 | occurrence, fnv(status));
+    }
+    k
+}
+
+/// How many of `items[..n]` equal `items[n]`.
+fn occurrences_before<T: PartialEq>(items: &[T], n: usize) -> usize {
+    items[..n].iter().filter(|item| **item == items[n]).count()
+}
+
+/// Key for a room: its name, system, doors, hazards and floor items. Hazards and
+/// items are keyed by occurrence (first fire, second fire, ...), so their order
+/// does not matter.
+pub fn room_key(room: &Room) -> u64 {
+    let r = room.id as u64;
+    let mut k = key(
+        TAG_ROOM,
+        r,
+        fnv(&(&room.name, &room.system, &room.neighbors)),
+    ) ^ key(TAG_ROOM_HEALTH, r, room.system_health as u64)
+        ^ key(TAG_ROOM_BROKEN, r, room.is_broken as u64);
+    let mut hazards = [0u64; 2];
+    for hazard in &room.hazards {
+        let kind = match hazard {
+            HazardType::Fire => 0,
+            HazardType::Water => 1,
+        };
+        k ^= key(TAG_HAZARD, r << 8 | kind, hazards[kind as usize]);
+        hazards[kind as usize] += 1;
+    }
+    let mut items = [0u64; ItemType::COUNT];
+    for item in &room.items {
+        let kind = item.as_usize();
+        k ^= key(TAG_ITEM, r << 8 | kind as u64, items[kind]);
+        items[kind] += 1;
+    }
+    k
+}
+
+/// Key for an active situation, the `occurrence`-th with its card id. Situations
+/// are unordered.
+pub fn situation_key(card: &Card, occurrence: usize) -> u64 {
+    key(
+        TAG_SITUATION,
+        (card.id as u64) << 8 | occurrence as u64,
+        fnv(card),
+    )
+}
+
+pub fn deck_key(position: usize, card: CardId) -> u64 {
+    key(TAG_DECK, position as u64, card as u64)
+}
+
+pub fn discard_key(position: usize, card: CardId) -> u64 {
+    key(TAG_DISCARD, position as u64, card as u64)
+}
+
+/// Key for a queued proposal. The proposal's UUID is not part of the key.
+pub fn proposal_key(position: usize, proposal: &ProposedAction) -> u64 {
+    key(
+        TAG_PROPOSAL,
+        position as u64,
+        fnv(&(&proposal.player_id, &proposal.action)),
+    )
+}
+
+/// The key of `state`, computed from scratch.
+pub fn state_key(state: &GameState) -> u64 {
+    let mut k = globals_key(state) ^ rng_key(state.rng_seed);
+    for (i, p) in state.players.values().enumerate() {
+        k ^= player_key(i, p);
+    }
+    for room in state.map.rooms.values() {
+        k ^= room_key(room);
+    }
+    let ids: Vec<CardId> = state.active_situations.iter().map(|c| c.id).collect();
+    for (n, card) in state.active_situations.iter().enumerate() {
+        k ^= situation_key(card, occurrences_before(&ids, n));
+    }
+    for (i, &card) in state.deck.iter().enumerate() {
+        k ^= deck_key(i, card);
+    }
+    for (i, &card) in state.discard.iter().enumerate() {
+        k ^= discard_key(i, card);
+    }
+    for (i, prop) in state.proposal_queue.iter().enumerate() {
+        k ^= proposal_key(i, prop);
+    }
+    k
+}
+
+impl GameState {
+    /// Zobrist key of this state. Covers everything except `sequence_id` and the
+    /// chat log, including the deck order and the RNG seed.
+    pub fn zobrist(&self) -> u64 {
+        state_key(self)
+    }
+}
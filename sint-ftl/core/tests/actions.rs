@@ -72,7 +72,7 @@ fn test_move_then_evade_valid() {
     let state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
+        Action::Game(GameAction::move_to(0)),
         None,
     )
     .unwrap();
@@ -80,9 +80,9 @@ fn test_move_then_evade_valid() {
     let state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move {
-            to_room: find_room_with_system_in_map(&state.map, SystemType::Engine).unwrap(),
-        }),
+        Action::Game(GameAction::move_to(
+            find_room_with_system_in_map(&state.map, SystemType::Engine).unwrap(),
+        )),
         None,
     )
     .unwrap();
@@ -164,7 +164,7 @@ fn test_move_in_lobby() {
     assert_eq!(player.ap, 2);
 
     // Try to move to Hallway (7)
-    let action = Action::Game(GameAction::Move { to_room: 0 });
+    let action = Action::Game(GameAction::move_to(0));
 
     // This should now FAIL because we are in Lobby
     let res = GameLogic::apply_action(state.clone(), "Player1", action, None);
@@ -207,8 +207,8 @@ fn test_lookout_action() {
     )
     .unwrap();
 
-    // Verify Chat Log contains "LOOKOUT REPORT"
-    let last_msg = state.chat_log.last().unwrap();
+    // Verify the resolution posted "LOOKOUT REPORT"
+    let last_msg = state.chat.last().unwrap();
     assert_eq!(last_msg.sender, "SYSTEM");
     assert!(last_msg.text.contains("LOOKOUT REPORT"));
 }
@@ -403,13 +403,8 @@ fn test_planning_loop() {
     assert_eq!(state.players["P1"].ap, 3);
 
     // 4. Move (AP 3 -> 2)
-    state = GameLogic::apply_action(
-        state,
-        "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
-        None,
-    )
-    .unwrap();
+    state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
     assert_eq!(state.players["P1"].ap, 2);
 
     // 5. Ready -> Execution
@@ -436,9 +431,9 @@ fn test_planning_loop() {
     state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move {
-            to_room: find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
-        }),
+        Action::Game(GameAction::move_to(
+            find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
+        )),
         None,
     )
     .unwrap();
@@ -465,13 +460,8 @@ fn test_planning_loop() {
     assert_eq!(state.phase, GamePhase::TacticalPlanning);
 
     // 10. Move again (AP 1 -> 0) - Move back to Hallway
-    state = GameLogic::apply_action(
-        state,
-        "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
-        None,
-    )
-    .unwrap();
+    state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
     assert_eq!(state.players["P1"].ap, 0);
 
     // 11. Ready -> Execution
@@ -511,7 +501,7 @@ fn test_projected_location_validation() {
     let has_move_7 = actions.iter().any(|a| {
         matches!(
             a,
-            Action::Game(GameAction::Move { to_room }) if *to_room == 0
+            Action::Game(GameAction::Move { to_room, .. }) if *to_room == 0
         )
     });
     assert!(has_move_7, "Should be able to move to Room 7");
@@ -520,7 +510,7 @@ fn test_projected_location_validation() {
     state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
+        Action::Game(GameAction::move_to(0)),
         None,
     )
     .unwrap();
@@ -533,7 +523,7 @@ fn test_projected_location_validation() {
     let has_move_7 = actions.iter().any(|a| {
         matches!(
             a,
-            Action::Game(GameAction::Move { to_room }) if *to_room == 0
+            Action::Game(GameAction::Move { to_room, .. }) if *to_room == 0
         )
     });
     assert!(
@@ -545,7 +535,7 @@ fn test_projected_location_validation() {
     let has_move_6 = actions.iter().any(|a| {
         matches!(
             a,
-            Action::Game(GameAction::Move { to_room }) if *to_room == find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap()
+            Action::Game(GameAction::Move { to_room, .. }) if *to_room == find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap()
         )
     });
     assert!(has_move_6, "Should be able to move to Room 6 (Kitchen)");
@@ -571,9 +561,9 @@ fn test_projected_system_availability() {
     state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move {
-            to_room: find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
-        }),
+        Action::Game(GameAction::move_to(
+            find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
+        )),
         None,
     )
     .unwrap();
@@ -593,13 +583,8 @@ fn test_projected_ap_exhaustion() {
 
     // P1 has 2 AP.
     // Queue Move (1 AP).
-    state = GameLogic::apply_action(
-        state,
-        "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
-        None,
-    )
-    .unwrap();
+    state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
 
     // Remaining AP: 1.
     let actions = get_valid_actions(&state, "P1");
@@ -613,9 +598,9 @@ fn test_projected_ap_exhaustion() {
     state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move {
-            to_room: find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
-        }),
+        Action::Game(GameAction::move_to(
+            find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap(),
+        )),
         None,
     )
     .unwrap();
@@ -697,13 +682,8 @@ fn test_undo_middle_of_chain() {
     // P1 has 2 AP.
 
     // 1. Queue Move 3 -> 7 (Cost 1)
-    state = GameLogic::apply_action(
-        state,
-        "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
-        None,
-    )
-    .unwrap();
+    state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
     assert_eq!(state.players["P1"].ap, 1);
     let id_move_7 = state.proposal_queue[0].id;
 
@@ -711,9 +691,9 @@ fn test_undo_middle_of_chain() {
     state = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move {
-            to_room: find_room_with_system_in_map(&state.map, SystemType::Bridge).unwrap(),
-        }),
+        Action::Game(GameAction::move_to(
+            find_room_with_system_in_map(&state.map, SystemType::Bridge).unwrap(),
+        )),
         None,
     )
     .unwrap();
@@ -735,7 +715,7 @@ fn test_undo_middle_of_chain() {
     // a. Queue length should be 1
     assert_eq!(state.proposal_queue.len(), 1);
     // b. Remaining action should be Move 7 -> 9
-    if let GameAction::Move { to_room } = &state.proposal_queue[0].action {
+    if let GameAction::Move { to_room, .. } = &state.proposal_queue[0].action {
         assert_eq!(
             *to_room,
             find_room_with_system_in_map(&state.map, SystemType::Bridge).unwrap()
@@ -36,9 +36,7 @@ fn test_blockade_prevents_move_to_cannons_in_valid_actions() {
     let actions = get_valid_actions(&state, "P1");
 
     // 6. Assert that "Move to Cannons" is NOT in the list
-    let move_to_cannons = Action::Game(GameAction::Move {
-        to_room: cannons_id,
-    });
+    let move_to_cannons = Action::Game(GameAction::move_to(cannons_id));
 
     let contains_illegal_move = actions.contains(&move_to_cannons);
     assert!(
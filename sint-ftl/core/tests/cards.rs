@@ -444,18 +444,13 @@ fn test_seagull_attack_block_move() {
     let res = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move { to_room: 6 }),
+        Action::Game(GameAction::move_to(6)),
         None,
     );
     assert!(res.is_err());
 
     state.players.get_mut("P1").unwrap().inventory.clear();
-    let res = GameLogic::apply_action(
-        state,
-        "P1",
-        Action::Game(GameAction::Move { to_room: 6 }),
-        None,
-    );
+    let res = GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(6)), None);
     assert!(res.is_ok());
 }
 
@@ -487,7 +482,7 @@ fn test_seasick_restriction() {
     let state_after_move = GameLogic::apply_action(
         state.clone(),
         "P1",
-        Action::Game(GameAction::Move { to_room: bow }),
+        Action::Game(GameAction::move_to(bow)),
         None,
     )
     .unwrap();
@@ -503,7 +498,7 @@ fn test_seasick_restriction() {
     let res = GameLogic::apply_action(
         state_after_bake,
         "P1",
-        Action::Game(GameAction::Move { to_room: 0 }),
+        Action::Game(GameAction::move_to(0)),
         None,
     );
     assert!(res.is_err(), "Seasick should block Move after Bake");
@@ -650,7 +645,7 @@ fn test_slippery_deck() {
     };
     state.active_situations.push(card);
 
-    let cost_move = action_cost(&state, "P1", &GameAction::Move { to_room: 0 });
+    let cost_move = action_cost(&state, "P1", &GameAction::move_to(0));
     assert_eq!(cost_move, 0);
 
     let cost_bake = action_cost(&state, "P1", &GameAction::Bake);
@@ -674,10 +669,10 @@ fn test_sticky_floor() {
     let kitchen = find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap();
     let bridge = find_room_with_system_in_map(&state.map, SystemType::Bridge).unwrap();
 
-    let cost_kitchen = action_cost(&state, "P1", &GameAction::Move { to_room: kitchen });
+    let cost_kitchen = action_cost(&state, "P1", &GameAction::move_to(kitchen));
     assert_eq!(cost_kitchen, 2);
 
-    let cost_other = action_cost(&state, "P1", &GameAction::Move { to_room: bridge });
+    let cost_other = action_cost(&state, "P1", &GameAction::move_to(bridge));
     assert_eq!(cost_other, 1);
 }
 
@@ -695,7 +690,7 @@ fn test_sugar_rush() {
     };
     state.active_situations.push(card);
 
-    let cost_move = action_cost(&state, "P1", &GameAction::Move { to_room: 0 });
+    let cost_move = action_cost(&state, "P1", &GameAction::move_to(0));
     assert_eq!(cost_move, 1);
 
     if let Some(p) = state.players.get_mut("P1") {
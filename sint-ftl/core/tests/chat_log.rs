@@ -0,0 +1,150 @@
+use sint_core::{
+    GameLogic,
+    chat::{ChatEntry, ChatLog},
+    types::*,
+};
+
+fn chat(state: GameState, player_id: &str, message: &str) -> GameState {
+    GameLogic::apply_action(
+        state,
+        player_id,
+        Action::Game(GameAction::Chat {
+            message: message.to_owned(),
+        }),
+        None,
+    )
+    .unwrap()
+}
+
+fn message(sender: &str, channel: ChatChannel) -> ChatMessage {
+    ChatMessage {
+        sender: sender.to_owned(),
+        text: format!("from {}", sender),
+        timestamp: 0,
+        channel,
+    }
+}
+
+#[test]
+fn test_chat_only_holds_the_last_action() {
+    let state = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let state = chat(state, "P1", "hello");
+    assert_eq!(state.chat.len(), 1);
+    assert_eq!(state.chat[0].channel, ChatChannel::Crew);
+
+    let state = GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::VoteReady { ready: false }),
+        None,
+    )
+    .unwrap();
+    assert!(state.chat.is_empty());
+}
+
+#[test]
+fn test_chat_from_outside_the_game_is_for_spectators() {
+    let state = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let state = chat(state, "Watcher", "go crew!");
+    assert_eq!(state.chat[0].channel, ChatChannel::Spectators);
+}
+
+#[test]
+fn test_record_stamps_messages_and_skips_replays() {
+    let state = chat(
+        GameLogic::new_game(vec!["P1".to_owned()], 12345),
+        "P1",
+        "hello",
+    );
+    let mut log = ChatLog::default();
+
+    let added = log.record(4, 1_700_000_000_000, &state.chat);
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].sequence_id, 4);
+    assert_eq!(added[0].message.timestamp, 1_700_000_000_000);
+
+    assert!(log.record(4, 1_700_000_000_500, &state.chat).is_empty());
+    assert!(log.record(2, 1_700_000_000_500, &state.chat).is_empty());
+    assert_eq!(log.len(), 1);
+}
+
+#[test]
+fn test_pages_walk_back_by_channel() {
+    let mut log = ChatLog::default();
+    for seq in 1..=5 {
+        log.record(seq, seq * 1000, &[message("P1", ChatChannel::Crew)]);
+    }
+    log.record(6, 6000, &[message("SYSTEM", ChatChannel::System)]);
+
+    let page = log.page(None, None, 2);
+    let seqs: Vec<_> = page.messages.iter().map(|e| e.sequence_id).collect();
+    assert_eq!(seqs, vec![5, 6]);
+    assert_eq!(page.before, Some(5));
+
+    let page = log.page(Some(ChatChannel::Crew), page.before, 10);
+    let seqs: Vec<_> = page.messages.iter().map(|e| e.sequence_id).collect();
+    assert_eq!(seqs, vec![1, 2, 3, 4]);
+    assert_eq!(page.before, None);
+
+    assert!(
+        log.page(Some(ChatChannel::Spectators), None, 10)
+            .messages
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_page_keeps_an_event_together() {
+    let mut log = ChatLog::default();
+    log.record(1, 1000, &[message("P1", ChatChannel::Crew)]);
+    log.record(
+        2,
+        2000,
+        &[
+            message("SYSTEM", ChatChannel::System),
+            message("SYSTEM", ChatChannel::System),
+        ],
+    );
+
+    let page = log.page(None, None, 1);
+    assert_eq!(page.messages.len(), 2);
+    assert_eq!(page.before, Some(2));
+}
+
+#[test]
+fn test_prepend_fills_in_older_history() {
+    let mut server = ChatLog::default();
+    for seq in 1..=4 {
+        server.record(seq, seq * 1000, &[message("P1", ChatChannel::Crew)]);
+    }
+
+    // A peer that synced at 2 and then saw events 3 and 4 itself
+    let mut peer = ChatLog::default();
+    peer.record(3, 3000, &[message("P1", ChatChannel::Crew)]);
+    peer.record(4, 4000, &[message("P1", ChatChannel::Crew)]);
+    peer.prepend(&server.page(None, None, 10).messages);
+    assert_eq!(peer, server);
+
+    let entries: Vec<ChatEntry> =
+        serde_json::from_str(&serde_json::to_string(server.entries()).unwrap()).unwrap();
+    assert_eq!(ChatLog::from_entries(entries), server);
+}
+
+#[test]
+fn test_trim_keeps_the_newest_entries() {
+    let mut log = ChatLog::default();
+    for seq in 1..=5 {
+        log.record(seq, seq * 1000, &[message("P1", ChatChannel::Crew)]);
+    }
+    log.trim(0);
+    assert_eq!(log.len(), 5);
+
+    log.trim(2);
+    let kept: Vec<u64> = log.entries().iter().map(|e| e.sequence_id).collect();
+    assert_eq!(kept, [4, 5]);
+    // Replays of trimmed events are still skipped
+    assert!(
+        log.record(3, 3000, &[message("P1", ChatChannel::Crew)])
+            .is_empty()
+    );
+}
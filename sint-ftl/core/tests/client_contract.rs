@@ -48,7 +48,7 @@ fn test_fire_blocks_system_action() {
         "Should NOT show Bake (Blocked by Fire)"
     );
     assert!(
-        has_action(&actions, &GameAction::Move { to_room: 0 }),
+        has_action(&actions, &GameAction::move_to(0)),
         "Should show Move (to Hub)"
     );
 }
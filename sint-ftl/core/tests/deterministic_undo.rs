@@ -33,7 +33,7 @@ fn test_deterministic_action_ids() {
     let state2 = state.clone();
 
     // Action 1: Move to Hallway
-    let action = Action::Game(GameAction::Move { to_room: 0 });
+    let action = Action::Game(GameAction::move_to(0));
 
     // Apply on State 1
     let state1_prime = GameLogic::apply_action(state1, "P1", action.clone(), None).unwrap();
@@ -54,9 +54,7 @@ fn test_deterministic_action_ids() {
 
     // Action 2: Subsequent Move
     let kitchen_id = find_room_with_system_in_map(&state1_prime.map, SystemType::Kitchen).unwrap();
-    let action3 = Action::Game(GameAction::Move {
-        to_room: kitchen_id,
-    });
+    let action3 = Action::Game(GameAction::move_to(kitchen_id));
 
     let state1_double = GameLogic::apply_action(state1_prime, "P1", action3.clone(), None).unwrap();
     let id1_next = state1_double.proposal_queue.last().unwrap().id;
@@ -71,6 +71,7 @@ fn test_revive_restores_1_hp_only() {
     if let Some(p) = state.players.get_mut("P2") {
         p.room_id = dormitory;
         p.hp = 0;
+        p.ap = 0;
         p.status.push(PlayerStatus::Fainted);
     }
 
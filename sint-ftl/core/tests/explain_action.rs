@@ -0,0 +1,214 @@
+use sint_core::{
+    GameLogic,
+    logic::{
+        actions::get_valid_actions,
+        cards::get_behavior,
+        explain::{BlockerSource, explain_action},
+        find_room_with_system_in_map,
+    },
+    types::*,
+};
+
+fn new_test_game() -> GameState {
+    let mut state = GameLogic::new_game(vec!["P1".to_owned(), "P2".to_owned()], 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    state.deck.clear();
+    state
+}
+
+fn room_of(state: &GameState, sys: SystemType) -> RoomId {
+    find_room_with_system_in_map(&state.map, sys).unwrap()
+}
+
+#[test]
+fn test_shoot_lists_every_prerequisite() {
+    let state = new_test_game();
+
+    // P1 starts in the Dormitory with an empty inventory.
+    let ex = explain_action(&state, "P1", &GameAction::Shoot);
+    assert!(!ex.allowed);
+    assert_eq!(ex.blockers.len(), 2, "{:?}", ex.blockers);
+    assert!(ex.blockers.iter().all(|b| b.source == BlockerSource::Rules));
+    assert!(ex.blockers[0].message.contains("requires"));
+    assert!(ex.blockers[1].message.contains("No ammo"));
+
+    let storage = room_of(&state, SystemType::Storage);
+    let cannons = room_of(&state, SystemType::Cannons);
+    assert_eq!(
+        ex.suggestions,
+        vec![
+            format!(
+                "Move to Storage (Room {}) and pick up a Peppernut first",
+                storage
+            ),
+            format!("Move to Cannons (Room {})", cannons),
+        ]
+    );
+}
+
+#[test]
+fn test_fire_blocks_system_action() {
+    let mut state = new_test_game();
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    state.players.get_mut("P1").unwrap().room_id = kitchen;
+    state
+        .map
+        .rooms
+        .get_mut(&kitchen)
+        .unwrap()
+        .hazards
+        .push(HazardType::Fire);
+
+    let ex = explain_action(&state, "P1", &GameAction::Bake);
+    assert!(!ex.allowed);
+    assert_eq!(ex.blockers.len(), 1);
+    assert_eq!(ex.blockers[0].source, BlockerSource::Rules);
+    assert_eq!(
+        ex.suggestions,
+        vec![format!("Extinguish the fire in Kitchen ({})", kitchen)]
+    );
+}
+
+#[test]
+fn test_card_blocker_suggests_resolution() {
+    let mut state = new_test_game();
+    let cannons = room_of(&state, SystemType::Cannons);
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.room_id = cannons;
+    p1.inventory.push(ItemType::Peppernut);
+    state
+        .active_situations
+        .push(get_behavior(CardId::JammedCannon).get_struct());
+
+    let ex = explain_action(&state, "P1", &GameAction::Shoot);
+    assert!(!ex.allowed);
+    assert!(
+        ex.blockers
+            .iter()
+            .any(|b| b.source == BlockerSource::Card(CardId::JammedCannon))
+    );
+    assert!(
+        ex.suggestions.iter().any(|s| s.starts_with("Resolve ")),
+        "{:?}",
+        ex.suggestions
+    );
+}
+
+#[test]
+fn test_ap_shortfall_names_cost_modifier() {
+    let mut state = new_test_game();
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.room_id = kitchen;
+    p1.ap = 1;
+    state
+        .active_situations
+        .push(get_behavior(CardId::SlipperyDeck).get_struct());
+
+    let ex = explain_action(&state, "P1", &GameAction::Bake);
+    assert!(!ex.allowed);
+    assert_eq!(ex.ap_cost, 2);
+    assert_eq!(ex.ap_available, 1);
+    assert_eq!(ex.blockers.len(), 1);
+    assert_eq!(ex.blockers[0].source, BlockerSource::ActionPoints);
+    assert!(
+        ex.blockers[0].message.contains("+1"),
+        "{}",
+        ex.blockers[0].message
+    );
+    assert!(
+        ex.suggestions
+            .contains(&"Wait for next round when AP refills".to_owned())
+    );
+}
+
+#[test]
+fn test_queued_move_blocks_later_action() {
+    let mut state = new_test_game();
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    state.players.get_mut("P1").unwrap().room_id = kitchen;
+
+    let neighbor = state.map.rooms[&kitchen].neighbors[0];
+    let state = GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::move_to(neighbor)),
+        None,
+    )
+    .unwrap();
+
+    let ex = explain_action(&state, "P1", &GameAction::Bake);
+    assert!(!ex.allowed);
+    assert_eq!(ex.blockers[0].source, BlockerSource::Queue);
+    assert!(
+        ex.suggestions
+            .iter()
+            .any(|s| s.starts_with("Undo the queued actions"))
+    );
+}
+
+#[test]
+fn test_wrong_phase_and_fainted() {
+    let mut state = new_test_game();
+    state.phase = GamePhase::EnemyAction;
+    state
+        .players
+        .get_mut("P1")
+        .unwrap()
+        .status
+        .push(PlayerStatus::Fainted);
+
+    let ex = explain_action(&state, "P1", &GameAction::Pass);
+    assert!(!ex.allowed);
+    assert!(ex.blockers.iter().any(|b| b.source == BlockerSource::Phase));
+    assert!(
+        ex.blockers
+            .iter()
+            .any(|b| b.source == BlockerSource::Player)
+    );
+
+    // Chat is always allowed.
+    let chat = GameAction::Chat {
+        message: "help".to_owned(),
+    };
+    assert!(explain_action(&state, "P1", &chat).allowed);
+}
+
+#[test]
+fn test_agrees_with_apply_action() {
+    let state = new_test_game();
+
+    for action in get_valid_actions(&state, "P1") {
+        if let Action::Game(game_action) = action {
+            let ex = explain_action(&state, "P1", &game_action);
+            assert!(ex.allowed, "{:?}: {}", game_action, ex.summary());
+        }
+    }
+
+    let candidates = [
+        GameAction::Bake,
+        GameAction::Shoot,
+        GameAction::RaiseShields,
+        GameAction::EvasiveManeuvers,
+        GameAction::Lookout,
+        GameAction::Extinguish,
+        GameAction::Repair,
+        GameAction::move_to(99),
+        GameAction::PickUp {
+            item_type: ItemType::Peppernut,
+        },
+    ];
+    for action in candidates {
+        let ex = explain_action(&state, "P1", &action);
+        let applied =
+            GameLogic::apply_action(state.clone(), "P1", Action::Game(action.clone()), None);
+        assert_eq!(
+            ex.allowed,
+            applied.is_ok(),
+            "{:?}: {} vs {:?}",
+            action,
+            ex.summary(),
+            applied.err()
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use sint_core::{
+    GameLogic,
+    logic::{find_room_with_system_in_map, forecast::forecast},
+    types::*,
+};
+
+fn setup_planning(target: SystemType) -> (GameState, RoomId) {
+    let mut state = GameLogic::new_game(vec!["P1".to_owned(), "P2".to_owned()], 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    state.deck.clear();
+
+    let room = find_room_with_system_in_map(&state.map, target).unwrap();
+    state.enemy.next_attack = Some(EnemyAttack {
+        target_room: Some(room),
+        target_system: Some(target),
+        effect: AttackEffect::Fireball,
+    });
+    (state, room)
+}
+
+#[test]
+fn test_telegraphed_hit_costs_hull() {
+    let (state, _) = setup_planning(SystemType::Kitchen);
+
+    let f = forecast(&state);
+    assert_eq!(f.hull_loss, vec![0.0, 1.0]);
+    assert!((f.expected_hull_loss - 1.0).abs() < 1e-9);
+    assert_eq!(f.game_over_risk, 0.0);
+}
+
+#[test]
+fn test_shields_block_the_attack() {
+    let (mut state, _) = setup_planning(SystemType::Kitchen);
+    state.shields_active = true;
+
+    let f = forecast(&state);
+    assert_eq!(f.hull_loss, vec![1.0]);
+    assert_eq!(f.expected_hull_loss, 0.0);
+}
+
+#[test]
+fn test_faint_risk_for_player_in_target_room() {
+    let (mut state, kitchen) = setup_planning(SystemType::Kitchen);
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.room_id = kitchen;
+    p1.hp = 1;
+
+    let f = forecast(&state);
+    assert_eq!(f.faint_risk["P1"], 1.0);
+    assert_eq!(f.faint_risk["P2"], 0.0);
+}
+
+#[test]
+fn test_queued_shot_splits_enemy_hp() {
+    let (mut state, _) = setup_planning(SystemType::Kitchen);
+    let cannons = find_room_with_system_in_map(&state.map, SystemType::Cannons).unwrap();
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.room_id = cannons;
+    p1.inventory.push(ItemType::Peppernut);
+    let hp = state.enemy.hp as usize;
+
+    let state = GameLogic::apply_action(state, "P1", Action::Game(GameAction::Shoot), None)
+        .expect("Shoot should be queued");
+
+    // Default hit threshold is 3 on a d6: 4 in 6 shots hit.
+    let f = forecast(&state);
+    let hit = f.enemy_hp[hp - 1];
+    assert!(hit > 0.55 && hit < 0.78, "hit chance {}", hit);
+    assert!((f.enemy_hp[hp] + hit - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_fire_spread_breaks_systems() {
+    let (mut state, kitchen) = setup_planning(SystemType::Kitchen);
+    state.shields_active = true;
+    let room = state.map.rooms.get_mut(&kitchen).unwrap();
+    room.system_health = 1;
+    room.hazards.push(HazardType::Fire);
+    room.hazards.push(HazardType::Fire);
+
+    let f = forecast(&state);
+    // Two fires destroy the Kitchen's last health point.
+    assert_eq!(f.break_risk[&kitchen], 1.0);
+    assert_eq!(f.hull_loss, vec![0.0, 1.0]);
+    assert!(f.expected_systems_broken >= 1.0);
+}
+
+#[test]
+fn test_forecast_is_deterministic() {
+    let (mut state, _) = setup_planning(SystemType::Kitchen);
+    state.enemy.next_attack = Some(EnemyAttack {
+        target_room: None,
+        target_system: None,
+        effect: AttackEffect::Hidden,
+    });
+    state
+        .active_situations
+        .push(sint_core::logic::cards::get_behavior(CardId::FogBank).get_struct());
+
+    let a = forecast(&state);
+    let b = forecast(&state);
+    assert_eq!(a, b);
+
+    // A hidden attack might hit or miss.
+    assert!(a.hull_loss.len() >= 2);
+    assert!(a.hull_loss[0] > 0.0);
+    assert!((a.hull_loss.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
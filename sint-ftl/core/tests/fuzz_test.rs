@@ -1,5 +1,5 @@
 use rand::{prelude::IndexedRandom, rng};
-use sint_core::{GameLogic, GamePhase, logic::actions::get_valid_actions};
+use sint_core::{GameLogic, GamePhase, invariants, logic::actions::get_valid_actions};
 
 #[test]
 fn test_fuzz_random_walk() {
@@ -41,6 +41,15 @@ fn test_fuzz_random_walk() {
         // Apply
         match GameLogic::apply_action(state.clone(), &pid, action.clone(), None) {
             Ok(new_state) => {
+                let violations = invariants::check(&new_state);
+                assert!(
+                    violations.is_empty(),
+                    "Step {}: {:?} by {} broke invariants: {:?}",
+                    i,
+                    action,
+                    pid,
+                    violations
+                );
                 state = new_state;
             }
             Err(e) => {
@@ -58,9 +58,7 @@ fn test_multiple_situation_cards_stack_effects() {
     // 4. Action & Assert: Attempt to move, which should fail.
     let start_room = player.room_id;
     let target_room = state.map.rooms.get(&start_room).unwrap().neighbors[0];
-    let move_action = Action::Game(GameAction::Move {
-        to_room: target_room,
-    });
+    let move_action = Action::Game(GameAction::move_to(target_room));
 
     let result = apply_action(state.clone(), &player_id, move_action);
     assert!(result.is_err(), "Move action should fail with 0 AP.");
@@ -98,9 +96,7 @@ fn test_hazard_blocks_card_effect() {
 
     // 2. Action: Attempt to move.
     let target_room = state.map.rooms.get(&room_id).unwrap().neighbors[0];
-    let move_action = Action::Game(GameAction::Move {
-        to_room: target_room,
-    });
+    let move_action = Action::Game(GameAction::move_to(target_room));
 
     let result = apply_action(state.clone(), &player_id, move_action);
 
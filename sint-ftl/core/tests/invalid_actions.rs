@@ -10,7 +10,7 @@ fn test_action_from_invalid_player_id() {
     state.phase = GamePhase::TacticalPlanning;
 
     // 2. Action: Attempt to perform an action with a player_id that does not exist.
-    let move_action = Action::Game(GameAction::Move { to_room: 1 }); // Room doesn't matter
+    let move_action = Action::Game(GameAction::move_to(1)); // Room doesn't matter
     let result = apply_action(state, "bogus_player", move_action);
 
     // 3. Assert: The action fails with a PlayerNotFound error.
@@ -34,7 +34,7 @@ fn test_move_to_nonexistent_room() {
     let player_id = state.players.keys().next().unwrap().clone();
 
     // 2. Action: Attempt to move to a room ID that is guaranteed not to exist.
-    let move_action = Action::Game(GameAction::Move { to_room: 999 });
+    let move_action = Action::Game(GameAction::move_to(999));
     let result = apply_action(state, &player_id, move_action);
 
     // 3. Assert: The action fails with an InvalidMove error.
@@ -42,12 +42,14 @@ proptest! {
         state.phase = GamePhase::EnemyAction;
 
         let dormitory_id = sint_core::logic::find_room_with_system_in_map(&state.map, SystemType::Dormitory).unwrap_or(0);
+        let away = state.map.rooms.keys().find(|&r| r != dormitory_id).unwrap();
 
         // Faint P1, keep P2 alive
         if let Some(p1) = state.players.get_mut("P1") {
             p1.hp = 0;
+            p1.ap = 0;
             p1.status.push(PlayerStatus::Fainted);
-            p1.room_id = 999; // Away from dormitory
+            p1.room_id = away; // Away from dormitory
         }
         if let Some(p2) = state.players.get_mut("P2") {
             p2.hp = 3;
@@ -117,6 +119,7 @@ proptest! {
 
         // Add Static Noise card
         let static_noise = sint_core::logic::cards::registry::get_behavior(CardId::StaticNoise).get_struct();
+        state.deck.retain(|&c| c != CardId::StaticNoise);
         state.active_situations.push(static_noise);
 
         // Try to chat with letters
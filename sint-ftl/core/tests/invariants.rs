@@ -0,0 +1,136 @@
+use sint_core::{
+    GameLogic,
+    invariants::{Violation, check},
+    types::*,
+};
+
+fn new_test_game() -> GameState {
+    let mut state = GameLogic::new_game(vec!["P1".to_owned(), "P2".to_owned()], 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    state
+}
+
+#[test]
+fn test_new_game_is_consistent() {
+    for layout in [MapLayout::Star, MapLayout::Torus] {
+        let state = GameLogic::new_game_with_layout(vec!["P1".to_owned()], 7, layout);
+        assert_eq!(check(&state), vec![]);
+    }
+}
+
+#[test]
+fn test_player_violations() {
+    let mut state = new_test_game();
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.ap = -1;
+    p1.hp = 4;
+    let p2 = state.players.get_mut("P2").unwrap();
+    p2.hp = 0;
+    p2.status.push(PlayerStatus::Fainted);
+    p2.ap = 1;
+    p2.room_id = 99;
+
+    let violations = check(&state);
+    assert_eq!(
+        violations,
+        vec![
+            Violation::NegativeAp {
+                player: "P1".to_owned(),
+                ap: -1
+            },
+            Violation::HpOutOfRange {
+                player: "P1".to_owned(),
+                hp: 4
+            },
+            Violation::FaintedWithAp {
+                player: "P2".to_owned(),
+                ap: 1
+            },
+            Violation::PlayerInMissingRoom {
+                player: "P2".to_owned(),
+                room: 99
+            },
+        ]
+    );
+    assert_eq!(violations[2].to_string(), "P2 is Fainted but has 1 AP");
+}
+
+#[test]
+fn test_fainted_status_must_match_hp() {
+    let mut state = new_test_game();
+    state.players.get_mut("P1").unwrap().hp = 0;
+
+    assert_eq!(
+        check(&state),
+        vec![Violation::FaintedMismatch {
+            player: "P1".to_owned(),
+            hp: 0,
+            fainted: false
+        }]
+    );
+}
+
+#[test]
+fn test_inventory_capacity() {
+    let mut state = new_test_game();
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.inventory
+        .extend([ItemType::Peppernut, ItemType::Extinguisher]);
+    assert!(check(&state).is_empty());
+
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.inventory.push(ItemType::Peppernut);
+    assert!(matches!(
+        check(&state)[..],
+        [Violation::InventoryOverCapacity {
+            peppernuts: 2,
+            special_items: 1,
+            ..
+        }]
+    ));
+
+    // A Wheelbarrow holds five Peppernuts but nothing else.
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.inventory.clear();
+    p1.inventory.push(ItemType::Wheelbarrow);
+    p1.inventory.extend([ItemType::Peppernut; 5]);
+    assert!(check(&state).is_empty());
+    state
+        .players
+        .get_mut("P1")
+        .unwrap()
+        .inventory
+        .push(ItemType::Keychain);
+    assert_eq!(check(&state).len(), 1);
+
+    // Two hands hold two special items, not three.
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.inventory.clear();
+    p1.inventory
+        .extend([ItemType::Extinguisher, ItemType::Keychain]);
+    assert!(check(&state).is_empty());
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.inventory.push(ItemType::Mitre);
+    assert_eq!(check(&state).len(), 1);
+}
+
+#[test]
+fn test_map_and_card_violations() {
+    let mut state = new_test_game();
+    state.hull_integrity = MAX_HULL + 1;
+    state.map.rooms.get_mut(&0).unwrap().neighbors.push(42);
+    let card = state.deck[0];
+    state.discard.push(card);
+
+    assert_eq!(
+        check(&state),
+        vec![
+            Violation::HullOutOfRange { hull: MAX_HULL + 1 },
+            Violation::MissingNeighbor {
+                room: 0,
+                neighbor: 42
+            },
+            Violation::DuplicateCard { card, count: 2 },
+        ]
+    );
+}
@@ -37,7 +37,7 @@ fn test_listing_card_lifecycle() {
     assert_eq!(state.players["P1"].ap, 7);
 
     // Verify costs
-    let cost_move = action_cost(&state, "P1", &GameAction::Move { to_room: 0 });
+    let cost_move = action_cost(&state, "P1", &GameAction::move_to(0));
     assert_eq!(cost_move, 1);
 
     // Other actions (e.g. Bake) usually 1, should be 2.
@@ -325,10 +325,12 @@ fn test_game_over_crew() {
     state.phase = GamePhase::TacticalPlanning;
 
     if let Some(p) = state.players.get_mut("P1") {
+        p.hp = 0;
         p.status.push(PlayerStatus::Fainted);
         p.ap = 0;
     }
     if let Some(p) = state.players.get_mut("P2") {
+        p.hp = 0;
         p.status.push(PlayerStatus::Fainted);
         p.ap = 0;
     }
@@ -424,6 +426,7 @@ fn test_revive_mechanics() {
     // Setup: P2 is Fainted in Kitchen. P1 is in Kitchen.
     if let Some(p2) = state.players.get_mut("P2") {
         p2.hp = 0;
+        p2.ap = 0;
         p2.status.push(PlayerStatus::Fainted);
         p2.room_id = kitchen_id;
     }
@@ -119,7 +119,7 @@ fn test_lights_out() {
     let state = create_test_state();
     let behavior = get_behavior(CardId::LightsOut);
 
-    let cost = behavior.modify_action_cost(&state, "P1", &GameAction::Move { to_room: 0 }, 1);
+    let cost = behavior.modify_action_cost(&state, "P1", &GameAction::move_to(0), 1);
     assert_eq!(cost, 2);
 }
 
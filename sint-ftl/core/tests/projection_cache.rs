@@ -0,0 +1,64 @@
+use rand::{SeedableRng, prelude::IndexedRandom, rngs::StdRng};
+use sint_core::{
+    GameLogic, GamePhase,
+    logic::actions::{clear_projection_cache, get_valid_actions},
+    types::*,
+};
+
+#[test]
+fn test_cached_projection_matches_uncached() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut state = GameLogic::new_game(
+        vec!["P1".to_owned(), "P2".to_owned(), "P3".to_owned()],
+        12345,
+    );
+
+    for _ in 0..500 {
+        if state.phase == GamePhase::GameOver || state.phase == GamePhase::Victory {
+            break;
+        }
+
+        let mut candidates = Vec::new();
+        for pid in state.players.keys() {
+            clear_projection_cache();
+            let cold = get_valid_actions(&state, pid);
+            let warm = get_valid_actions(&state, pid);
+            assert_eq!(cold, warm);
+            candidates.extend(cold.into_iter().map(|a| (pid.clone(), a)));
+        }
+
+        let Some((pid, action)) = candidates.choose(&mut rng).cloned() else {
+            break;
+        };
+
+        clear_projection_cache();
+        let cold = GameLogic::apply_action(state.clone(), &pid, action.clone(), None);
+        let warm = GameLogic::apply_action(state.clone(), &pid, action, None);
+        match (cold, warm) {
+            (Ok(cold), Ok(warm)) => {
+                assert_eq!(cold, warm);
+                state = warm;
+            }
+            (Err(cold), Err(warm)) => assert_eq!(cold.to_string(), warm.to_string()),
+            (cold, warm) => panic!("Cache changed the result: {:?} vs {:?}", cold, warm),
+        }
+    }
+}
+
+#[test]
+fn test_mutated_state_is_not_served_from_cache() {
+    let mut state = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    let state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
+
+    // Warm the cache, then change the state without touching sequence_id or the queue.
+    let before = get_valid_actions(&state, "P1");
+    let mut mutated = state.clone();
+    mutated.players.get_mut("P1").unwrap().ap = 0;
+
+    let after = get_valid_actions(&mutated, "P1");
+    clear_projection_cache();
+    assert_eq!(after, get_valid_actions(&mutated, "P1"));
+    assert_ne!(before, after);
+}
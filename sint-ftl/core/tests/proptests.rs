@@ -3,7 +3,7 @@ use rand::prelude::IndexedRandom;
 use rand::{SeedableRng, rngs::StdRng};
 use sint_core::logic::actions::get_valid_actions;
 use sint_core::small_map::SmallSet;
-use sint_core::{GameLogic, logic::resolution, types::*};
+use sint_core::{GameLogic, invariants, logic::resolution, types::*};
 
 proptest! {
     /// Test that any sequence of valid actions preserves basic game invariants.
@@ -52,31 +52,8 @@ proptest! {
             state = next_state.unwrap();
 
             // Property: Invariants must hold
-            prop_assert!(state.hull_integrity <= 20, "Hull exceeded MAX_HULL");
-
-            for p in state.players.values() {
-                prop_assert!(p.hp <= 3, "Player HP exceeded MAX_PLAYER_HP");
-                prop_assert!(p.ap >= 0, "Player AP dropped below zero");
-
-                // Fainted Status Invariant
-                if p.hp <= 0 {
-                    prop_assert!(p.status.contains(&PlayerStatus::Fainted), "Player {} has {} HP but is not Fainted", p.id, p.hp);
-                } else {
-                    prop_assert!(!p.status.contains(&PlayerStatus::Fainted), "Player {} has {} HP but is Fainted", p.id, p.hp);
-                }
-
-                // Inventory Limit Invariants
-                let nut_count = p.peppernut_count();
-                let special_count = p.special_item_count();
-                let has_wheelbarrow = p.has_wheelbarrow();
-
-                if has_wheelbarrow {
-                    prop_assert!(nut_count <= 5, "Player {} exceeded Wheelbarrow capacity ({} > 5)", p.id, nut_count);
-                    prop_assert_eq!(special_count, 1, "Player {} with Wheelbarrow should only have 1 special item", p.id);
-                } else {
-                    prop_assert!(nut_count + special_count <= 2, "Player {} exceeded hand capacity ({} + {} > 2)", p.id, nut_count, special_count);
-                }
-            }
+            let violations = invariants::check(&state);
+            prop_assert!(violations.is_empty(), "Invariants violated after {:?} by {}: {:?}", action, player_id, violations);
 
             // Disabled System Invariants (Check against PROJECTED state)
             {
@@ -354,6 +331,7 @@ proptest! {
 
         for p in state.players.values_mut() {
             p.hp = 0;
+            p.ap = 0;
             if !p.status.contains(&PlayerStatus::Fainted) {
                 p.status.push(PlayerStatus::Fainted);
             }
@@ -0,0 +1,238 @@
+use sint_core::{
+    GameError, GameLogic,
+    logic::{
+        cards::get_behavior,
+        find_room_with_system_in_map,
+        pathfinding::{RouteMode, find_path, find_route},
+    },
+    types::*,
+};
+
+fn setup(layout: MapLayout) -> GameState {
+    let mut state =
+        GameLogic::new_game_with_layout(vec!["P1".to_owned(), "P2".to_owned()], 12345, layout);
+    state.phase = GamePhase::TacticalPlanning;
+    state.deck.clear();
+    state
+}
+
+fn room_of(state: &GameState, sys: SystemType) -> RoomId {
+    find_room_with_system_in_map(&state.map, sys).unwrap()
+}
+
+#[test]
+fn test_safest_route_goes_around_fire() {
+    let mut state = setup(MapLayout::Torus);
+    let dorm = room_of(&state, SystemType::Dormitory);
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    let cargo = room_of(&state, SystemType::Cargo);
+    state
+        .map
+        .rooms
+        .get_mut(&cargo)
+        .unwrap()
+        .hazards
+        .push(HazardType::Fire);
+
+    let shortest = find_route(&state, "P1", dorm, kitchen, RouteMode::Shortest, false).unwrap();
+    assert_eq!(shortest.ap_cost, 4);
+    assert!(shortest.steps.contains(&cargo));
+    assert_eq!(shortest.danger, 3);
+
+    let safest = find_route(&state, "P1", dorm, kitchen, RouteMode::Safest, false).unwrap();
+    assert_eq!(safest.ap_cost, 8);
+    assert!(!safest.steps.contains(&cargo));
+    assert_eq!(safest.danger, 0);
+    assert_eq!(safest.steps.last(), Some(&kitchen));
+
+    // A Move queues the steps of the route its mode picks
+    let p1 = state.players.get_mut("P1").unwrap();
+    p1.room_id = dorm;
+    p1.ap = 8;
+    for (mode, route) in [(RouteMode::Shortest, shortest), (RouteMode::Safest, safest)] {
+        let next = GameLogic::apply_action(
+            state.clone(),
+            "P1",
+            Action::Game(GameAction::Move {
+                to_room: kitchen,
+                mode,
+                force: false,
+            }),
+            None,
+        )
+        .unwrap();
+        let steps: Vec<RoomId> = next
+            .proposal_queue
+            .iter()
+            .filter_map(|p| match p.action {
+                GameAction::Move { to_room, .. } => Some(to_room),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(steps, route.steps);
+        assert_eq!(next.players["P1"].ap, 8 - route.ap_cost);
+    }
+}
+
+#[test]
+fn test_route_uses_per_step_card_costs() {
+    let mut state = setup(MapLayout::Star);
+    state
+        .active_situations
+        .push(get_behavior(CardId::SlipperyDeck).get_struct());
+    state.players.get_mut("P1").unwrap().ap = 1;
+    let dorm = room_of(&state, SystemType::Dormitory);
+    let kitchen = room_of(&state, SystemType::Kitchen);
+
+    // Slippery Deck makes the Hallway step free.
+    let route = find_route(&state, "P1", dorm, kitchen, RouteMode::Shortest, false).unwrap();
+    assert_eq!(route.steps, vec![0, kitchen]);
+    assert_eq!(route.step_costs, vec![0, 1]);
+    assert_eq!(route.ap_cost, 1);
+
+    let state = GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::move_to(kitchen)),
+        None,
+    )
+    .expect("One AP should be enough to reach the Kitchen");
+    assert_eq!(state.players["P1"].ap, 0);
+    assert_eq!(state.proposal_queue.len(), 2);
+}
+
+#[test]
+fn test_auto_path_refuses_ending_round_in_fire() {
+    let mut state = setup(MapLayout::Star);
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    state
+        .map
+        .rooms
+        .get_mut(&kitchen)
+        .unwrap()
+        .hazards
+        .push(HazardType::Fire);
+
+    let res = GameLogic::apply_action(
+        state.clone(),
+        "P1",
+        Action::Game(GameAction::move_to(kitchen)),
+        None,
+    );
+    assert!(matches!(res, Err(GameError::InvalidAction(_))));
+
+    // Forcing the route is allowed.
+    let dorm = room_of(&state, SystemType::Dormitory);
+    assert!(find_route(&state, "P1", dorm, kitchen, RouteMode::Shortest, true).is_ok());
+    let forced = GameLogic::apply_action(
+        state.clone(),
+        "P1",
+        Action::Game(GameAction::Move {
+            to_room: kitchen,
+            mode: RouteMode::Shortest,
+            force: true,
+        }),
+        None,
+    )
+    .expect("A forced route may end in fire");
+    assert_eq!(forced.players["P1"].ap, 0);
+
+    // Walking there one room at a time is explicit.
+    let state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
+    GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::move_to(kitchen)),
+        None,
+    )
+    .expect("Single step into fire should be allowed");
+}
+
+#[test]
+fn test_burning_destination_allowed_with_ap_left() {
+    let mut state = setup(MapLayout::Star);
+    let kitchen = room_of(&state, SystemType::Kitchen);
+    state
+        .map
+        .rooms
+        .get_mut(&kitchen)
+        .unwrap()
+        .hazards
+        .push(HazardType::Fire);
+    state.players.get_mut("P1").unwrap().ap = 3;
+
+    // One AP is left to Extinguish after arriving.
+    let state = GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::move_to(kitchen)),
+        None,
+    )
+    .unwrap();
+    assert_eq!(state.players["P1"].ap, 1);
+}
+
+#[test]
+fn test_route_errors() {
+    let state = setup(MapLayout::Star);
+    let dorm = room_of(&state, SystemType::Dormitory);
+
+    assert!(matches!(
+        find_route(&state, "P1", dorm, 99, RouteMode::Shortest, false),
+        Err(GameError::RoomNotFound)
+    ));
+    assert!(matches!(
+        find_route(&state, "P9", dorm, 0, RouteMode::Shortest, false),
+        Err(GameError::PlayerNotFound)
+    ));
+    assert_eq!(
+        find_route(&state, "P1", dorm, dorm, RouteMode::Safest, false)
+            .unwrap()
+            .steps,
+        Vec::<RoomId>::new()
+    );
+}
+
+#[test]
+fn test_plain_map_routes_agree() {
+    // Without hazards or cost cards, Shortest takes the BFS path and Safest the
+    // weighted search; both must find routes of the same length and cost.
+    for layout in [MapLayout::Star, MapLayout::Torus] {
+        let state = setup(layout);
+        let rooms: Vec<RoomId> = state.map.rooms.keys().collect();
+        for &start in &rooms {
+            for &end in &rooms {
+                let shortest =
+                    find_route(&state, "P1", start, end, RouteMode::Shortest, true).unwrap();
+                let safest = find_route(&state, "P1", start, end, RouteMode::Safest, true).unwrap();
+                let bfs = find_path(&state.map, start, end).unwrap();
+                assert_eq!(shortest.steps, bfs);
+                assert_eq!(safest.steps.len(), bfs.len());
+                assert_eq!(shortest.ap_cost, safest.ap_cost);
+                assert_eq!(shortest.ap_cost, bfs.len() as i32);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_move_json_omits_default_route_options() {
+    let json = serde_json::to_value(GameAction::move_to(3)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"type": "Move", "payload": {"to_room": 3}})
+    );
+    let parsed: GameAction = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, GameAction::move_to(3));
+
+    let forced = GameAction::Move {
+        to_room: 3,
+        mode: RouteMode::Safest,
+        force: true,
+    };
+    let json = serde_json::to_value(&forced).unwrap();
+    assert_eq!(json["payload"]["mode"], "Safest");
+    assert_eq!(json["payload"]["force"], true);
+    assert_eq!(serde_json::from_value::<GameAction>(json).unwrap(), forced);
+}
@@ -0,0 +1,162 @@
+use sint_core::{
+    GameLogic, invariants,
+    scenario::{Scenario, ScenarioBuilder},
+    types::*,
+};
+
+#[test]
+fn test_builder_sets_players_and_rooms() {
+    let b = ScenarioBuilder::new(&["P1", "P2"], 12345);
+    let kitchen = b.room_of(SystemType::Kitchen);
+    let state = b
+        .player_at("P1", kitchen)
+        .ap("P1", 1)
+        .hp("P2", 2)
+        .give("P2", ItemType::Peppernut)
+        .fire(kitchen)
+        .water(kitchen)
+        .item(kitchen, ItemType::Extinguisher)
+        .hull(15)
+        .build();
+
+    assert_eq!(state.phase, GamePhase::TacticalPlanning);
+    assert_eq!(state.players["P1"].room_id, kitchen);
+    assert_eq!(state.players["P1"].ap, 1);
+    assert_eq!(state.players["P2"].hp, 2);
+    assert_eq!(
+        state.players["P2"].inventory.as_slice(),
+        &[ItemType::Peppernut]
+    );
+    let room = &state.map.rooms[&kitchen];
+    assert_eq!(
+        room.hazards.as_slice(),
+        &[HazardType::Fire, HazardType::Water]
+    );
+    assert_eq!(room.items.as_slice(), &[ItemType::Extinguisher]);
+    assert_eq!(state.hull_integrity, 15);
+    assert!(invariants::check(&state).is_empty());
+}
+
+#[test]
+fn test_fainting_keeps_invariants() {
+    let state = ScenarioBuilder::new(&["P1"], 1).hp("P1", 0).build();
+    let p1 = &state.players["P1"];
+    assert!(p1.status.contains(&PlayerStatus::Fainted));
+    assert_eq!(p1.ap, 0);
+    assert!(invariants::check(&state).is_empty());
+
+    let state = ScenarioBuilder::from_state(state).hp("P1", 3).build();
+    assert!(state.players["P1"].status.is_empty());
+}
+
+#[test]
+fn test_cards_are_moved_out_of_the_deck() {
+    let state = ScenarioBuilder::new(&["P1"], 12345)
+        .card(CardId::SlipperyDeck)
+        .timebomb(CardId::Stowaway, 1)
+        .build();
+
+    assert_eq!(state.active_situations.len(), 2);
+    assert!(!state.deck.contains(&CardId::SlipperyDeck));
+    assert!(!state.deck.contains(&CardId::Stowaway));
+    assert_eq!(
+        state.active_situations[1].card_type,
+        CardType::Timebomb { rounds_left: 1 }
+    );
+    assert!(invariants::check(&state).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "not a Timebomb")]
+fn test_timebomb_rejects_situations() {
+    ScenarioBuilder::new(&["P1"], 12345).timebomb(CardId::AfternoonNap, 1);
+}
+
+#[test]
+fn test_telegraph_and_deck_order() {
+    let state = ScenarioBuilder::new(&["P1", "P2"], 12345)
+        .telegraph_system(SystemType::Bridge)
+        .deck(&[CardId::FogBank, CardId::Leak])
+        .ready("P1", true)
+        .ready("P2", true)
+        .phase(GamePhase::EnemyAction)
+        .build();
+
+    let attack = state.enemy.next_attack.as_ref().unwrap();
+    assert_eq!(attack.target_system, Some(SystemType::Bridge));
+    assert_eq!(state.deck, vec![CardId::FogBank, CardId::Leak]);
+
+    // The last card in the deck is drawn at the next Morning Report.
+    let state = GameLogic::apply_action(
+        state,
+        "P1",
+        Action::Game(GameAction::VoteReady { ready: true }),
+        None,
+    )
+    .unwrap();
+    assert_eq!(state.phase, GamePhase::MorningReport);
+    assert_eq!(
+        state.latest_event.as_ref().map(|c| c.id),
+        Some(CardId::Leak)
+    );
+}
+
+#[test]
+fn test_fixture_roundtrip() {
+    let path = std::env::temp_dir().join(format!("sint_scenario_{}.json", std::process::id()));
+    let saved = ScenarioBuilder::new(&["P1"], 99)
+        .name("Kitchen fire")
+        .description("Put out the fire before baking")
+        .fire_at_system(SystemType::Kitchen)
+        .save(&path)
+        .unwrap();
+
+    let loaded = Scenario::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, saved);
+    assert_eq!(loaded.name, "Kitchen fire");
+
+    let state = ScenarioBuilder::from_scenario(loaded).ap("P1", 0).build();
+    assert_eq!(state.players["P1"].ap, 0);
+}
+
+#[test]
+fn test_built_state_is_validated_as_built() {
+    let state = GameLogic::apply_action(
+        ScenarioBuilder::new(&["P1"], 12345).build(),
+        "P1",
+        Action::Game(GameAction::Chat {
+            message: "hi".to_owned(),
+        }),
+        None,
+    )
+    .unwrap();
+    let moves = |state: &GameState| -> Vec<RoomId> {
+        GameLogic::get_valid_actions(state, "P1")
+            .into_iter()
+            .filter_map(|a| match a {
+                Action::Game(GameAction::Move { to_room, .. }) => Some(to_room),
+                _ => None,
+            })
+            .collect()
+    };
+    let before = moves(&state);
+
+    // Edits made through the builder apply to the very next validation
+    let hub = state
+        .map
+        .rooms
+        .values()
+        .max_by_key(|r| r.neighbors.len())
+        .unwrap()
+        .id;
+    let state = ScenarioBuilder::from_state(state)
+        .player_at("P1", hub)
+        .build();
+    let mut expected: Vec<RoomId> = state.map.rooms[&hub].neighbors.to_vec();
+    let mut after = moves(&state);
+    expected.sort();
+    after.sort();
+    assert_ne!(after, before);
+    assert_eq!(after, expected);
+}
@@ -32,7 +32,7 @@ fn test_scenario_fire_in_kitchen() {
     state = GameLogic::apply_action(
         state,
         "P1",
-        Action::Game(GameAction::Move { to_room: kitchen }),
+        Action::Game(GameAction::move_to(kitchen)),
         None,
     )
     .unwrap();
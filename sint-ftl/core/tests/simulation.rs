@@ -24,6 +24,7 @@ fn test_interact_validation_wrong_room() {
         }),
         affected_player: None,
     };
+    state.deck.retain(|&c| c != CardId::TheBook);
     state.active_situations.push(card);
 
     // 2. Place Player in Kitchen (6)
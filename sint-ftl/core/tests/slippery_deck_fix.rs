@@ -22,8 +22,7 @@ fn test_slippery_deck_lifecycle() {
     state.active_situations.push(card);
 
     // Verify Free Moves to Hallway (Room 0)
-    let cost =
-        sint_core::logic::actions::action_cost(&state, "P1", &GameAction::Move { to_room: 0 });
+    let cost = sint_core::logic::actions::action_cost(&state, "P1", &GameAction::move_to(0));
     assert_eq!(cost, 0);
 
     // Other actions +1
@@ -0,0 +1,121 @@
+use rand::{SeedableRng, prelude::IndexedRandom, rngs::StdRng};
+use sint_core::{
+    GameLogic, GamePhase,
+    delta::{PatchOp, StateDelta},
+    types::*,
+};
+
+fn chat(state: GameState, player_id: &str, message: &str) -> GameState {
+    GameLogic::apply_action(
+        state,
+        player_id,
+        Action::Game(GameAction::Chat {
+            message: message.to_owned(),
+        }),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_delta_reproduces_random_games() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let start = GameLogic::new_game(vec!["P1".to_owned(), "P2".to_owned()], 12345);
+    let mut state = start.clone();
+
+    for step in 0..300 {
+        if matches!(state.phase, GamePhase::GameOver | GamePhase::Victory) {
+            break;
+        }
+        let candidates: Vec<_> = state
+            .players
+            .keys()
+            .flat_map(|pid| {
+                GameLogic::get_valid_actions(&state, pid)
+                    .into_iter()
+                    .map(move |a| (pid.clone(), a))
+            })
+            .collect();
+        let Some((pid, action)) = candidates.choose(&mut rng).cloned() else {
+            break;
+        };
+        let Ok(next) = GameLogic::apply_action(state.clone(), &pid, action, None) else {
+            continue;
+        };
+        state = next;
+
+        if step % 25 == 0 {
+            // Both from the start, as for a late joiner, and from the last step.
+            assert_eq!(start.apply_delta(&start.diff(&state)).unwrap(), state);
+        }
+    }
+    assert_eq!(start.apply_delta(&start.diff(&state)).unwrap(), state);
+}
+
+#[test]
+fn test_chat_history_stays_out_of_deltas() {
+    let before = chat(
+        GameLogic::new_game(vec!["P1".to_owned()], 12345),
+        "P1",
+        "hello",
+    );
+    let after = chat(before.clone(), "P1", "anyone there?");
+    assert_eq!(after.chat.len(), 1);
+
+    let delta = before.diff(&after);
+    let json = serde_json::to_string(&delta).unwrap();
+    assert!(!json.contains("hello"));
+    assert!(json.len() < serde_json::to_string(&after).unwrap().len() / 4);
+    assert_eq!(before.apply_delta(&delta).unwrap(), after);
+}
+
+#[test]
+fn test_identical_states_have_empty_delta() {
+    let state = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let delta = state.diff(&state.clone());
+    assert!(delta.is_empty());
+    assert_eq!(delta.base, delta.target);
+    assert_eq!(state.apply_delta(&delta).unwrap(), state);
+}
+
+#[test]
+fn test_delta_rejects_other_base() {
+    let start = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let later = chat(start.clone(), "P1", "hi");
+    let other = GameLogic::new_game(vec!["P1".to_owned()], 999);
+
+    let err = other.apply_delta(&start.diff(&later)).unwrap_err();
+    assert!(err.to_string().contains("Bad Sync"), "{}", err);
+}
+
+#[test]
+fn test_tampered_delta_is_detected() {
+    let start = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let later = chat(start.clone(), "P1", "hi");
+    let mut delta = start.diff(&later);
+    delta.ops.push(PatchOp::Set {
+        path: "/hull_integrity".to_owned(),
+        value: 1.into(),
+    });
+    assert!(start.apply_delta(&delta).is_err());
+
+    let missing = StateDelta {
+        ops: vec![PatchOp::Remove {
+            path: "/players/Nobody".to_owned(),
+        }],
+        ..start.diff(&later)
+    };
+    assert!(start.apply_delta(&missing).is_err());
+}
+
+#[test]
+fn test_snapshot_hash_covers_sequence_and_chat() {
+    let state = GameLogic::new_game(vec!["P1".to_owned()], 12345);
+    let chatted = chat(state.clone(), "P1", "hi");
+    assert_eq!(state.zobrist(), chatted.zobrist());
+    assert_ne!(state.snapshot_hash(), chatted.snapshot_hash());
+
+    let mut bumped = state.clone();
+    bumped.sequence_id += 1;
+    assert_ne!(state.snapshot_hash(), bumped.snapshot_hash());
+}
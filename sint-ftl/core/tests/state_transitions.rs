@@ -50,9 +50,7 @@ fn test_actions_are_disallowed_outside_planning_phase() {
     // Find a valid room to move to
     let start_room = state.players.get(&player_id).unwrap().room_id;
     let target_room = state.map.rooms.get(&start_room).unwrap().neighbors[0];
-    let move_action = Action::Game(GameAction::Move {
-        to_room: target_room,
-    });
+    let move_action = Action::Game(GameAction::move_to(target_room));
 
     // 2. Action & Assert: Test in various non-planning phases
     let disallowed_phases = [
@@ -20,7 +20,7 @@ fn test_sugar_rush_teleport() {
     }
 
     // Room 1 and 2 are not adjacent in Star layout (path is 1 -> 0 -> 2, length 2)
-    let move_to_2 = Action::Game(GameAction::Move { to_room: 2 });
+    let move_to_2 = Action::Game(GameAction::move_to(2));
 
     // 1. Without Sugar Rush, move from 1 to 2 should fail because it costs 2 AP
     let result = GameLogic::apply_action(state.clone(), "P1", move_to_2.clone(), None);
@@ -33,6 +33,7 @@ fn test_sugar_rush_teleport() {
     use sint_core::logic::cards::get_behavior;
     let behavior = get_behavior(CardId::SugarRush);
     let card = behavior.get_struct();
+    state.deck.retain(|&c| c != CardId::SugarRush);
     state.active_situations.push(card);
 
     let result = GameLogic::apply_action(state.clone(), "P1", move_to_2.clone(), None);
@@ -47,7 +48,7 @@ fn test_sugar_rush_teleport() {
     assert_eq!(state_after_move.proposal_queue.len(), 1);
     assert!(matches!(
         state_after_move.proposal_queue[0].action,
-        GameAction::Move { to_room: 2 }
+        GameAction::Move { to_room: 2, .. }
     ));
 
     // Check AP cost (should be 1 AP total for the leap)
@@ -62,7 +63,7 @@ fn test_sugar_rush_teleport() {
     let move_targets: Vec<u32> = valid_actions
         .iter()
         .filter_map(|a| {
-            if let Action::Game(GameAction::Move { to_room }) = a {
+            if let Action::Game(GameAction::Move { to_room, .. }) = a {
                 Some(*to_room)
             } else {
                 None
@@ -0,0 +1,31 @@
+use sint_core::export_typescript;
+
+#[test]
+fn test_declares_every_schema_type() {
+    let ts = export_typescript();
+    for name in [
+        "GameState",
+        "Action",
+        "GameAction",
+        "Player",
+        "Room",
+        "CardId",
+    ] {
+        assert!(
+            ts.contains(&format!("export type {} = ", name)),
+            "missing {}",
+            name
+        );
+    }
+    assert!(!ts.contains("unknown"), "{}", ts);
+}
+
+#[test]
+fn test_renders_tagged_actions_and_maps() {
+    let ts = export_typescript();
+    assert!(ts.contains("type: \"Move\";"));
+    assert!(ts.contains("to_room: number;"));
+    assert!(ts.contains("export type FieldMap_Player = Record<string, Player>;"));
+    assert!(ts.contains("export type SmallMap_uint32_Room = Record<string, Room>;"));
+    assert!(ts.contains("next_attack?: EnemyAttack | null;"));
+}
@@ -0,0 +1,144 @@
+use sint_core::{GameLogic, logic::find_room_with_system_in_map, types::*, zobrist};
+
+fn new_test_game() -> GameState {
+    let mut state = GameLogic::new_game(vec!["P1".to_owned(), "P2".to_owned()], 12345);
+    state.phase = GamePhase::TacticalPlanning;
+    state
+}
+
+#[test]
+fn test_key_covers_deck_order_and_seed() {
+    let state = new_test_game();
+    let base = state.zobrist();
+
+    let mut swapped = state.clone();
+    swapped.deck.swap(0, 1);
+    assert_ne!(swapped.deck, state.deck);
+    assert_ne!(swapped.zobrist(), base);
+
+    let mut reseeded = state.clone();
+    reseeded.rng_seed ^= 1;
+    assert_ne!(reseeded.zobrist(), base);
+}
+
+#[test]
+fn test_key_ignores_sequence_and_chat() {
+    let state = new_test_game();
+    let mut other = state.clone();
+    other.sequence_id += 10;
+    other.chat.push(ChatMessage {
+        sender: "P1".to_owned(),
+        text: "hi".to_owned(),
+        timestamp: 1,
+        channel: ChatChannel::Crew,
+    });
+    assert_eq!(other.zobrist(), state.zobrist());
+}
+
+#[test]
+fn test_hazard_order_does_not_matter() {
+    let mut a = new_test_game();
+    let kitchen = find_room_with_system_in_map(&a.map, SystemType::Kitchen).unwrap();
+    let mut b = a.clone();
+
+    let room = a.map.rooms.get_mut(&kitchen).unwrap();
+    room.hazards
+        .extend([HazardType::Fire, HazardType::Water, HazardType::Fire]);
+    let room = b.map.rooms.get_mut(&kitchen).unwrap();
+    room.hazards
+        .extend([HazardType::Fire, HazardType::Fire, HazardType::Water]);
+    assert_eq!(a.zobrist(), b.zobrist());
+
+    // A second fire is not the same as none (keys do not cancel out).
+    let mut c = a.clone();
+    c.map.rooms.get_mut(&kitchen).unwrap().hazards.pop();
+    c.map.rooms.get_mut(&kitchen).unwrap().hazards.pop();
+    assert_ne!(c.zobrist(), a.zobrist());
+    c.map.rooms.get_mut(&kitchen).unwrap().hazards.clear();
+    assert_eq!(c.zobrist(), new_test_game().zobrist());
+}
+
+#[test]
+fn test_incremental_room_update() {
+    let mut state = new_test_game();
+    let kitchen = find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap();
+    let before = state.zobrist();
+
+    let old = zobrist::room_key(&state.map.rooms[&kitchen]);
+    state
+        .map
+        .rooms
+        .get_mut(&kitchen)
+        .unwrap()
+        .add_hazard(HazardType::Fire);
+    let new = zobrist::room_key(&state.map.rooms[&kitchen]);
+    assert_eq!(before ^ old ^ new, state.zobrist());
+}
+
+#[test]
+fn test_actions_change_key_and_replay_matches() {
+    let state = new_test_game();
+    let apply = |s: GameState| {
+        GameLogic::apply_action(s, "P1", Action::Game(GameAction::move_to(0)), None).unwrap()
+    };
+
+    let a = apply(state.clone());
+    let b = apply(state.clone());
+    assert_ne!(a.zobrist(), state.zobrist());
+    assert_eq!(a.zobrist(), b.zobrist());
+
+    // Round-tripping through JSON keeps the key, so it can be used as a sync checkpoint.
+    let json = serde_json::to_string(&a).unwrap();
+    let restored: GameState = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.zobrist(), a.zobrist());
+}
+
+#[test]
+fn test_duplicates_do_not_cancel_out() {
+    let state = new_test_game();
+
+    let mut twice = state.clone();
+    let p1 = twice.players.get_mut("P1").unwrap();
+    p1.status.push(PlayerStatus::Silenced);
+    p1.status.push(PlayerStatus::Silenced);
+    assert_ne!(twice.zobrist(), state.zobrist());
+
+    let card = sint_core::logic::cards::get_behavior(CardId::Leak).get_struct();
+    let mut doubled = state.clone();
+    doubled.active_situations.push(card.clone());
+    doubled.active_situations.push(card);
+    assert_ne!(doubled.zobrist(), state.zobrist());
+}
+
+#[test]
+fn test_key_follows_direct_edits() {
+    let state = new_test_game();
+    let state =
+        GameLogic::apply_action(state, "P1", Action::Game(GameAction::move_to(0)), None).unwrap();
+    let base = state.zobrist();
+
+    // What forecasts do to a state returned by apply_action
+    let mut reseeded = state.clone();
+    reseeded.rng_seed ^= 1;
+    assert_ne!(reseeded.zobrist(), base);
+    assert_eq!(reseeded.zobrist(), zobrist::state_key(&reseeded));
+
+    let mut damaged = state.clone();
+    damaged.hull_integrity -= 1;
+    assert_ne!(damaged.zobrist(), base);
+
+    let mut renamed = state.clone();
+    renamed.players.get_mut("P2").unwrap().name = "Piet".to_owned();
+    assert_ne!(renamed.zobrist(), base);
+
+    let kitchen = find_room_with_system_in_map(&state.map, SystemType::Kitchen).unwrap();
+    let mut walled = state.clone();
+    walled
+        .map
+        .rooms
+        .get_mut(&kitchen)
+        .unwrap()
+        .neighbors
+        .clear();
+    assert_ne!(walled.zobrist(), base);
+}
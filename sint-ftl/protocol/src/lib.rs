@@ -0,0 +1,279 @@
+//! Messages exchanged over `/ws`, shared by the server and its clients.
+//!
+//! A client opens with `Hello`, naming its protocol version and the optional
+//! features it understands. The server answers with the version it will speak and
+//! the capabilities both sides support, or with `HelloRejected` and closes the
+//! socket. Clients that go straight to `Join` are treated as protocol version 1
+//! with no capabilities.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sint_core::delta::StateDelta;
+use sint_core::{GameLogic, GameState};
+use std::fmt;
+use uuid::Uuid;
+
+pub mod msgpack;
+pub mod replica;
+
+/// The newest protocol version this crate speaks.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// The oldest version still accepted. Version 1 predates `Hello`.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Seed of the state every room and client starts from.
+pub const INITIAL_SEED: u64 = 12345;
+
+/// The state before a room's first event. Every peer can build it, so it is the
+/// shared snapshot that `Sync` deltas fall back to.
+pub fn initial_state() -> GameState {
+    GameLogic::new_game(vec![], INITIAL_SEED)
+}
+
+/// The id of the `PlayerEvent` in an event's `data`, if it has one.
+pub fn event_id(data: &Value) -> Option<Uuid> {
+    data.get("id")?.as_str()?.parse().ok()
+}
+
+/// An optional feature agreed on during the handshake.
+///
+/// Names this build does not know deserialize as `Other`, so a newer client can
+/// offer them without breaking the handshake.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Capability {
+    /// `Resume` replays missed events instead of sending a full sync.
+    Resume,
+    /// The server validates events with sint-core and rejects illegal ones with
+    /// `Rejected`, instead of relaying them.
+    Authoritative,
+    /// `PresenceChanged` messages. Only sent to sockets that negotiated this.
+    Presence,
+    /// Snapshots come as a `Sync` delta instead of a `FullSync` event.
+    Delta,
+    /// The server sends binary MessagePack frames (see `msgpack`) instead of JSON
+    /// text. It reads either from the client.
+    MessagePack,
+    Other(String),
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Capability::Resume => "Resume",
+            Capability::Authoritative => "Authoritative",
+            Capability::Presence => "Presence",
+            Capability::Delta => "Delta",
+            Capability::MessagePack => "MessagePack",
+            Capability::Other(name) => name,
+        }
+    }
+}
+
+impl From<String> for Capability {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "Resume" => Capability::Resume,
+            "Authoritative" => Capability::Authoritative,
+            "Presence" => Capability::Presence,
+            "Delta" => Capability::Delta,
+            "MessagePack" => Capability::MessagePack,
+            _ => Capability::Other(name),
+        }
+    }
+}
+
+impl From<Capability> for String {
+    fn from(capability: Capability) -> Self {
+        match capability {
+            Capability::Other(name) => name,
+            known => known.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ClientMessage {
+    /// First message on a socket, before `Join`.
+    Hello {
+        protocol_version: u32,
+        #[serde(default)]
+        capabilities: Vec<Capability>,
+    },
+    Join {
+        room_id: String,
+        player_id: String,
+        /// Either credential opens a private room.
+        #[serde(default)]
+        invite_token: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        /// From an earlier `Welcome`, to reclaim the same seat.
+        #[serde(default)]
+        session_token: Option<String>,
+    },
+    Event {
+        sequence_id: u64,
+        data: Value,
+    },
+    SyncRequest {
+        requestor_id: String,
+    },
+    /// Catch up from the last event this client applied (0 for a fresh client).
+    Resume {
+        last_sequence_id: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ServerMessage {
+    /// Answer to a compatible `Hello`: the client's capabilities that the server
+    /// supports too.
+    Hello {
+        protocol_version: u32,
+        capabilities: Vec<Capability>,
+    },
+    /// Sent before the server closes the socket.
+    HelloRejected {
+        reason: HelloError,
+    },
+    Welcome {
+        room_id: String,
+        player_id: String,
+        /// Present on the next `Join` to reconnect as the same player.
+        session_token: String,
+    },
+    Event {
+        sequence_id: u64,
+        /// Unix milliseconds at which the server sequenced the event. Peers stamp
+        /// the chat the event produces with it, so they agree on chat times.
+        #[serde(default)]
+        timestamp: u64,
+        data: Value,
+    },
+    SyncRequest {
+        requestor_id: String,
+    },
+    /// The room's state as of `sequence_id`, as a delta from the room's latest
+    /// `Snapshot` or from `initial_state()` (`delta.base` names it). A client that
+    /// cannot apply it sends `SyncRequest` for a `FullSync` instead.
+    Sync {
+        sequence_id: u64,
+        delta: StateDelta,
+    },
+    /// The room persisted its state as of `sequence_id`, with `snapshot_hash` `hash`.
+    /// Later `Sync` deltas are taken from it, so a client at that point keeps a copy.
+    /// Only sent to sockets that negotiated `Delta`.
+    Snapshot {
+        sequence_id: u64,
+        hash: u64,
+    },
+    Error {
+        msg: String,
+    },
+    /// The sender's event `event_id` was refused (illegal, sent for another player,
+    /// or over the rate limit) and will not be sequenced.
+    Rejected {
+        event_id: Uuid,
+        reason: String,
+    },
+    JoinRejected {
+        room_id: String,
+        reason: JoinError,
+    },
+    /// Sent before the server closes the socket.
+    Kicked {
+        reason: KickReason,
+    },
+    /// An announcement from the server operators.
+    System {
+        message: String,
+    },
+    /// A player's first socket joined the room, or their last one left. Sent for
+    /// everyone already online right after `Welcome`.
+    PresenceChanged {
+        player_id: String,
+        connected: bool,
+    },
+}
+
+/// Why a `Hello` was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HelloError {
+    /// The server speaks versions `min..=max` only.
+    UnsupportedVersion { min: u32, max: u32 },
+}
+
+impl HelloError {
+    pub fn describe(&self) -> String {
+        match self {
+            HelloError::UnsupportedVersion { max, .. } if *max < PROTOCOL_VERSION => {
+                "The server is older than this client. Try again once it is updated.".to_owned()
+            }
+            HelloError::UnsupportedVersion { min, max } => format!(
+                "This client is out of date (the server speaks protocol {} to {}). Reload to update.",
+                min, max
+            ),
+        }
+    }
+}
+
+/// Why a `Join` was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JoinError {
+    RoomFull,
+    /// The room is private and neither an invite token nor a password was given.
+    CredentialsRequired,
+    InvalidInviteToken,
+    WrongPassword,
+    /// Another connection holds this player id; reconnect with its session token.
+    SeatTaken,
+    InvalidSessionToken,
+    /// Empty, or reserved for the server.
+    InvalidPlayerId,
+}
+
+impl JoinError {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            JoinError::RoomFull => "This room is full.",
+            JoinError::CredentialsRequired => {
+                "This room is private. Use an invite link or password."
+            }
+            JoinError::InvalidInviteToken => "This invite link is not valid.",
+            JoinError::WrongPassword => "Wrong password.",
+            JoinError::SeatTaken => "Someone is already playing under this name.",
+            JoinError::InvalidSessionToken => {
+                "Your session for this name has expired. Pick another name."
+            }
+            JoinError::InvalidPlayerId => "That player name is reserved.",
+        }
+    }
+}
+
+/// Why the server closed a socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KickReason {
+    /// An admin disconnected this socket.
+    Kicked,
+    /// An admin closed the room.
+    RoomClosed,
+}
+
+impl KickReason {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            KickReason::Kicked => "You were removed from this room by an admin.",
+            KickReason::RoomClosed => "This room was closed by an admin.",
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! MessagePack encoding for sockets that negotiated `Capability::MessagePack`.
+//!
+//! Structs are written as maps with string keys and types use their
+//! human-readable form (ids as strings), so messages have the same shape as
+//! their JSON form.
+
+use rmp_serde::{Deserializer, Serializer};
+use serde::{Serialize, de::DeserializeOwned};
+
+pub use rmp_serde::decode::Error as DecodeError;
+pub use rmp_serde::encode::Error as EncodeError;
+
+/// Nesting allowed in input, well above any message and low enough that hostile
+/// input cannot overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    let mut out = vec![];
+    value.serialize(
+        &mut Serializer::new(&mut out)
+            .with_struct_map()
+            .with_human_readable(),
+    )?;
+    Ok(out)
+}
+
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut de = Deserializer::new(bytes).with_human_readable();
+    de.set_max_depth(MAX_DEPTH);
+    let value = T::deserialize(&mut de)?;
+    if !de.into_inner().is_empty() {
+        return Err(DecodeError::Syntax("trailing bytes".to_owned()));
+    }
+    Ok(value)
+}
@@ -0,0 +1,144 @@
+//! A client's copy of a room's state, with optimistic prediction.
+//!
+//! The replica keeps the state as of the last sequenced event the server sent
+//! (`verified`) and the local player's events that the server has not echoed back
+//! yet (`pending`). What the player sees is `predict()`: the pending events replayed
+//! on top of the verified state. When an event arrives it is applied to the
+//! verified state, the matching pending event (if it was ours) is dropped, and the
+//! rest are replayed, so a prediction the server disagreed with rolls back. An
+//! event the server refuses comes back as `Rejected` and is dropped via `reject`.
+//!
+//! It also keeps the room's latest persisted snapshot if it was there when the
+//! server announced it, as a base for `Sync` deltas besides `initial_state()`.
+
+use crate::initial_state;
+use sint_core::delta::StateDelta;
+use sint_core::{Action, GameError, GameLogic, GameState, PlayerEvent};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Replica {
+    verified: GameState,
+    pending: VecDeque<PlayerEvent>,
+    /// Last server sequence applied to `verified`.
+    last_sequence_id: u64,
+    snapshot: Option<GameState>,
+}
+
+impl Default for Replica {
+    fn default() -> Self {
+        Replica {
+            verified: initial_state(),
+            pending: VecDeque::new(),
+            last_sequence_id: 0,
+            snapshot: None,
+        }
+    }
+}
+
+impl Replica {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The state as of `last_sequence_id`, without local predictions.
+    pub fn verified(&self) -> &GameState {
+        &self.verified
+    }
+
+    /// Local events sent but not yet sequenced, oldest first.
+    pub fn pending(&self) -> &VecDeque<PlayerEvent> {
+        &self.pending
+    }
+
+    pub fn last_sequence_id(&self) -> u64 {
+        self.last_sequence_id
+    }
+
+    /// Replays pending events on top of the verified state, dropping the ones
+    /// that no longer apply.
+    pub fn predict(&mut self) -> GameState {
+        let mut predicted = self.verified.clone();
+        self.pending.retain(|p| {
+            match GameLogic::apply_action(predicted.clone(), &p.player_id, p.action.clone(), None) {
+                Ok(next) => {
+                    predicted = next;
+                    true
+                }
+                Err(_) => false,
+            }
+        });
+        predicted
+    }
+
+    /// Applies sequenced event `sequence_id` to the verified state and retires the
+    /// pending event it confirms. On success the verified state's `chat` holds what
+    /// the event said, ready to be recorded in a `ChatLog`.
+    pub fn apply_event(&mut self, sequence_id: u64, event: &PlayerEvent) -> Result<(), GameError> {
+        self.last_sequence_id = sequence_id;
+        self.verified = GameLogic::apply_action(
+            self.verified.clone(),
+            &event.player_id,
+            event.action.clone(),
+            None,
+        )?;
+        // The server rules on a socket's events in order, so pending events sent
+        // before this one were refused even if their rejection has not arrived
+        if let Some(confirmed) = self.pending.iter().position(|p| p.id == event.id) {
+            self.pending.drain(..=confirmed);
+        }
+        Ok(())
+    }
+
+    /// Drops pending event `event_id` after the server refused it. Returns whether
+    /// it was pending; call `predict()` afterwards to roll the prediction back.
+    pub fn reject(&mut self, event_id: Uuid) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|p| p.id != event_id);
+        self.pending.len() != before
+    }
+
+    /// Keeps the verified state as the room's persisted snapshot, if the replica is
+    /// at it. Returns whether it was kept.
+    pub fn keep_snapshot(&mut self, sequence_id: u64, hash: u64) -> bool {
+        let at = self.last_sequence_id == sequence_id && self.verified.snapshot_hash() == hash;
+        if at {
+            self.snapshot = Some(self.verified.clone());
+        }
+        at
+    }
+
+    /// Replaces the verified state with the room's snapshot as of `sequence_id`.
+    /// Deltas are taken from the verified state, the kept snapshot or
+    /// `initial_state()`, which a fresh replica still holds. If it fails the replica
+    /// is unchanged, and the caller should ask for a full sync instead.
+    pub fn apply_sync(&mut self, sequence_id: u64, delta: &StateDelta) -> Result<(), GameError> {
+        let base = [Some(&self.verified), self.snapshot.as_ref()]
+            .into_iter()
+            .flatten()
+            .find(|state| state.snapshot_hash() == delta.base)
+            .cloned()
+            .unwrap_or_else(initial_state);
+        self.verified = base.apply_delta(delta)?;
+        self.last_sequence_id = sequence_id;
+        Ok(())
+    }
+
+    /// Tries `action` on the predicted state. If it applies, the event is queued
+    /// as pending and returned for sending, along with the new prediction.
+    pub fn perform(
+        &mut self,
+        player_id: &str,
+        action: Action,
+    ) -> Result<(PlayerEvent, GameState), GameError> {
+        let predicted = GameLogic::apply_action(self.predict(), player_id, action.clone(), None)?;
+        let event = PlayerEvent {
+            id: Uuid::new_v4(),
+            player_id: player_id.to_owned(),
+            action,
+        };
+        self.pending.push_back(event.clone());
+        Ok((event, predicted))
+    }
+}
@@ -0,0 +1,118 @@
+use sint_protocol::{
+    Capability, ClientMessage, HelloError, PROTOCOL_VERSION, ServerMessage, msgpack,
+};
+
+#[test]
+fn test_unknown_capabilities_survive_a_round_trip() {
+    let hello: ClientMessage = serde_json::from_value(serde_json::json!({
+        "type": "Hello",
+        "payload": { "protocol_version": 3, "capabilities": ["Resume", "Telepathy"] }
+    }))
+    .unwrap();
+    let ClientMessage::Hello { capabilities, .. } = &hello else {
+        panic!("Expected Hello, got {:?}", hello);
+    };
+    assert_eq!(
+        capabilities,
+        &[
+            Capability::Resume,
+            Capability::Other("Telepathy".to_owned())
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(&hello).unwrap()["payload"]["capabilities"],
+        serde_json::json!(["Resume", "Telepathy"])
+    );
+}
+
+#[test]
+fn test_hello_without_capabilities() {
+    let hello: ClientMessage =
+        serde_json::from_str(r#"{"type": "Hello", "payload": {"protocol_version": 2}}"#).unwrap();
+    assert!(matches!(
+        hello,
+        ClientMessage::Hello { protocol_version: 2, capabilities } if capabilities.is_empty()
+    ));
+}
+
+#[test]
+fn test_version_rejection_names_the_outdated_side() {
+    let server_too_old = HelloError::UnsupportedVersion {
+        min: 1,
+        max: PROTOCOL_VERSION - 1,
+    };
+    assert!(server_too_old.describe().contains("server is older"));
+    let client_too_old = HelloError::UnsupportedVersion {
+        min: PROTOCOL_VERSION + 1,
+        max: PROTOCOL_VERSION + 1,
+    };
+    assert!(client_too_old.describe().contains("out of date"));
+}
+
+#[test]
+fn test_messagepack_round_trip() {
+    let value = serde_json::json!({
+        "small": [0, 127, 128, 255, 65535, 65536, u64::MAX],
+        "negative": [-1, -32, -33, -128, -129, -32768, -32769, i64::MIN],
+        "float": 0.25,
+        "flags": [true, false, null],
+        "text": ["", "short", "x".repeat(31), "y".repeat(300), "z".repeat(70000)],
+        "many": (0..20).collect::<Vec<_>>(),
+        "nested": { "empty": {}, "list": [] },
+    });
+    let bytes = msgpack::to_vec(&value).unwrap();
+    assert_eq!(
+        msgpack::from_slice::<serde_json::Value>(&bytes).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_messagepack_matches_reference_encoding() {
+    // {"a": [1, -1, "b"]} as encoded by the reference implementation.
+    let bytes = msgpack::to_vec(&serde_json::json!({ "a": [1, -1, "b"] })).unwrap();
+    assert_eq!(bytes, [0x81, 0xa1, b'a', 0x93, 0x01, 0xff, 0xa1, b'b']);
+}
+
+#[test]
+fn test_messagepack_rejects_malformed_input() {
+    let bytes = msgpack::to_vec(&serde_json::json!(["truncated"])).unwrap();
+    assert!(msgpack::from_slice::<serde_json::Value>(&bytes[..bytes.len() - 1]).is_err());
+    assert!(msgpack::from_slice::<serde_json::Value>(&[0x01, 0x02]).is_err());
+    // A claimed length of four billion elements with nothing behind it.
+    assert!(msgpack::from_slice::<serde_json::Value>(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+    assert!(msgpack::from_slice::<serde_json::Value>(&[0x91; 1000]).is_err());
+}
+
+#[test]
+fn test_server_messages_survive_messagepack() {
+    let msg = ServerMessage::Welcome {
+        room_id: "room".to_owned(),
+        player_id: "P1".to_owned(),
+        session_token: "token".to_owned(),
+    };
+    let bytes = msgpack::to_vec(&msg).unwrap();
+    assert!(bytes.len() < serde_json::to_vec(&msg).unwrap().len());
+    let decoded: ServerMessage = msgpack::from_slice(&bytes).unwrap();
+    assert!(matches!(
+        decoded,
+        ServerMessage::Welcome { player_id, .. } if player_id == "P1"
+    ));
+}
+
+#[test]
+fn test_messagepack_has_the_json_shape() {
+    let event_id = uuid::Uuid::new_v4();
+    let msg = ServerMessage::Rejected {
+        event_id,
+        reason: "no".to_owned(),
+    };
+    let bytes = msgpack::to_vec(&msg).unwrap();
+    // Ids are strings, not 16 raw bytes, so a generic decoder sees the JSON form
+    assert_eq!(
+        msgpack::from_slice::<serde_json::Value>(&bytes).unwrap(),
+        serde_json::to_value(&msg).unwrap()
+    );
+    let decoded: ServerMessage = msgpack::from_slice(&bytes).unwrap();
+    assert!(matches!(decoded, ServerMessage::Rejected { event_id: id, .. } if id == event_id));
+}
@@ -0,0 +1,154 @@
+use sint_core::{Action, GameAction, MetaAction, PlayerEvent};
+use sint_protocol::initial_state;
+use sint_protocol::replica::Replica;
+use uuid::Uuid;
+
+fn join(name: &str) -> Action {
+    Action::Meta(MetaAction::Join {
+        name: name.to_owned(),
+    })
+}
+
+fn event(player_id: &str, action: Action) -> PlayerEvent {
+    PlayerEvent {
+        id: Uuid::new_v4(),
+        player_id: player_id.to_owned(),
+        action,
+    }
+}
+
+#[test]
+fn test_echoed_event_retires_the_prediction() {
+    let mut replica = Replica::new();
+    let (sent, predicted) = replica.perform("P1", join("P1")).unwrap();
+    assert!(predicted.players.contains_key("P1"));
+    assert!(!replica.verified().players.contains_key("P1"));
+    assert_eq!(replica.pending().len(), 1);
+
+    replica.apply_event(1, &sent).unwrap();
+    assert!(replica.pending().is_empty());
+    assert_eq!(replica.last_sequence_id(), 1);
+    assert_eq!(replica.predict(), *replica.verified());
+}
+
+#[test]
+fn test_foreign_events_go_under_pending_ones() {
+    let mut replica = Replica::new();
+    replica.apply_event(1, &event("P1", join("P1"))).unwrap();
+    let (sent, _) = replica
+        .perform(
+            "P1",
+            Action::Game(GameAction::Chat {
+                message: "hi".to_owned(),
+            }),
+        )
+        .unwrap();
+
+    replica.apply_event(2, &event("P2", join("P2"))).unwrap();
+    assert_eq!(replica.pending().len(), 1);
+    let predicted = replica.predict();
+    assert!(predicted.players.contains_key("P2"));
+    assert_eq!(predicted.chat[0].text, "hi");
+
+    replica.apply_event(3, &sent).unwrap();
+    assert!(replica.pending().is_empty());
+    assert_eq!(replica.verified().chat[0].text, "hi");
+}
+
+#[test]
+fn test_prediction_rolls_back_when_the_server_disagrees() {
+    let mut replica = Replica::new();
+    replica.perform("P2", join("Nick")).unwrap();
+
+    // P1 took the name first
+    replica.apply_event(1, &event("P1", join("Nick"))).unwrap();
+    let predicted = replica.predict();
+    assert!(replica.pending().is_empty());
+    assert!(!predicted.players.contains_key("P2"));
+    assert!(predicted.players.contains_key("P1"));
+}
+
+fn chat(message: &str) -> Action {
+    Action::Game(GameAction::Chat {
+        message: message.to_owned(),
+    })
+}
+
+#[test]
+fn test_rejected_event_does_not_block_later_ones() {
+    let mut replica = Replica::new();
+    replica.apply_event(1, &event("P1", join("P1"))).unwrap();
+    let (refused, _) = replica.perform("P1", chat("a")).unwrap();
+    let (accepted, _) = replica.perform("P1", chat("b")).unwrap();
+
+    assert!(replica.reject(refused.id));
+    assert!(!replica.reject(refused.id));
+    replica.apply_event(2, &accepted).unwrap();
+    assert!(replica.pending().is_empty());
+
+    let predicted = replica.predict();
+    let said: Vec<&str> = predicted.chat.iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(said, ["b"]);
+    assert_eq!(predicted, *replica.verified());
+}
+
+#[test]
+fn test_confirmed_event_retires_earlier_unanswered_ones() {
+    let mut replica = Replica::new();
+    replica.apply_event(1, &event("P1", join("P1"))).unwrap();
+    replica.perform("P1", chat("a")).unwrap();
+    let (accepted, _) = replica.perform("P1", chat("b")).unwrap();
+    let (later, _) = replica.perform("P1", chat("c")).unwrap();
+
+    // The rejection of "a" was lost
+    replica.apply_event(2, &accepted).unwrap();
+    assert_eq!(replica.pending().len(), 1);
+    assert_eq!(replica.pending()[0].id, later.id);
+}
+
+#[test]
+fn test_invalid_actions_are_not_sent() {
+    let mut replica = Replica::new();
+    assert!(
+        replica
+            .perform("P1", Action::Game(GameAction::Bake))
+            .is_err()
+    );
+    assert!(replica.pending().is_empty());
+}
+
+#[test]
+fn test_sync_from_initial_state() {
+    let mut room = Replica::new();
+    room.apply_event(1, &event("P1", join("P1"))).unwrap();
+    room.apply_event(2, &event("P2", join("P2"))).unwrap();
+    let delta = initial_state().diff(room.verified());
+
+    // A client that already applied the first event still syncs
+    let mut late = Replica::new();
+    late.apply_event(1, &event("P1", join("P1"))).unwrap();
+    late.apply_sync(2, &delta).unwrap();
+    assert_eq!(late.verified(), room.verified());
+    assert_eq!(late.last_sequence_id(), 2);
+}
+
+#[test]
+fn test_sync_from_kept_snapshot() {
+    let mut room = Replica::new();
+    room.apply_event(1, &event("P1", join("P1"))).unwrap();
+    let snapshot = room.verified().clone();
+    room.apply_event(2, &event("P2", join("P2"))).unwrap();
+    room.apply_event(3, &event("P1", chat("a"))).unwrap();
+    let delta = snapshot.diff(room.verified());
+
+    // Only a replica that was at the announced snapshot keeps it
+    let mut late = Replica::new();
+    late.apply_event(1, &event("P1", join("P1"))).unwrap();
+    assert!(!late.keep_snapshot(2, snapshot.snapshot_hash()));
+    assert!(late.keep_snapshot(1, snapshot.snapshot_hash()));
+    late.apply_event(2, &event("P2", join("P2"))).unwrap();
+    late.apply_sync(3, &delta).unwrap();
+    assert_eq!(late.verified(), room.verified());
+
+    assert!(Replica::new().apply_sync(3, &delta).is_err());
+}
@@ -0,0 +1,199 @@
+//! Moderation and debugging endpoints under `/api/admin`.
+//!
+//! Every request needs `Authorization: Bearer <token>` matching
+//! `ServerConfig::admin_token`; without a configured token all of them answer 401.
+
+use crate::room::{SocketInfo, sha256_hex};
+use crate::storage::{self, Snapshot};
+use crate::{
+    ApiError, AppState, Broadcast, KickReason, Room, RoomInfo, ServerConfig, ServerMessage,
+};
+use axum::{
+    Router,
+    extract::{FromRequestParts, Path, State},
+    http::{HeaderMap, StatusCode, header, request::Parts},
+    response::{IntoResponse, Json},
+    routing::{delete, get, post},
+};
+use serde::{Deserialize, Serialize};
+
+/// Proof that the request carried the admin token.
+pub struct Admin;
+
+impl Admin {
+    /// Checks the bearer token in `headers` against the configured admin token.
+    pub fn authorize(headers: &HeaderMap, config: &ServerConfig) -> Result<Self, ApiError> {
+        let expected = config
+            .admin_token
+            .as_deref()
+            .ok_or(ApiError::Unauthorized)?;
+        let given = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(ApiError::Unauthorized)?;
+        // Compare digests, so the time taken does not leak a matching prefix.
+        if sha256_hex(&[given]) == sha256_hex(&[expected]) {
+            Ok(Admin)
+        } else {
+            Err(ApiError::Unauthorized)
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for Admin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        Admin::authorize(&parts.headers, &state.config)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminRoomList {
+    pub rooms: Vec<AdminRoomInfo>,
+}
+
+/// A room as the lobby sees it, plus what only admins get to see.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminRoomInfo {
+    #[serde(flatten)]
+    pub info: RoomInfo,
+    pub unlisted: bool,
+    pub sequence_id: u64,
+    pub sockets: Vec<SocketInfo>,
+}
+
+/// Body of `POST /api/admin/rooms/{room_id}/broadcast`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SystemMessage {
+    pub message: String,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/rooms", get(list_rooms))
+        .route("/rooms/{room_id}", delete(close_room))
+        .route("/rooms/{room_id}/state", get(room_state))
+        .route("/rooms/{room_id}/log", get(room_log))
+        .route("/rooms/{room_id}/broadcast", post(broadcast))
+        .route("/rooms/{room_id}/connections/{connection_id}", delete(kick))
+}
+
+fn find_room(state: &AppState, room_id: &str) -> Result<Room, ApiError> {
+    state
+        .rooms
+        .get(room_id)
+        .map(|r| r.clone())
+        .ok_or(ApiError::RoomNotFound)
+}
+
+/// Every room, unlisted and private ones included.
+async fn list_rooms(_: Admin, State(state): State<AppState>) -> Json<AdminRoomList> {
+    let mut rooms: Vec<AdminRoomInfo> = state
+        .rooms
+        .iter()
+        .map(|r| AdminRoomInfo {
+            info: r.info(r.key(), state.config.max_players),
+            unlisted: r.access.unlisted,
+            sequence_id: r.authority.lock().unwrap().sequence_id,
+            sockets: r.socket_infos(),
+        })
+        .collect();
+    rooms.sort_by(|a, b| a.info.id.cmp(&b.info.id));
+    Json(AdminRoomList { rooms })
+}
+
+/// The server's copy of the game: validated in authoritative mode, otherwise the
+/// state as of the last relayed event.
+async fn room_state(
+    _: Admin,
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<Snapshot>, ApiError> {
+    let room = find_room(&state, &room_id)?;
+    let authority = room.authority.lock().unwrap();
+    Ok(Json(Snapshot {
+        sequence_id: authority.sequence_id,
+        state: authority.state.clone(),
+    }))
+}
+
+async fn room_log(
+    _: Admin,
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let room = find_room(&state, &room_id)?;
+    let log = room.authority.lock().unwrap().export_log();
+    let log = log
+        .await
+        .unwrap_or_else(|_| Err(std::io::Error::other("Room writer has stopped")))
+        .map_err(|e| {
+            tracing::error!("Cannot read log for room {}: {}", room_id, e);
+            ApiError::LogUnavailable
+        })?;
+    let disposition = format!(
+        "attachment; filename=\"{}.jsonl\"",
+        storage::encode_room_id(&room_id)
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        log,
+    ))
+}
+
+async fn broadcast(
+    _: Admin,
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+    Json(req): Json<SystemMessage>,
+) -> Result<StatusCode, ApiError> {
+    let room = find_room(&state, &room_id)?;
+    let msg = ServerMessage::System {
+        message: req.message,
+    };
+    let _ = room.tx.send(Broadcast::new(None, msg, None));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn kick(
+    _: Admin,
+    State(state): State<AppState>,
+    Path((room_id, connection_id)): Path<(String, u64)>,
+) -> Result<StatusCode, ApiError> {
+    let room = find_room(&state, &room_id)?;
+    if !room.kick(connection_id, KickReason::Kicked) {
+        return Err(ApiError::ConnectionNotFound);
+    }
+    tracing::info!("Kicked connection {} from room {}", connection_id, room_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Disconnects everyone and deletes the room, log included. Rooms that expired
+/// are only on disk, so this deletes their files.
+async fn close_room(
+    _: Admin,
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let Some((_, room)) = state.rooms.remove(&room_id) else {
+        let dir = (state.config.data_dir.as_deref())
+            .filter(|_| crate::is_stored(&state.config, &room_id))
+            .ok_or(ApiError::RoomNotFound)?;
+        if let Err(e) = storage::remove_room(dir, &room_id) {
+            tracing::error!("Cannot delete log for room {}: {}", room_id, e);
+        }
+        tracing::info!("Closed room {}", room_id);
+        return Ok(StatusCode::NO_CONTENT);
+    };
+    room.kick_all(KickReason::RoomClosed);
+    if let Some(dir) = &state.config.data_dir {
+        room.authority.lock().unwrap().remove_files(dir, &room_id);
+    }
+    tracing::info!("Closed room {}", room_id);
+    Ok(StatusCode::NO_CONTENT)
+}
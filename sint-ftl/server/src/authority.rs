@@ -0,0 +1,310 @@
+use crate::room::now_millis;
+use crate::storage::{self, ChatFile, LogEntry, RoomLog, RoomWriter, Snapshot, StoredRoom};
+use crate::{Broadcast, Capability, ServerConfig, ServerMessage, initial_state};
+use sint_core::chat::ChatLog;
+use sint_core::{Action, ChatMessage, GameLogic, GameState, MetaAction, PlayerEvent};
+use std::collections::VecDeque;
+use std::path::Path;
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+
+/// Player id used for events the server itself produces.
+pub const SERVER_PLAYER_ID: &str = "server";
+
+/// The server's copy of a room's game.
+///
+/// In authoritative mode it validates every event. Otherwise it follows the relayed
+/// events like any other peer, so the room can be persisted and answer syncs after
+/// everyone has left.
+pub struct Authority {
+    pub state: GameState,
+    /// Sequence number of the last broadcast event. Starts at 0; the first event is 1.
+    pub sequence_id: u64,
+    /// The newest `ServerConfig::chat_history` chat messages since the room was
+    /// created, or since the restart if the room does not keep its chat.
+    pub chat: ChatLog,
+    chat_history: usize,
+    validate: bool,
+    /// Whether `writer` has an event log to write to.
+    logged: bool,
+    writer: Option<RoomWriter>,
+    /// The state last written by compaction, which `Sync` deltas are taken from.
+    snapshot: Option<Snapshot>,
+    /// The most recent events, oldest first, for clients that resume.
+    recent: VecDeque<LogEntry>,
+    recent_capacity: usize,
+}
+
+impl Authority {
+    pub fn new(config: &ServerConfig, log: Option<RoomLog>, chat_file: Option<ChatFile>) -> Self {
+        Authority {
+            state: initial_state(),
+            sequence_id: 0,
+            chat: ChatLog::default(),
+            chat_history: config.chat_history,
+            validate: config.authoritative,
+            logged: log.is_some(),
+            writer: RoomWriter::spawn(log, chat_file),
+            snapshot: None,
+            recent: VecDeque::new(),
+            recent_capacity: config.resume_buffer,
+        }
+    }
+
+    /// Rebuilds a room from its snapshot, the events logged after it and its chat.
+    pub fn restore(
+        config: &ServerConfig,
+        log: Option<RoomLog>,
+        chat_file: Option<ChatFile>,
+        stored: StoredRoom,
+    ) -> Self {
+        let mut authority = Self::new(config, log, chat_file);
+        let persist_chat = stored.chat.is_some();
+        authority.chat = ChatLog::from_entries(stored.chat.unwrap_or_default());
+        authority.chat.trim(authority.chat_history);
+        if let Some(snapshot) = stored.snapshot {
+            authority.state = snapshot.state.clone();
+            authority.sequence_id = snapshot.sequence_id;
+            authority.snapshot = Some(snapshot);
+        }
+        for entry in stored.events {
+            if let Ok(event) = serde_json::from_value::<PlayerEvent>(entry.data.clone()) {
+                let chat = authority.follow(event);
+                // Recovers chat that a crash kept from reaching the chat file
+                if persist_chat {
+                    authority.record_chat(entry.sequence_id, entry.timestamp, &chat);
+                }
+            }
+            authority.sequence_id = entry.sequence_id;
+            authority.remember(entry);
+        }
+        authority
+    }
+
+    /// Applies `data` as a `PlayerEvent`, stamps it with the next sequence number, logs
+    /// it and broadcasts it to the room. When validating, an event that does not apply
+    /// changes nothing and the reason is returned for the sender; otherwise it is
+    /// relayed regardless, as the clients decide for themselves.
+    ///
+    /// `sender` is the player the submitting connection is bound to; events for any
+    /// other player are rejected in both modes. `None` marks the server's own events.
+    pub fn submit(
+        &mut self,
+        data: serde_json::Value,
+        sender: Option<&str>,
+        tx: &broadcast::Sender<Broadcast>,
+    ) -> Result<u64, String> {
+        if let Some(sender) = sender {
+            let player_id = data.get("player_id").and_then(|v| v.as_str());
+            if player_id != Some(sender) {
+                return Err(format!(
+                    "{} cannot act as {}",
+                    sender,
+                    player_id.unwrap_or("nobody")
+                ));
+            }
+        }
+
+        if !self.validate {
+            let chat = serde_json::from_value::<PlayerEvent>(data.clone())
+                .map(|event| self.follow(event))
+                .unwrap_or_default();
+            return Ok(self.broadcast(data, &chat, tx));
+        }
+
+        let event: PlayerEvent =
+            serde_json::from_value(data).map_err(|e| format!("Malformed event: {}", e))?;
+
+        if let Action::Meta(MetaAction::FullSync { .. }) = event.action {
+            return Err("Only the server can sync state".to_owned());
+        }
+
+        self.state = GameLogic::apply_action(
+            self.state.clone(),
+            &event.player_id,
+            event.action.clone(),
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let chat = self.state.chat.clone();
+        Ok(self.broadcast(serde_json::to_value(&event).unwrap(), &chat, tx))
+    }
+
+    /// Applies an event the way a relaying client would: invalid events leave the
+    /// state unchanged. Returns the chat the event produced.
+    fn follow(&mut self, event: PlayerEvent) -> Vec<ChatMessage> {
+        match GameLogic::apply_action(self.state.clone(), &event.player_id, event.action, None) {
+            Ok(next) => {
+                self.state = next;
+                self.state.chat.clone()
+            }
+            Err(_) => vec![],
+        }
+    }
+
+    fn broadcast(
+        &mut self,
+        data: serde_json::Value,
+        chat: &[ChatMessage],
+        tx: &broadcast::Sender<Broadcast>,
+    ) -> u64 {
+        self.sequence_id += 1;
+        let entry = LogEntry {
+            sequence_id: self.sequence_id,
+            timestamp: now_millis(),
+            data,
+        };
+        // Chat first, so once an event is in the log its chat is on disk too
+        self.record_chat(entry.sequence_id, entry.timestamp, chat);
+        let compacted = self.persist(&entry);
+
+        // Broadcast while the caller still holds the lock, so the room sees events in
+        // sequence order.
+        let _ = tx.send(Broadcast::new(
+            Some(entry.sequence_id),
+            event_message(&entry),
+            None,
+        ));
+        // Sockets at this event can keep the state as a base for later deltas
+        if compacted {
+            let msg = ServerMessage::Snapshot {
+                sequence_id: entry.sequence_id,
+                hash: self.state.snapshot_hash(),
+            };
+            let _ = tx.send(Broadcast::new(None, msg, Some(Capability::Delta)));
+        }
+        self.remember(entry);
+        self.sequence_id
+    }
+
+    fn remember(&mut self, entry: LogEntry) {
+        self.recent.push_back(entry);
+        while self.recent.len() > self.recent_capacity {
+            self.recent.pop_front();
+        }
+    }
+
+    /// What a client that has applied everything up to `after` needs to be current:
+    /// the missing events if they are still buffered, otherwise a snapshot of the
+    /// current state (a `Sync` delta if `delta`, else a `FullSync`). A client ahead
+    /// of the server (e.g. after a restart without a data dir) also gets the snapshot.
+    pub fn catch_up(&self, after: u64, delta: bool) -> Vec<ServerMessage> {
+        if after == self.sequence_id {
+            return vec![];
+        }
+        let buffered = self
+            .recent
+            .front()
+            .is_some_and(|first| first.sequence_id <= after + 1);
+        if after < self.sequence_id && buffered {
+            self.recent
+                .iter()
+                .filter(|e| e.sequence_id > after)
+                .map(event_message)
+                .collect()
+        } else if delta {
+            vec![self.delta_message(after)]
+        } else {
+            vec![self.sync_message()]
+        }
+    }
+
+    /// The room's event log as JSON lines: the file on disk when persisting (events
+    /// since the last snapshot), otherwise the events still buffered for resume.
+    /// Await it after releasing the lock.
+    pub fn export_log(&self) -> oneshot::Receiver<std::io::Result<String>> {
+        if let Some(writer) = self.writer.as_ref().filter(|_| self.logged) {
+            return writer.contents();
+        }
+        let (tx, rx) = oneshot::channel();
+        let mut out = String::new();
+        for entry in &self.recent {
+            out.push_str(&serde_json::to_string(entry).unwrap());
+            out.push('\n');
+        }
+        let _ = tx.send(Ok(out));
+        rx
+    }
+
+    /// Deletes the room's files, after any writes still queued.
+    pub fn remove_files(&mut self, data_dir: &Path, room_id: &str) {
+        match self.writer.take() {
+            Some(writer) => writer.remove_room(data_dir, room_id),
+            None => {
+                if let Err(e) = storage::remove_room(data_dir, room_id) {
+                    tracing::error!("Cannot delete log for room {}: {}", room_id, e);
+                }
+            }
+        }
+    }
+
+    fn record_chat(&mut self, sequence_id: u64, timestamp: u64, chat: &[ChatMessage]) {
+        let added = self.chat.record(sequence_id, timestamp, chat);
+        if let Some(writer) = &self.writer
+            && !added.is_empty()
+        {
+            writer.append_chat(added.to_vec());
+        }
+        self.chat.trim(self.chat_history);
+    }
+
+    /// Logs an event. Returns whether the log was compacted into a new snapshot.
+    fn persist(&mut self, entry: &LogEntry) -> bool {
+        let Some(writer) = self.writer.as_mut().filter(|_| self.logged) else {
+            return false;
+        };
+        writer.append(entry.clone());
+        if !writer.needs_compaction() {
+            return false;
+        }
+        writer.compact(self.sequence_id, self.state.clone());
+        self.snapshot = Some(Snapshot {
+            sequence_id: self.sequence_id,
+            state: self.state.clone(),
+        });
+        true
+    }
+
+    /// The current state as a `FullSync` event, for a client that asked to sync.
+    pub fn sync_message(&self) -> ServerMessage {
+        let event = PlayerEvent {
+            id: Uuid::new_v4(),
+            player_id: SERVER_PLAYER_ID.to_owned(),
+            action: Action::Meta(MetaAction::FullSync {
+                state_json: serde_json::to_string(&self.state).unwrap(),
+            }),
+        };
+        ServerMessage::Event {
+            sequence_id: self.sequence_id,
+            timestamp: now_millis(),
+            data: serde_json::to_value(&event).unwrap(),
+        }
+    }
+
+    /// The current state as a `Sync` delta for a client that has applied everything
+    /// up to `after`: from the latest snapshot if the client was there when it was
+    /// announced, else from the initial state, which every client has. A `FullSync`
+    /// if the delta would not be smaller.
+    pub fn delta_message(&self, after: u64) -> ServerMessage {
+        let delta = match self.snapshot.as_ref().filter(|s| after >= s.sequence_id) {
+            Some(snapshot) => snapshot.state.diff(&self.state),
+            None => initial_state().diff(&self.state),
+        };
+        let sync = ServerMessage::Sync {
+            sequence_id: self.sequence_id,
+            delta,
+        };
+        let full = self.sync_message();
+        let len = |msg: &ServerMessage| serde_json::to_vec(msg).map_or(usize::MAX, |v| v.len());
+        if len(&sync) < len(&full) { sync } else { full }
+    }
+}
+
+fn event_message(entry: &LogEntry) -> ServerMessage {
+    ServerMessage::Event {
+        sequence_id: entry.sequence_id,
+        timestamp: entry.timestamp,
+        data: entry.data.clone(),
+    }
+}
@@ -0,0 +1,218 @@
+//! Server-hosted bot players, so a small group can fill the crew without running
+//! the Python agent.
+//!
+//! Each bot takes a seat like a socket and submits events through the room's
+//! `Authority`, deciding with `sint_solver::bot` on a blocking thread. Only the
+//! room's owner (its invite token) or an admin may add them, and the server runs at
+//! most `ServerConfig::max_bots`.
+
+use crate::admin::Admin;
+use crate::room::Connection;
+use crate::{ApiError, AppState, JoinError, Room};
+use axum::extract::{Json, Path, State};
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use sint_core::{Action, GameLogic, MetaAction, PlayerEvent};
+use sint_solver::bot::{BotConfig, BotStrategy, decide, fallback};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+/// Bots leave a room once no human has been in it for this long.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Upper bound on `budget_ms`, so a request cannot stall a room.
+const MAX_BUDGET: Duration = Duration::from_secs(10);
+
+/// Body of `POST /api/rooms/{room_id}/bots`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddBots {
+    #[serde(default = "default_count")]
+    pub count: usize,
+    #[serde(default)]
+    pub strategy: BotStrategy,
+    /// Search time per action. Defaults to `BotConfig::default().budget`.
+    #[serde(default)]
+    pub budget_ms: Option<u64>,
+    /// The room's invite token. Not needed with the admin token.
+    #[serde(default)]
+    pub invite_token: Option<String>,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddedBots {
+    pub player_ids: Vec<String>,
+}
+
+/// A slot in `AppState::bots`, given back when dropped.
+struct BotSlot(Arc<AtomicUsize>);
+
+impl BotSlot {
+    /// Takes `count` slots, or none if that would go over `max`.
+    fn take(bots: &Arc<AtomicUsize>, count: usize, max: usize) -> Option<Vec<BotSlot>> {
+        bots.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+            running.checked_add(count).filter(|&total| total <= max)
+        })
+        .ok()?;
+        Some((0..count).map(|_| BotSlot(bots.clone())).collect())
+    }
+}
+
+impl Drop for BotSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Seats all the bots or none: if one cannot be seated, the ones already seated
+/// leave again before the error is returned.
+pub async fn add_bots(
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<AddBots>,
+) -> Result<Json<AddedBots>, ApiError> {
+    let room = state
+        .rooms
+        .get(&room_id)
+        .map(|r| r.clone())
+        .ok_or(ApiError::RoomNotFound)?;
+    if !room.access.is_owner(req.invite_token.as_deref())
+        && Admin::authorize(&headers, &state.config).is_err()
+    {
+        return Err(ApiError::Forbidden);
+    }
+    let seats = room.connection_count().checked_add(req.count);
+    if seats.is_none_or(|seats| seats > state.config.max_players) {
+        return Err(ApiError::RoomFull);
+    }
+    let slots = BotSlot::take(&state.bots, req.count, state.config.max_bots)
+        .ok_or(ApiError::TooManyBots)?;
+
+    let mut config = BotConfig {
+        strategy: req.strategy,
+        ..Default::default()
+    };
+    if let Some(ms) = req.budget_ms {
+        config.budget = Duration::from_millis(ms).min(MAX_BUDGET);
+    }
+
+    // Dropping the seated connections gives their seats back
+    let mut connections = vec![];
+    for _ in 0..req.count {
+        let player_id = format!("Bot-{}", &Uuid::new_v4().simple().to_string()[..4]);
+        let connection = room
+            .connect_bot(&player_id, state.config.max_players)
+            .map_err(|e| match e {
+                JoinError::RoomFull => ApiError::RoomFull,
+                _ => ApiError::Forbidden,
+            })?;
+        connections.push(connection);
+    }
+
+    let mut player_ids = vec![];
+    for (connection, slot) in connections.into_iter().zip(slots) {
+        tracing::info!("Bot {} joined room {}", connection.player_id, room_id);
+        player_ids.push(connection.player_id.clone());
+        tokio::spawn(run_bot(connection, config.clone(), slot));
+    }
+    Ok(Json(AddedBots { player_ids }))
+}
+
+/// Plays until kicked, the room closes, or the humans are gone.
+async fn run_bot(mut connection: Connection, config: BotConfig, _slot: BotSlot) {
+    let room = connection.room.clone();
+    let player_id = connection.player_id.clone();
+    let mut rx = room.tx.subscribe();
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    let mut last_human = Instant::now();
+    // Sequence number of the state the last decision was made on.
+    let mut decided_at = None;
+
+    submit(
+        &room,
+        &player_id,
+        Action::Meta(MetaAction::Join {
+            name: player_id.clone(),
+        }),
+    );
+
+    loop {
+        if room.human_count() > 0 {
+            last_human = Instant::now();
+        } else if last_human.elapsed() >= IDLE_TIMEOUT {
+            break;
+        }
+
+        let (sequence_id, state) = {
+            let authority = room.authority.lock().unwrap();
+            (authority.sequence_id, authority.state.clone())
+        };
+        if decided_at != Some(sequence_id) {
+            decided_at = Some(sequence_id);
+            let (pid, cfg) = (player_id.clone(), config.clone());
+            let decision =
+                tokio::task::spawn_blocking(move || decide(&state, &pid, &cfg).map(|a| (state, a)))
+                    .await
+                    .ok()
+                    .flatten();
+            if let Some((state, action)) = decision {
+                // Relay mode does not validate, so check the choice here
+                let action = match GameLogic::apply_action(
+                    state.clone(),
+                    &player_id,
+                    Action::Game(action.clone()),
+                    None,
+                ) {
+                    Ok(_) => action,
+                    Err(e) => {
+                        tracing::debug!("Bot {} chose an invalid action: {}", player_id, e);
+                        fallback(&state, &player_id)
+                    }
+                };
+                if submit(&room, &player_id, Action::Game(action)) {
+                    continue;
+                }
+                // Rejected: decide again on the next tick rather than wait for an
+                // event that may never come
+                decided_at = None;
+            }
+        }
+
+        tokio::select! {
+            _ = &mut connection.kicked => break,
+            msg = rx.recv() => {
+                if let Err(RecvError::Closed) = msg {
+                    break;
+                }
+            }
+            _ = tick.tick() => {}
+        }
+    }
+    tracing::info!("Bot {} left", player_id);
+}
+
+/// Whether the room accepted the event.
+fn submit(room: &Room, player_id: &str, action: Action) -> bool {
+    let event = PlayerEvent {
+        id: Uuid::new_v4(),
+        player_id: player_id.to_owned(),
+        action,
+    };
+    room.touch();
+    let result = room.authority.lock().unwrap().submit(
+        serde_json::to_value(&event).unwrap(),
+        Some(player_id),
+        &room.tx,
+    );
+    if let Err(msg) = &result {
+        tracing::debug!("Rejected bot event: {}", msg);
+    }
+    result.is_ok()
+}
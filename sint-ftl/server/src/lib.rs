@@ -1,155 +1,573 @@
 use axum::{
     Router,
     extract::{
-        State,
+        Path, Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    response::{IntoResponse, Json},
-    routing::get,
+    http::{Method, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
 };
 use dashmap::DashMap;
 use futures::{sink::SinkExt, stream::StreamExt};
 use serde::{Deserialize, Serialize};
+use sint_core::ChatChannel;
+use sint_core::chat::ChatPage;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::services::{ServeDir, ServeFile};
+
+pub mod admin;
+pub mod authority;
+pub mod bots;
+pub mod metrics;
+pub mod rate_limit;
+pub mod room;
+pub mod storage;
+
+pub use metrics::Metrics;
+pub use rate_limit::RateLimit;
+use rate_limit::TokenBucket;
+pub use room::{Broadcast, Room, RoomAccess, RoomInfo};
+pub use sint_protocol::{
+    Capability, ClientMessage, HelloError, INITIAL_SEED, JoinError, KickReason,
+    MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, ServerMessage, event_id, initial_state, msgpack,
+};
 
 // --- Types ---
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct RoomList {
-    pub rooms: Vec<String>,
+    pub rooms: Vec<RoomInfo>,
 }
 
-#[derive(Clone)]
-pub struct AppState {
-    // Room ID -> Broadcast Channel
-    pub rooms: Arc<DashMap<String, broadcast::Sender<String>>>,
+/// Body of `POST /api/rooms`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CreateRoom {
+    /// A random name is picked when omitted.
+    #[serde(default)]
+    pub room_id: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub unlisted: bool,
+    /// Overrides `ServerConfig::persist_chat` for this room.
+    #[serde(default)]
+    pub persist_chat: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(tag = "type", content = "payload")]
-pub enum ClientMessage {
-    Join {
-        room_id: String,
-        player_id: String,
-    },
-    Event {
-        sequence_id: u64,
-        data: serde_json::Value,
-    },
-    SyncRequest {
-        requestor_id: String,
-    },
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedRoom {
+    pub room_id: String,
+    pub invite_token: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(tag = "type", content = "payload")]
-pub enum ServerMessage {
-    Welcome {
-        room_id: String,
-    },
-    Event {
-        sequence_id: u64,
-        data: serde_json::Value,
-    },
-    SyncRequest {
-        requestor_id: String,
-    },
-    Error {
-        msg: String,
-    },
+/// Query of `GET /api/rooms/{room_id}/chat`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChatQuery {
+    /// All channels when omitted.
+    #[serde(default)]
+    pub channel: Option<ChatChannel>,
+    /// `before` from the previous page; the newest messages when omitted.
+    #[serde(default)]
+    pub before: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Either credential opens a private room, as for `Join`.
+    #[serde(default)]
+    pub invite_token: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Page size of the chat API when the query has no `limit`, and its upper bound.
+const CHAT_PAGE: usize = 50;
+const MAX_CHAT_PAGE: usize = 500;
+
+/// Error body of the REST API: `{"error": "RoomExists"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiError {
+    RoomExists,
+    InvalidRoomId,
+    /// Missing or wrong admin token, or the admin API is disabled.
+    Unauthorized,
+    RoomNotFound,
+    ConnectionNotFound,
+    /// The room has no free seats.
+    RoomFull,
+    /// The room is private and the credentials were missing or wrong.
+    Forbidden,
+    /// The event log could not be read.
+    LogUnavailable,
+    /// The server already runs `ServerConfig::max_bots` bots.
+    TooManyBots,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ApiError::RoomExists => StatusCode::CONFLICT,
+            ApiError::InvalidRoomId => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::RoomFull => StatusCode::CONFLICT,
+            ApiError::RoomNotFound | ApiError::ConnectionNotFound => StatusCode::NOT_FOUND,
+            ApiError::LogUnavailable => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::TooManyBots => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        (status, Json(serde_json::json!({ "error": self }))).into_response()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    /// Validate and sequence events with sint-core instead of relaying them blindly.
+    pub authoritative: bool,
+    /// Where room event logs and snapshots are kept. `None` keeps rooms in memory only.
+    pub data_dir: Option<PathBuf>,
+    /// Snapshot a room and truncate its log after this many events.
+    pub compact_every: u64,
+    /// Recent events kept per room for `Resume`. Older gaps are filled with a snapshot.
+    pub resume_buffer: usize,
+    /// Messages a socket may fall behind before it has to catch up.
+    pub broadcast_capacity: usize,
+    /// How long a room stays loaded after its last socket leaves. Its files stay.
+    pub room_ttl: Duration,
+    /// Sockets allowed in one room.
+    pub max_players: usize,
+    /// Bearer token for `/api/admin`. `None` disables the admin API.
+    pub admin_token: Option<String>,
+    /// How often the server pings each socket.
+    pub ping_interval: Duration,
+    /// A socket that sends nothing (not even a pong) for this long is dropped.
+    pub ping_timeout: Duration,
+    /// Built web client (`client/dist`) to serve at `/`. `None` serves only the API.
+    pub static_dir: Option<PathBuf>,
+    /// Origins allowed to call the API from a browser; `*` allows any. Empty allows
+    /// none, which is all a client served from `static_dir` needs.
+    pub cors_origins: Vec<String>,
+    /// Largest WebSocket message accepted from a client, in bytes.
+    pub max_message_size: usize,
+    /// Messages each socket may send. `None` is unlimited.
+    pub rate_limit: Option<RateLimit>,
+    /// Whether rooms keep their chat in `data_dir`, so it outlives a restart.
+    /// `POST /api/rooms` can choose per room.
+    pub persist_chat: bool,
+    /// Chat messages each room keeps, in memory and in its chat file; older ones are
+    /// dropped. 0 keeps everything.
+    pub chat_history: usize,
+    /// Bots allowed across all rooms.
+    pub max_bots: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            authoritative: false,
+            data_dir: None,
+            compact_every: 500,
+            resume_buffer: 1000,
+            broadcast_capacity: 100,
+            room_ttl: Duration::from_secs(30 * 60),
+            max_players: 6,
+            admin_token: None,
+            ping_interval: Duration::from_secs(15),
+            ping_timeout: Duration::from_secs(45),
+            static_dir: None,
+            cors_origins: vec![],
+            max_message_size: 1 << 20,
+            rate_limit: Some(RateLimit::default()),
+            persist_chat: true,
+            chat_history: 1000,
+            max_bots: 32,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// What this server offers in the `Hello` handshake.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        let mut capabilities = vec![
+            Capability::Resume,
+            Capability::Presence,
+            Capability::Delta,
+            Capability::MessagePack,
+        ];
+        if self.authoritative {
+            capabilities.push(Capability::Authoritative);
+        }
+        capabilities
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    // Room ID -> Room
+    pub rooms: Arc<DashMap<String, Room>>,
+    pub config: Arc<ServerConfig>,
+    pub metrics: Arc<Metrics>,
+    /// Bots running across all rooms, held to `ServerConfig::max_bots`.
+    pub bots: Arc<AtomicUsize>,
 }
 
 // --- App Factory ---
 
 pub fn create_app() -> Router {
+    create_app_with_config(ServerConfig::default())
+}
+
+pub fn create_app_with_config(config: ServerConfig) -> Router {
+    let rooms = DashMap::new();
+    if let Some(dir) = &config.data_dir {
+        for (room_id, room) in load_rooms(&config, dir) {
+            rooms.insert(room_id, room);
+        }
+    }
     let state = AppState {
-        rooms: Arc::new(DashMap::new()),
+        rooms: Arc::new(rooms),
+        config: Arc::new(config),
+        metrics: Arc::new(Metrics::default()),
+        bots: Arc::new(AtomicUsize::new(0)),
     };
+    room::spawn_reaper(Arc::downgrade(&state.rooms), state.config.clone());
 
-    Router::new()
+    let mut app = Router::new()
         .route("/ws", get(ws_handler))
-        .route("/api/rooms", get(list_rooms))
-        .layer(CorsLayer::permissive())
-        .with_state(state)
+        .route("/api/rooms", get(list_rooms).post(create_room))
+        .route("/api/rooms/{room_id}/bots", post(bots::add_bots))
+        .route("/api/rooms/{room_id}/chat", get(chat_page))
+        .nest("/api/admin", admin::routes())
+        .route("/metrics", get(metrics::metrics));
+    if let Some(dir) = &state.config.static_dir {
+        // Unknown paths get index.html, so the client's router can handle them
+        let index = ServeFile::new(dir.join("index.html"));
+        app = app.fallback_service(ServeDir::new(dir).fallback(index));
+    }
+    if let Some(cors) = cors_layer(&state.config.cors_origins) {
+        app = app.layer(cors);
+    }
+    app.with_state(state)
+}
+
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+    if origins.iter().any(|o| o == "*") {
+        return Some(CorsLayer::permissive());
+    }
+    let origins: Vec<_> = origins
+        .iter()
+        .filter_map(|o| {
+            o.parse()
+                .inspect_err(|_| tracing::error!("Ignoring invalid CORS origin {:?}", o))
+                .ok()
+        })
+        .collect();
+    Some(
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([Method::GET, Method::POST, Method::DELETE])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]),
+    )
+}
+
+fn load_rooms(config: &ServerConfig, dir: &std::path::Path) -> Vec<(String, Room)> {
+    let room_ids = storage::list_rooms(dir).unwrap_or_else(|e| {
+        tracing::error!("Cannot read data dir {}: {}", dir.display(), e);
+        vec![]
+    });
+    room_ids
+        .into_iter()
+        .filter_map(|room_id| restore_room(config, dir, &room_id).map(|room| (room_id, room)))
+        .collect()
+}
+
+/// The room stored in `dir`, if it has files there and they load.
+fn restore_room(config: &ServerConfig, dir: &std::path::Path, room_id: &str) -> Option<Room> {
+    if !storage::has_room(dir, room_id) {
+        return None;
+    }
+    match storage::load_room(dir, room_id) {
+        Ok(stored) => {
+            tracing::info!(
+                "Restoring room {} ({} events since snapshot)",
+                room_id,
+                stored.events.len()
+            );
+            Some(Room::restore(config, room_id, stored))
+        }
+        Err(e) => {
+            tracing::error!("Cannot restore room {}: {}", room_id, e);
+            None
+        }
+    }
+}
+
+/// Whether the room is stored in the data dir but not loaded, as after it expired.
+fn is_stored(config: &ServerConfig, room_id: &str) -> bool {
+    config
+        .data_dir
+        .as_deref()
+        .is_some_and(|dir| storage::has_room(dir, room_id))
 }
 
 async fn list_rooms(State(state): State<AppState>) -> impl IntoResponse {
-    let rooms: Vec<String> = state.rooms.iter().map(|r| r.key().clone()).collect();
+    let mut rooms: Vec<RoomInfo> = state
+        .rooms
+        .iter()
+        .filter(|r| !r.access.unlisted)
+        .map(|r| r.info(r.key(), state.config.max_players))
+        .collect();
+    rooms.sort_by_key(|r| std::cmp::Reverse(r.last_activity));
     Json(RoomList { rooms })
 }
 
+async fn create_room(
+    State(state): State<AppState>,
+    Json(req): Json<CreateRoom>,
+) -> Result<Json<CreatedRoom>, ApiError> {
+    let room_id = req
+        .room_id
+        .unwrap_or_else(|| format!("room-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]));
+    if room_id.trim().is_empty() {
+        return Err(ApiError::InvalidRoomId);
+    }
+
+    let access = RoomAccess::private(req.password.as_deref(), req.unlisted);
+    let invite_token = access.invite_token.clone().unwrap();
+    let persist_chat = req.persist_chat.unwrap_or(state.config.persist_chat);
+    match state.rooms.entry(room_id.clone()) {
+        dashmap::Entry::Occupied(_) => return Err(ApiError::RoomExists),
+        dashmap::Entry::Vacant(_) if is_stored(&state.config, &room_id) => {
+            return Err(ApiError::RoomExists);
+        }
+        dashmap::Entry::Vacant(entry) => {
+            entry.insert(Room::new(&state.config, &room_id, access, persist_chat));
+        }
+    }
+
+    tracing::info!("Created private room {}", room_id);
+    Ok(Json(CreatedRoom {
+        room_id,
+        invite_token,
+    }))
+}
+
+async fn chat_page(
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+    Query(query): Query<ChatQuery>,
+) -> Result<Json<ChatPage>, ApiError> {
+    let room = state
+        .rooms
+        .get(&room_id)
+        .map(|r| r.clone())
+        .ok_or(ApiError::RoomNotFound)?;
+    room.access
+        .check(query.invite_token.as_deref(), query.password.as_deref())
+        .map_err(|_| ApiError::Forbidden)?;
+    let limit = query.limit.unwrap_or(CHAT_PAGE).clamp(1, MAX_CHAT_PAGE);
+    let page = room
+        .authority
+        .lock()
+        .unwrap()
+        .chat
+        .page(query.channel, query.before, limit);
+    Ok(Json(page))
+}
+
 async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    let max = state.config.max_message_size;
+    ws.max_message_size(max)
+        .max_frame_size(max)
+        .on_upgrade(|socket| handle_socket(socket, state))
 }
 
 async fn handle_socket(socket: WebSocket, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
-    let mut rx_broadcast: Option<broadcast::Receiver<String>> = None;
-    let mut my_room_id: Option<String> = None;
+    let metrics = &*state.metrics;
+    let _open = metrics.open_socket();
+    let mut rx_broadcast: Option<broadcast::Receiver<Broadcast>> = None;
+    let mut my_room: Option<room::Connection> = None;
+    // Last event sequence this socket has been sent, directly or via broadcast.
+    let mut delivered: u64 = 0;
+    let ping_interval = state.config.ping_interval;
+    let mut ping =
+        tokio::time::interval_at(tokio::time::Instant::now() + ping_interval, ping_interval);
+    let mut last_seen = tokio::time::Instant::now();
+    // Agreed in `Hello`; a client that joins without one gets none.
+    let mut capabilities: Vec<Capability> = vec![];
+    // Whether to send MessagePack instead of JSON text.
+    let mut binary = false;
+    let mut rate_limit = state.config.rate_limit.map(TokenBucket::new);
 
     loop {
         tokio::select! {
             // A. Receive from Client
             msg = receiver.next() => {
-                match msg {
-                    Some(Ok(Message::Text(text))) => {
-                         // 1. Parse Message
-                        let client_msg: Result<ClientMessage, _> = serde_json::from_str(&text);
-
-                        match client_msg {
-                            Ok(ClientMessage::Join { room_id, player_id: _ }) => {
-                                // Create room if not exists
-                                let tx = state.rooms.entry(room_id.clone()).or_insert_with(|| {
-                                    let (tx, _) = broadcast::channel(100);
-                                    tx
-                                });
-
-                                // Subscribe
-                                rx_broadcast = Some(tx.subscribe());
-                                my_room_id = Some(room_id.clone());
-
-                                // Send Welcome
-                                let welcome = serde_json::to_string(&ServerMessage::Welcome { room_id: room_id.clone() }).unwrap();
-                                let _ = sender.send(Message::Text(welcome.into())).await;
-
-                                tracing::info!("Player joined room {}", room_id);
-                            }
+                if let Some(Ok(_)) = &msg {
+                    last_seen = tokio::time::Instant::now();
+                }
+                let client_msg: Result<ClientMessage, String> = match msg {
+                    Some(Ok(Message::Text(text))) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+                    Some(Ok(Message::Binary(bytes))) => msgpack::from_slice(&bytes).map_err(|e| e.to_string()),
+                    Some(Ok(Message::Close(_))) | None => break,
+                    _ => continue,
+                };
+                Metrics::inc(&metrics.messages_in);
+                if rate_limit.as_mut().is_some_and(|bucket| !bucket.take()) {
+                    Metrics::inc(&metrics.rate_limited);
+                    let reason = "Rate limit exceeded".to_owned();
+                    let error = match &client_msg {
+                        Ok(ClientMessage::Event { data, .. }) => rejection(event_id(data), reason),
+                        _ => ServerMessage::Error { msg: reason },
+                    };
+                    send(&mut sender, metrics, binary, &error).await;
+                    continue;
+                }
 
-                            Ok(ClientMessage::Event { sequence_id, data }) => {
-                                // Relay to Room
-                                if let Some(room_id) = &my_room_id
-                                    && let Some(tx) = state.rooms.get(room_id) {
-                                        // Re-wrap as Server Message
-                                        let relay_msg = serde_json::to_string(&ServerMessage::Event { sequence_id, data }).unwrap();
-                                        // We send raw string to broadcast
-                                        let _ = tx.send(relay_msg);
-                                    }
-                            }
+                match client_msg {
+                    Ok(ClientMessage::Hello { protocol_version, capabilities: offered }) => {
+                        if my_room.is_some() {
+                            send(&mut sender, metrics, binary, &ServerMessage::Error { msg: "Hello must come before Join".to_owned() }).await;
+                            continue;
+                        }
+                        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&protocol_version) {
+                            tracing::info!("Rejected protocol version {}", protocol_version);
+                            let reason = HelloError::UnsupportedVersion { min: MIN_PROTOCOL_VERSION, max: PROTOCOL_VERSION };
+                            send(&mut sender, metrics, binary, &ServerMessage::HelloRejected { reason }).await;
+                            let _ = sender.close().await;
+                            break;
+                        }
+                        capabilities = state.config.capabilities().into_iter().filter(|c| offered.contains(c)).collect();
+                        // Always JSON, as the client cannot know the encoding before it
+                        send(&mut sender, metrics, false, &ServerMessage::Hello { protocol_version, capabilities: capabilities.clone() }).await;
+                        binary = capabilities.contains(&Capability::MessagePack);
+                    }
 
-                            Ok(ClientMessage::SyncRequest { requestor_id }) => {
-                                // Broadcast SyncRequest to peers
-                                if let Some(room_id) = &my_room_id
-                                    && let Some(tx) = state.rooms.get(room_id) {
-                                        let relay_msg = serde_json::to_string(&ServerMessage::SyncRequest { requestor_id }).unwrap();
-                                        let _ = tx.send(relay_msg);
-                                    }
+                    Ok(ClientMessage::Join { room_id, player_id, invite_token, password, session_token }) => {
+                        // Load the room from disk or create it if not exists, and take a seat
+                        // while the entry is locked so the reaper cannot remove it in between
+                        let joined = {
+                            let room = state.rooms.entry(room_id.clone()).or_insert_with(|| {
+                                state
+                                    .config
+                                    .data_dir
+                                    .as_deref()
+                                    .and_then(|dir| restore_room(&state.config, dir, &room_id))
+                                    .unwrap_or_else(|| Room::new(&state.config, &room_id, RoomAccess::default(), state.config.persist_chat))
+                            });
+                            room.access
+                                .check(invite_token.as_deref(), password.as_deref())
+                                .and_then(|()| room.connect(&player_id, session_token.as_deref(), state.config.max_players, my_room.as_ref()))
+                        };
+                        let (connection, session_token) = match joined {
+                            Ok(joined) => joined,
+                            Err(reason) => {
+                                tracing::info!("Rejected join to room {}: {:?}", room_id, reason);
+                                send(&mut sender, metrics, binary, &ServerMessage::JoinRejected { room_id, reason }).await;
+                                continue;
                             }
+                        };
+
+                        // Only now leave the previous room; a rejected join keeps it
+                        rx_broadcast = Some(connection.room.tx.subscribe());
+                        my_room = Some(connection);
+                        delivered = 0;
 
-                            Err(e) => {
-                                tracing::error!("Bad message: {:?}", e);
+                        // Send Welcome
+                        let welcome = ServerMessage::Welcome {
+                            room_id: room_id.clone(),
+                            player_id: player_id.clone(),
+                            session_token,
+                        };
+                        send(&mut sender, metrics, binary, &welcome).await;
+
+                        // Who is already here; later changes come via broadcast
+                        let online = my_room
+                            .as_ref()
+                            .filter(|_| capabilities.contains(&Capability::Presence))
+                            .map(|c| c.room.online_players())
+                            .unwrap_or_default();
+                        for player_id in online {
+                            send(&mut sender, metrics, binary, &ServerMessage::PresenceChanged { player_id, connected: true }).await;
+                        }
+
+                        tracing::info!("Player {} joined room {}", player_id, room_id);
+                    }
+
+                    Ok(ClientMessage::Event { sequence_id: _, data }) => {
+                        if let Some(connection) = &my_room {
+                            let room = &connection.room;
+                            room.touch();
+                            // Sequence, log and broadcast; rejections go to the sender only
+                            let event_id = event_id(&data);
+                            let result = room.authority.lock().unwrap().submit(data, Some(&connection.player_id), &room.tx);
+                            if let Err(reason) = result {
+                                tracing::debug!("Rejected event: {}", reason);
+                                let error = rejection(event_id, reason);
+                                send(&mut sender, metrics, binary, &error).await;
                             }
                         }
                     }
-                    Some(Ok(Message::Close(_))) | None => {
-                        break;
+
+                    Ok(ClientMessage::SyncRequest { requestor_id: _ }) => {
+                        if let Some(room) = my_room.as_ref().map(|c| &c.room) {
+                            // The server has the state; answer directly
+                            let (sync_msg, sequence_id) = {
+                                let authority = room.authority.lock().unwrap();
+                                (authority.sync_message(), authority.sequence_id)
+                            };
+                            delivered = sequence_id;
+                            send(&mut sender, metrics, binary, &sync_msg).await;
+                        }
+                    }
+
+                    Ok(ClientMessage::Resume { last_sequence_id }) => {
+                        if let Some(room) = my_room.as_ref().map(|c| &c.room) {
+                            delivered = catch_up(&mut sender, metrics, binary, room, last_sequence_id, capabilities.contains(&Capability::Delta)).await;
+                        }
+                    }
+
+                    Err(e) => {
+                        Metrics::inc(&metrics.parse_errors);
+                        tracing::error!("Bad message: {:?}", e);
                     }
-                    _ => {}
                 }
             }
 
-            // B. Receive from Room (Broadcast)
+            // B. Heartbeat
+            _ = ping.tick() => {
+                if last_seen.elapsed() >= state.config.ping_timeout {
+                    tracing::info!("Socket timed out");
+                    break;
+                }
+                if sender.send(Message::Ping(Default::default())).await.is_err() {
+                    break;
+                }
+            }
+
+            // C. Kicked by an admin
+            reason = async {
+                match my_room.as_mut() {
+                    Some(connection) => (&mut connection.kicked).await,
+                    None => futures::future::pending().await,
+                }
+            } => {
+                if let Ok(reason) = reason {
+                    tracing::info!("Closing socket: {:?}", reason);
+                    send(&mut sender, metrics, binary, &ServerMessage::Kicked { reason }).await;
+                }
+                let _ = sender.close().await;
+                break;
+            }
+
+            // D. Receive from Room (Broadcast)
             res = async {
                 match rx_broadcast.as_mut() {
                     Some(rx) => rx.recv().await,
@@ -158,11 +576,25 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             } => {
                 match res {
                     Ok(msg) => {
+                        if msg.requires.as_ref().is_some_and(|c| !capabilities.contains(c)) {
+                            continue;
+                        }
+                        // Skip events already replayed by a catch-up
+                        if let Some(sequence_id) = msg.sequence_id {
+                            if sequence_id <= delivered {
+                                continue;
+                            }
+                            delivered = sequence_id;
+                        }
                         // Forward to Client
-                        let _ = sender.send(Message::Text(msg.into())).await;
+                        send_frame(&mut sender, metrics, msg.frame(binary)).await;
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
-                        tracing::warn!("Client lagged, skipped {} messages", skipped);
+                        Metrics::inc(&metrics.lagged);
+                        tracing::warn!("Client lagged, skipped {} messages; catching up", skipped);
+                        if let Some(room) = my_room.as_ref().map(|c| &c.room) {
+                            delivered = catch_up(&mut sender, metrics, binary, room, delivered, capabilities.contains(&Capability::Delta)).await;
+                        }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => {
                         // Room closed
@@ -173,3 +605,52 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         }
     }
 }
+
+/// Sends the events after `after` (or a snapshot, if they are no longer buffered).
+/// Returns the sequence number the socket is now up to.
+async fn catch_up(
+    sender: &mut WsSender,
+    metrics: &Metrics,
+    binary: bool,
+    room: &Room,
+    after: u64,
+    delta: bool,
+) -> u64 {
+    let (messages, sequence_id) = {
+        let authority = room.authority.lock().unwrap();
+        (authority.catch_up(after, delta), authority.sequence_id)
+    };
+    for msg in messages {
+        if !send(sender, metrics, binary, &msg).await {
+            break;
+        }
+    }
+    sequence_id
+}
+
+/// Tells the sender their event was refused, by id so the client can drop its
+/// prediction. Events without a readable id get a plain `Error`.
+fn rejection(event_id: Option<uuid::Uuid>, reason: String) -> ServerMessage {
+    match event_id {
+        Some(event_id) => ServerMessage::Rejected { event_id, reason },
+        None => ServerMessage::Error { msg: reason },
+    }
+}
+
+type WsSender = futures::stream::SplitSink<WebSocket, Message>;
+
+/// Sends one message and counts it, as MessagePack if `binary`. Returns false if
+/// the socket is gone.
+async fn send(sender: &mut WsSender, metrics: &Metrics, binary: bool, msg: &ServerMessage) -> bool {
+    send_frame(sender, metrics, room::encode(msg, binary)).await
+}
+
+/// Sends an encoded message and counts it. A message that could not be encoded
+/// is skipped.
+async fn send_frame(sender: &mut WsSender, metrics: &Metrics, frame: Option<Message>) -> bool {
+    let Some(frame) = frame else {
+        return true;
+    };
+    Metrics::inc(&metrics.messages_out);
+    sender.send(frame).await.is_ok()
+}
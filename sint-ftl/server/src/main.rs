@@ -1,19 +1,127 @@
-use std::net::SocketAddr;
+use clap::builder::BoolishValueParser;
+use clap::{ArgAction, Parser, ValueEnum};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Every option can also be set through the environment variable named in its help.
+#[derive(Parser, Debug)]
+#[command(version, about = "Sint FTL game server")]
+struct Args {
+    /// Address to listen on.
+    #[arg(long, env = "SINT_BIND", default_value = "0.0.0.0")]
+    bind: IpAddr,
+
+    #[arg(long, env = "SINT_PORT", default_value_t = 3000)]
+    port: u16,
+
+    /// Validate and sequence events with sint-core instead of relaying them.
+    #[arg(long, env = "SINT_AUTHORITATIVE", value_parser = BoolishValueParser::new())]
+    authoritative: bool,
+
+    /// Keep room event logs and snapshots here, so games survive a restart.
+    #[arg(long, env = "SINT_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Keep each room's chat in the data dir. Rooms created through the API can
+    /// choose for themselves.
+    #[arg(
+        long,
+        env = "SINT_PERSIST_CHAT",
+        value_parser = BoolishValueParser::new(),
+        action = ArgAction::Set,
+        default_value = "true"
+    )]
+    persist_chat: bool,
+
+    /// Chat messages each room keeps, in memory and on disk; 0 keeps all.
+    #[arg(long, env = "SINT_CHAT_HISTORY", default_value_t = 1000)]
+    chat_history: usize,
+
+    /// Bearer token for /api/admin. The admin API is off without one.
+    #[arg(long, env = "SINT_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+
+    /// Built web client to serve at / (e.g. client/dist).
+    #[arg(long, env = "SINT_STATIC_DIR")]
+    static_dir: Option<PathBuf>,
+
+    /// Origins allowed to call the API from a browser, comma separated; * for any.
+    #[arg(long, env = "SINT_CORS_ORIGINS", value_delimiter = ',')]
+    cors_origins: Vec<String>,
+
+    /// Largest WebSocket message accepted from a client, in bytes.
+    #[arg(long, env = "SINT_MAX_MESSAGE_SIZE", default_value_t = 1 << 20)]
+    max_message_size: usize,
+
+    /// Messages per second each connection may send; 0 for no limit.
+    #[arg(long, env = "SINT_RATE_LIMIT", default_value_t = 20.0)]
+    rate_limit: f64,
+
+    /// Messages a connection may send in a burst after being quiet.
+    #[arg(long, env = "SINT_RATE_BURST", default_value_t = 60)]
+    rate_burst: u32,
+
+    /// Log output format. Filter with RUST_LOG.
+    #[arg(long, env = "SINT_LOG_FORMAT", value_enum, default_value_t = LogFormat::Full)]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum LogFormat {
+    Full,
+    Compact,
+    Pretty,
+}
+
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "sint_server=debug,tower_http=debug".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let args = Args::parse();
+
+    let filter = tracing_subscriber::EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "sint_server=debug,tower_http=debug".into()),
+    );
+    let registry = tracing_subscriber::registry().with(filter);
+    match args.log_format {
+        LogFormat::Full => registry.with(tracing_subscriber::fmt::layer()).init(),
+        LogFormat::Compact => registry
+            .with(tracing_subscriber::fmt::layer().compact())
+            .init(),
+        LogFormat::Pretty => registry
+            .with(tracing_subscriber::fmt::layer().pretty())
+            .init(),
+    }
 
-    let app = sint_server::create_app();
+    let config = sint_server::ServerConfig {
+        authoritative: args.authoritative,
+        data_dir: args.data_dir,
+        persist_chat: args.persist_chat,
+        chat_history: args.chat_history,
+        admin_token: args.admin_token.filter(|t| !t.is_empty()),
+        static_dir: args.static_dir,
+        cors_origins: args.cors_origins,
+        max_message_size: args.max_message_size,
+        rate_limit: (args.rate_limit > 0.0).then_some(sint_server::RateLimit {
+            per_second: args.rate_limit,
+            burst: args.rate_burst.max(1),
+        }),
+        ..Default::default()
+    };
+    if config.authoritative {
+        tracing::info!("authoritative mode: validating and sequencing events");
+    }
+    if let Some(dir) = &config.data_dir {
+        tracing::info!("persisting rooms to {}", dir.display());
+    }
+    if config.admin_token.is_some() {
+        tracing::info!("admin API enabled at /api/admin");
+    }
+    if let Some(dir) = &config.static_dir {
+        tracing::info!("serving the web client from {}", dir.display());
+    }
+    let app = sint_server::create_app_with_config(config);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    let addr = SocketAddr::new(args.bind, args.port);
     tracing::info!("listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
@@ -0,0 +1,125 @@
+//! Server counters, served at `/metrics` in the Prometheus text format.
+
+use crate::AppState;
+use axum::{extract::State, http::header, response::IntoResponse};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Open WebSocket connections, joined to a room or not.
+    pub sockets: AtomicU64,
+    pub messages_in: AtomicU64,
+    pub messages_out: AtomicU64,
+    /// Times a socket fell behind its room's broadcast channel.
+    pub lagged: AtomicU64,
+    /// Client messages that were not valid `ClientMessage` JSON.
+    pub parse_errors: AtomicU64,
+    /// Client messages dropped for exceeding `ServerConfig::rate_limit`.
+    pub rate_limited: AtomicU64,
+}
+
+impl Metrics {
+    pub fn inc(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a socket as open until the guard is dropped.
+    pub fn open_socket(&self) -> SocketGuard<'_> {
+        Self::inc(&self.sockets);
+        SocketGuard(self)
+    }
+}
+
+pub struct SocketGuard<'a>(&'a Metrics);
+
+impl Drop for SocketGuard<'_> {
+    fn drop(&mut self) {
+        self.0.sockets.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(&state),
+    )
+}
+
+fn render(state: &AppState) -> String {
+    let m = &state.metrics;
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: u64| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(out, "{} {}", name, value);
+    };
+    metric(
+        "sint_rooms",
+        "gauge",
+        "Rooms currently open.",
+        state.rooms.len() as u64,
+    );
+    metric(
+        "sint_sockets",
+        "gauge",
+        "Open WebSocket connections.",
+        m.sockets.load(Ordering::Relaxed),
+    );
+    metric(
+        "sint_messages_in_total",
+        "counter",
+        "WebSocket messages received from clients.",
+        m.messages_in.load(Ordering::Relaxed),
+    );
+    metric(
+        "sint_messages_out_total",
+        "counter",
+        "WebSocket messages sent to clients.",
+        m.messages_out.load(Ordering::Relaxed),
+    );
+    metric(
+        "sint_broadcast_lagged_total",
+        "counter",
+        "Times a socket fell behind its room and had to catch up.",
+        m.lagged.load(Ordering::Relaxed),
+    );
+    metric(
+        "sint_parse_errors_total",
+        "counter",
+        "Client messages that could not be parsed.",
+        m.parse_errors.load(Ordering::Relaxed),
+    );
+    metric(
+        "sint_rate_limited_total",
+        "counter",
+        "Client messages dropped by the per-connection rate limit.",
+        m.rate_limited.load(Ordering::Relaxed),
+    );
+
+    let mut rooms: Vec<(String, u64)> = state
+        .rooms
+        .iter()
+        .map(|r| (r.key().clone(), r.authority.lock().unwrap().sequence_id))
+        .collect();
+    rooms.sort();
+    out.push_str("# HELP sint_room_events_total Events sequenced in the room.\n");
+    out.push_str("# TYPE sint_room_events_total counter\n");
+    for (room_id, events) in rooms {
+        let _ = writeln!(
+            out,
+            "sint_room_events_total{{room=\"{}\"}} {}",
+            escape_label(&room_id),
+            events
+        );
+    }
+    out
+}
+
+/// Label values escape backslash, double quote and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
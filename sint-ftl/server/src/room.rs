@@ -0,0 +1,596 @@
+use crate::authority::{Authority, SERVER_PLAYER_ID};
+use crate::storage::{self, ChatFile, RoomLog, StoredRoom};
+use crate::{Capability, JoinError, KickReason, ServerConfig, ServerMessage, msgpack};
+use axum::extract::ws::Message;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sint_core::{GamePhase, MapLayout};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+
+/// A message for every socket in a room.
+#[derive(Clone, Debug)]
+pub struct Broadcast {
+    /// Set for sequenced events, so a socket can skip what it already replayed.
+    pub sequence_id: Option<u64>,
+    /// Only for sockets that negotiated this capability.
+    pub requires: Option<Capability>,
+    frames: Arc<Frames>,
+}
+
+/// A broadcast's message and its encodings, shared by every receiver.
+#[derive(Debug)]
+struct Frames {
+    msg: ServerMessage,
+    json: OnceLock<Option<Message>>,
+    msgpack: OnceLock<Option<Message>>,
+}
+
+impl Broadcast {
+    pub fn new(sequence_id: Option<u64>, msg: ServerMessage, requires: Option<Capability>) -> Self {
+        Broadcast {
+            sequence_id,
+            requires,
+            frames: Arc::new(Frames {
+                msg,
+                json: OnceLock::new(),
+                msgpack: OnceLock::new(),
+            }),
+        }
+    }
+
+    /// The message as a frame, MessagePack if `binary`. Each encoding is made once,
+    /// by the first socket that needs it.
+    pub fn frame(&self, binary: bool) -> Option<Message> {
+        let frames = &self.frames;
+        let cell = if binary {
+            &frames.msgpack
+        } else {
+            &frames.json
+        };
+        cell.get_or_init(|| encode(&frames.msg, binary)).clone()
+    }
+}
+
+/// Serializes a message for one socket, MessagePack if `binary`. Returns None,
+/// after logging why, if it cannot be encoded.
+pub fn encode(msg: &ServerMessage, binary: bool) -> Option<Message> {
+    let frame = if binary {
+        msgpack::to_vec(msg)
+            .map(|bytes| Message::Binary(bytes.into()))
+            .map_err(|e| e.to_string())
+    } else {
+        serde_json::to_string(msg)
+            .map(|text| Message::Text(text.into()))
+            .map_err(|e| e.to_string())
+    };
+    frame
+        .inspect_err(|e| tracing::error!("Cannot encode message: {}", e))
+        .ok()
+}
+
+#[derive(Clone)]
+pub struct Room {
+    pub tx: broadcast::Sender<Broadcast>,
+    /// The server's copy of the game, which sequences every event.
+    pub authority: Arc<Mutex<Authority>>,
+    /// Sockets currently in the room, by connection id.
+    pub sockets: Arc<Mutex<HashMap<u64, Socket>>>,
+    /// Unix milliseconds. Rooms restored from disk count from when they were loaded.
+    pub created_at: u64,
+    /// Unix milliseconds of the last join, leave or event.
+    pub last_activity: Arc<AtomicU64>,
+    pub access: Arc<RoomAccess>,
+    pub sessions: Arc<Mutex<Sessions>>,
+}
+
+/// A socket seated in a room, as seen from the room.
+#[derive(Debug)]
+pub struct Socket {
+    pub player_id: String,
+    /// A server-hosted bot rather than a client.
+    pub bot: bool,
+    /// Unix milliseconds.
+    pub connected_at: u64,
+    /// Taken to disconnect the socket.
+    kick: Option<oneshot::Sender<KickReason>>,
+}
+
+/// One socket in the admin room listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketInfo {
+    pub connection_id: u64,
+    pub player_id: String,
+    pub bot: bool,
+    /// Unix seconds.
+    pub connected_at: u64,
+}
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Who may join a room. Rooms created implicitly by a `Join` are public; rooms
+/// created through the REST API require their invite token or password.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoomAccess {
+    pub invite_token: Option<String>,
+    pub password: Option<PasswordHash>,
+    /// Hidden from `/api/rooms`.
+    pub unlisted: bool,
+}
+
+/// Which connection may speak for which player. The first socket to join as a
+/// player gets a session token; later joins as that player must present it.
+#[derive(Debug, Default)]
+pub struct Sessions {
+    /// Player id -> SHA-256 of the session token.
+    tokens: HashMap<String, String>,
+    /// Where to save `tokens` when a seat is claimed.
+    save_to: Option<(PathBuf, String)>,
+}
+
+impl Sessions {
+    /// Binds `player_id` to the caller. Returns the token to keep for reconnects.
+    pub fn claim(&mut self, player_id: &str, token: Option<&str>) -> Result<String, JoinError> {
+        if player_id.is_empty() || player_id == SERVER_PLAYER_ID {
+            return Err(JoinError::InvalidPlayerId);
+        }
+        match (self.tokens.get(player_id), token) {
+            (Some(hash), Some(token)) if *hash == sha256_hex(&[token]) => Ok(token.to_owned()),
+            (Some(_), Some(_)) => Err(JoinError::InvalidSessionToken),
+            (Some(_), None) => Err(JoinError::SeatTaken),
+            (None, _) => {
+                // Unknown seat: a stale token from an expired room is simply replaced.
+                let token = Uuid::new_v4().simple().to_string();
+                self.tokens
+                    .insert(player_id.to_owned(), sha256_hex(&[&token]));
+                self.save();
+                Ok(token)
+            }
+        }
+    }
+
+    fn save(&self) {
+        if let Some((dir, room_id)) = &self.save_to
+            && let Err(e) = storage::save_sessions(dir, room_id, &self.tokens)
+        {
+            tracing::error!("Cannot save sessions for room {}: {}", room_id, e);
+        }
+    }
+}
+
+pub(crate) fn sha256_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Salted SHA-256, so passwords are not kept in the clear in room files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHash {
+    pub salt: String,
+    pub hash: String,
+}
+
+impl PasswordHash {
+    pub fn new(password: &str) -> Self {
+        let salt = Uuid::new_v4().simple().to_string();
+        let hash = Self::digest(&salt, password);
+        PasswordHash { salt, hash }
+    }
+
+    pub fn matches(&self, password: &str) -> bool {
+        Self::digest(&self.salt, password) == self.hash
+    }
+
+    fn digest(salt: &str, password: &str) -> String {
+        sha256_hex(&[salt, password])
+    }
+}
+
+impl RoomAccess {
+    /// A private room with a fresh invite token.
+    pub fn private(password: Option<&str>, unlisted: bool) -> Self {
+        RoomAccess {
+            invite_token: Some(Uuid::new_v4().simple().to_string()),
+            password: password.map(PasswordHash::new),
+            unlisted,
+        }
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.invite_token.is_some()
+    }
+
+    /// Whether `invite_token` is this private room's, which its creator got back from
+    /// `POST /api/rooms`. Public rooms have no owner.
+    pub fn is_owner(&self, invite_token: Option<&str>) -> bool {
+        self.invite_token
+            .as_deref()
+            .is_some_and(|token| invite_token == Some(token))
+    }
+
+    /// Either credential is enough; a wrong one is rejected even if the other is absent.
+    pub fn check(
+        &self,
+        invite_token: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<(), JoinError> {
+        if !self.is_private() {
+            return Ok(());
+        }
+        if let Some(token) = invite_token {
+            return if self.invite_token.as_deref() == Some(token) {
+                Ok(())
+            } else {
+                Err(JoinError::InvalidInviteToken)
+            };
+        }
+        match (password, &self.password) {
+            (Some(password), Some(hash)) if hash.matches(password) => Ok(()),
+            (Some(_), Some(_)) => Err(JoinError::WrongPassword),
+            _ => Err(JoinError::CredentialsRequired),
+        }
+    }
+}
+
+/// Lobby listing for one room, as served by `/api/rooms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomInfo {
+    pub id: String,
+    /// Needs an invite token or password to join.
+    pub private: bool,
+    /// Player names in the game, in id order.
+    pub players: Vec<String>,
+    pub player_count: usize,
+    pub connections: usize,
+    pub max_players: usize,
+    pub phase: GamePhase,
+    pub layout: MapLayout,
+    pub boss: String,
+    pub boss_level: u32,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Unix seconds.
+    pub last_activity: u64,
+}
+
+impl Room {
+    /// `persist_chat` keeps the room's chat on disk too, if the server has a data dir.
+    pub fn new(
+        config: &ServerConfig,
+        room_id: &str,
+        access: RoomAccess,
+        persist_chat: bool,
+    ) -> Self {
+        if let Some(dir) = &config.data_dir
+            && access.is_private()
+            && let Err(e) = storage::save_access(dir, room_id, &access)
+        {
+            tracing::error!("Cannot save access for room {}: {}", room_id, e);
+        }
+        Self::with_authority(
+            config,
+            room_id,
+            access,
+            HashMap::new(),
+            persist_chat,
+            |log, chat_file| Authority::new(config, log, chat_file),
+        )
+    }
+
+    /// A room keeps its chat after a restart if it has a chat file.
+    pub fn restore(config: &ServerConfig, room_id: &str, mut stored: StoredRoom) -> Self {
+        let access = stored.access.take().unwrap_or_default();
+        let sessions = std::mem::take(&mut stored.sessions);
+        let persist_chat = stored.chat.is_some();
+        Self::with_authority(
+            config,
+            room_id,
+            access,
+            sessions,
+            persist_chat,
+            |log, chat_file| Authority::restore(config, log, chat_file, stored),
+        )
+    }
+
+    fn with_authority(
+        config: &ServerConfig,
+        room_id: &str,
+        access: RoomAccess,
+        session_tokens: HashMap<String, String>,
+        persist_chat: bool,
+        make: impl FnOnce(Option<RoomLog>, Option<ChatFile>) -> Authority,
+    ) -> Self {
+        let (tx, _) = broadcast::channel(config.broadcast_capacity);
+        let log = config.data_dir.as_ref().and_then(|dir| {
+            RoomLog::open(dir, room_id, config.compact_every)
+                .inspect_err(|e| tracing::error!("Cannot open log for room {}: {}", room_id, e))
+                .ok()
+        });
+        let chat_file = config
+            .data_dir
+            .as_ref()
+            .filter(|_| persist_chat)
+            .and_then(|dir| {
+                ChatFile::open(dir, room_id, config.chat_history)
+                    .inspect_err(|e| {
+                        tracing::error!("Cannot open chat log for room {}: {}", room_id, e)
+                    })
+                    .ok()
+            });
+        let now = now_millis();
+        Room {
+            tx,
+            authority: Arc::new(Mutex::new(make(log, chat_file))),
+            sockets: Arc::new(Mutex::new(HashMap::new())),
+            created_at: now,
+            last_activity: Arc::new(AtomicU64::new(now)),
+            access: Arc::new(access),
+            sessions: Arc::new(Mutex::new(Sessions {
+                tokens: session_tokens,
+                save_to: config
+                    .data_dir
+                    .as_ref()
+                    .map(|dir| (dir.clone(), room_id.to_owned())),
+            })),
+        }
+    }
+
+    /// Takes a seat in the room as `player_id`. Returns the connection and the
+    /// player's session token. The seat of `leaving`, the socket's current
+    /// connection, is free for this one, so rejoining a full room works.
+    pub fn connect(
+        &self,
+        player_id: &str,
+        session_token: Option<&str>,
+        max_players: usize,
+        leaving: Option<&Connection>,
+    ) -> Result<(Connection, String), JoinError> {
+        self.seat(
+            player_id,
+            session_token,
+            max_players,
+            false,
+            leaving.map(|c| c.id),
+        )
+    }
+
+    /// Takes a seat for a server-hosted bot. Bots count towards `max_players`.
+    pub fn connect_bot(
+        &self,
+        player_id: &str,
+        max_players: usize,
+    ) -> Result<Connection, JoinError> {
+        self.seat(player_id, None, max_players, true, None)
+            .map(|(connection, _)| connection)
+    }
+
+    fn seat(
+        &self,
+        player_id: &str,
+        session_token: Option<&str>,
+        max_players: usize,
+        bot: bool,
+        leaving: Option<u64>,
+    ) -> Result<(Connection, String), JoinError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let mut sockets = self.sockets.lock().unwrap();
+        // Check the seat before adding the socket, so a rejected claim takes no slot.
+        let leaving = leaving.is_some_and(|id| sockets.contains_key(&id));
+        if sockets.len() - usize::from(leaving) >= max_players {
+            return Err(JoinError::RoomFull);
+        }
+        let token = sessions.claim(player_id, session_token)?;
+        let id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+        let first = !sockets.values().any(|s| s.player_id == player_id);
+        let (kick, kicked) = oneshot::channel();
+        sockets.insert(
+            id,
+            Socket {
+                player_id: player_id.to_owned(),
+                bot,
+                connected_at: now_millis(),
+                kick: Some(kick),
+            },
+        );
+        drop(sockets);
+        if first {
+            self.announce_presence(player_id, true);
+        }
+        self.touch();
+        let connection = Connection {
+            id,
+            room: self.clone(),
+            player_id: player_id.to_owned(),
+            kicked,
+        };
+        Ok((connection, token))
+    }
+
+    pub fn touch(&self) {
+        self.last_activity.store(now_millis(), Ordering::Relaxed);
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.sockets.lock().unwrap().len()
+    }
+
+    /// Players with at least one socket in the room, in id order.
+    pub fn online_players(&self) -> Vec<String> {
+        let mut players: Vec<String> = self
+            .sockets
+            .lock()
+            .unwrap()
+            .values()
+            .map(|s| s.player_id.clone())
+            .collect();
+        players.sort();
+        players.dedup();
+        players
+    }
+
+    /// Tells the room a player came or went. A player with several sockets (a
+    /// reconnect racing the old one's timeout) is online until the last one leaves.
+    fn announce_presence(&self, player_id: &str, connected: bool) {
+        let msg = ServerMessage::PresenceChanged {
+            player_id: player_id.to_owned(),
+            connected,
+        };
+        let _ = self
+            .tx
+            .send(Broadcast::new(None, msg, Some(Capability::Presence)));
+    }
+
+    /// Sockets that are not bots.
+    pub fn human_count(&self) -> usize {
+        self.sockets
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|s| !s.bot)
+            .count()
+    }
+
+    /// Disconnects one socket. Returns false if it is not in the room.
+    pub fn kick(&self, connection_id: u64, reason: KickReason) -> bool {
+        let mut sockets = self.sockets.lock().unwrap();
+        let Some(socket) = sockets.get_mut(&connection_id) else {
+            return false;
+        };
+        if let Some(kick) = socket.kick.take() {
+            let _ = kick.send(reason);
+        }
+        true
+    }
+
+    /// Disconnects every socket.
+    pub fn kick_all(&self, reason: KickReason) {
+        for socket in self.sockets.lock().unwrap().values_mut() {
+            if let Some(kick) = socket.kick.take() {
+                let _ = kick.send(reason);
+            }
+        }
+    }
+
+    pub fn socket_infos(&self) -> Vec<SocketInfo> {
+        let mut infos: Vec<SocketInfo> = self
+            .sockets
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&connection_id, socket)| SocketInfo {
+                connection_id,
+                player_id: socket.player_id.clone(),
+                bot: socket.bot,
+                connected_at: socket.connected_at / 1000,
+            })
+            .collect();
+        infos.sort_by_key(|s| s.connection_id);
+        infos
+    }
+
+    pub fn is_expired(&self, now: u64, ttl: Duration) -> bool {
+        self.connection_count() == 0
+            && now.saturating_sub(self.last_activity.load(Ordering::Relaxed))
+                >= ttl.as_millis() as u64
+    }
+
+    pub fn info(&self, id: &str, max_players: usize) -> RoomInfo {
+        let authority = self.authority.lock().unwrap();
+        let state = &authority.state;
+        RoomInfo {
+            id: id.to_owned(),
+            private: self.access.is_private(),
+            players: state.players.values().map(|p| p.name.clone()).collect(),
+            player_count: state.players.len(),
+            connections: self.connection_count(),
+            max_players,
+            phase: state.phase,
+            layout: state.layout,
+            boss: state.enemy.name.clone(),
+            boss_level: state.boss_level,
+            created_at: self.created_at / 1000,
+            last_activity: self.last_activity.load(Ordering::Relaxed) / 1000,
+        }
+    }
+}
+
+/// A socket's seat in a room. Dropping it (on disconnect or when joining another
+/// room) releases the seat and starts the idle clock if the room is now empty.
+pub struct Connection {
+    /// Unique across rooms, for the admin API.
+    pub id: u64,
+    pub room: Room,
+    /// The player this socket speaks for.
+    pub player_id: String,
+    /// Fires when an admin kicks this socket or closes the room.
+    pub kicked: oneshot::Receiver<KickReason>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let last = {
+            let mut sockets = self.room.sockets.lock().unwrap();
+            sockets.remove(&self.id);
+            !sockets.values().any(|s| s.player_id == self.player_id)
+        };
+        if last {
+            self.room.announce_presence(&self.player_id, false);
+        }
+        self.room.touch();
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Unloads rooms that have been empty for `ttl`. Their files stay in the data
+/// dir, and the next join loads the room from them.
+pub fn reap_expired(rooms: &DashMap<String, Room>, config: &ServerConfig) {
+    let now = now_millis();
+    let expired: Vec<String> = rooms
+        .iter()
+        .filter(|r| r.is_expired(now, config.room_ttl))
+        .map(|r| r.key().clone())
+        .collect();
+    for room_id in expired {
+        // Re-checked under the map lock, in case someone joined in between.
+        if rooms
+            .remove_if(&room_id, |_, room| room.is_expired(now, config.room_ttl))
+            .is_some()
+        {
+            tracing::info!("Room {} expired", room_id);
+        }
+    }
+}
+
+/// Reaps expired rooms until the app is dropped.
+pub fn spawn_reaper(rooms: Weak<DashMap<String, Room>>, config: Arc<ServerConfig>) {
+    if tokio::runtime::Handle::try_current().is_err() {
+        return;
+    }
+    let period = (config.room_ttl / 2).clamp(Duration::from_millis(50), Duration::from_secs(60));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let Some(rooms) = rooms.upgrade() else { break };
+            reap_expired(&rooms, &config);
+        }
+    });
+}
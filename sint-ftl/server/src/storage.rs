@@ -0,0 +1,475 @@
+//! Append-only event logs and snapshots, one pair of files per room.
+//!
+//! `<room>.jsonl` holds one `LogEntry` per broadcast event. Every so often the
+//! room's state is written to `<room>.snapshot.json` and the log is truncated.
+//! A crash between the two leaves entries the snapshot already covers; loading
+//! skips them by sequence number. Private rooms also have `<room>.access.json`, and
+//! `<room>.sessions.json` holds hashes of the players' session tokens. Rooms that
+//! keep their chat append it to `<room>.chat.jsonl`, which is cut back to the newest
+//! entries when it grows past twice the room's chat history.
+//!
+//! A running room writes its log and chat through a `RoomWriter`, whose thread does
+//! the file I/O so the room's lock is never held across a disk write.
+
+use crate::room::RoomAccess;
+use serde::{Deserialize, Serialize};
+use sint_core::GameState;
+use sint_core::chat::ChatEntry;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tokio::sync::oneshot;
+
+const LOG_EXT: &str = "jsonl";
+const SNAPSHOT_SUFFIX: &str = ".snapshot.json";
+const ACCESS_SUFFIX: &str = ".access.json";
+const SESSIONS_SUFFIX: &str = ".sessions.json";
+const CHAT_SUFFIX: &str = ".chat.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub sequence_id: u64,
+    /// Unix milliseconds at which the event was sequenced.
+    #[serde(default)]
+    pub timestamp: u64,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Sequence number of the last event included in `state`.
+    pub sequence_id: u64,
+    pub state: GameState,
+}
+
+/// What is on disk for one room.
+#[derive(Debug, Default)]
+pub struct StoredRoom {
+    pub snapshot: Option<Snapshot>,
+    /// Events after the snapshot, in order.
+    pub events: Vec<LogEntry>,
+    /// `None` for public rooms.
+    pub access: Option<RoomAccess>,
+    /// Player id -> session token hash.
+    pub sessions: HashMap<String, String>,
+    /// `None` if the room does not keep its chat.
+    pub chat: Option<Vec<ChatEntry>>,
+}
+
+pub struct RoomLog {
+    log_path: PathBuf,
+    snapshot_path: PathBuf,
+    file: File,
+    since_snapshot: u64,
+    compact_every: u64,
+}
+
+impl RoomLog {
+    pub fn open(data_dir: &Path, room_id: &str, compact_every: u64) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        let (log_path, snapshot_path) = paths(data_dir, room_id);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)?;
+        let since_snapshot = BufReader::new(File::open(&log_path)?).lines().count() as u64;
+        Ok(RoomLog {
+            log_path,
+            snapshot_path,
+            file,
+            since_snapshot,
+            compact_every,
+        })
+    }
+
+    pub fn append(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        // One write per line, so a crash can at worst tear the last entry.
+        self.file.write_all(line.as_bytes())?;
+        self.since_snapshot += 1;
+        Ok(())
+    }
+
+    /// The log as written: one JSON `LogEntry` per line, since the last snapshot.
+    pub fn contents(&self) -> io::Result<String> {
+        fs::read_to_string(&self.log_path)
+    }
+
+    /// Replaces the snapshot with `state` and empties the log.
+    pub fn compact(&mut self, sequence_id: u64, state: &GameState) -> io::Result<()> {
+        let snapshot = Snapshot {
+            sequence_id,
+            state: state.clone(),
+        };
+        let tmp = self.snapshot_path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&snapshot)?)?;
+        fs::rename(&tmp, &self.snapshot_path)?;
+
+        self.file = File::create(&self.log_path)?;
+        self.since_snapshot = 0;
+        Ok(())
+    }
+}
+
+/// A room's chat, one `ChatEntry` per line.
+pub struct ChatFile {
+    path: PathBuf,
+    file: File,
+    lines: usize,
+    /// Entries kept when the file is cut back; 0 keeps everything.
+    keep: usize,
+}
+
+impl ChatFile {
+    pub fn open(data_dir: &Path, room_id: &str, keep: usize) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        let path = side_path(data_dir, room_id, CHAT_SUFFIX);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let lines = BufReader::new(File::open(&path)?).lines().count();
+        Ok(ChatFile {
+            path,
+            file,
+            lines,
+            keep,
+        })
+    }
+
+    pub fn append(&mut self, entries: &[ChatEntry]) -> io::Result<()> {
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+        self.file.write_all(lines.as_bytes())?;
+        self.lines += entries.len();
+        if self.keep > 0 && self.lines > 2 * self.keep {
+            self.cut_back()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the file with only the newest `keep` entries.
+    fn cut_back(&mut self) -> io::Result<()> {
+        let contents = fs::read_to_string(&self.path)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let kept = &lines[lines.len().saturating_sub(self.keep)..];
+        let mut out = kept.join("\n");
+        out.push('\n');
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, out)?;
+        fs::rename(&tmp, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.lines = kept.len();
+        Ok(())
+    }
+}
+
+enum PendingWrite {
+    Event(LogEntry),
+    Compact(Box<Snapshot>),
+    Chat(Vec<ChatEntry>),
+    Contents(oneshot::Sender<io::Result<String>>),
+    Remove { data_dir: PathBuf, room_id: String },
+}
+
+/// Queues a room's writes for a thread that owns its `RoomLog` and `ChatFile`.
+/// Writes happen in the order they were queued; the thread exits when the writer
+/// is dropped, after finishing what is queued.
+pub struct RoomWriter {
+    tx: mpsc::Sender<PendingWrite>,
+    since_snapshot: u64,
+    compact_every: u64,
+}
+
+impl RoomWriter {
+    /// `None` if there is nothing to write to.
+    pub fn spawn(log: Option<RoomLog>, chat: Option<ChatFile>) -> Option<Self> {
+        if log.is_none() && chat.is_none() {
+            return None;
+        }
+        let since_snapshot = log.as_ref().map_or(0, |l| l.since_snapshot);
+        let compact_every = log.as_ref().map_or(0, |l| l.compact_every);
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("room-writer".to_owned())
+            .spawn(move || write_loop(rx, log, chat));
+        if let Err(e) = spawned {
+            tracing::error!("Cannot start room writer: {}", e);
+            return None;
+        }
+        Some(RoomWriter {
+            tx,
+            since_snapshot,
+            compact_every,
+        })
+    }
+
+    fn send(&self, write: PendingWrite) {
+        if self.tx.send(write).is_err() {
+            tracing::error!("Room writer has stopped; dropping a write");
+        }
+    }
+
+    pub fn append(&mut self, entry: LogEntry) {
+        self.since_snapshot += 1;
+        self.send(PendingWrite::Event(entry));
+    }
+
+    pub fn needs_compaction(&self) -> bool {
+        self.compact_every > 0 && self.since_snapshot >= self.compact_every
+    }
+
+    /// Replaces the snapshot with `state` and empties the log.
+    pub fn compact(&mut self, sequence_id: u64, state: GameState) {
+        self.since_snapshot = 0;
+        self.send(PendingWrite::Compact(Box::new(Snapshot {
+            sequence_id,
+            state,
+        })));
+    }
+
+    pub fn append_chat(&self, entries: Vec<ChatEntry>) {
+        self.send(PendingWrite::Chat(entries));
+    }
+
+    /// The log's contents once the writes queued before have landed.
+    pub fn contents(&self) -> oneshot::Receiver<io::Result<String>> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PendingWrite::Contents(tx));
+        rx
+    }
+
+    /// Deletes the room's files once the writes queued before have landed.
+    pub fn remove_room(self, data_dir: &Path, room_id: &str) {
+        self.send(PendingWrite::Remove {
+            data_dir: data_dir.to_owned(),
+            room_id: room_id.to_owned(),
+        });
+    }
+}
+
+fn write_loop(
+    rx: mpsc::Receiver<PendingWrite>,
+    mut log: Option<RoomLog>,
+    mut chat: Option<ChatFile>,
+) {
+    for write in rx {
+        match write {
+            PendingWrite::Event(entry) => {
+                if let Some(log) = &mut log
+                    && let Err(e) = log.append(&entry)
+                {
+                    tracing::error!("Failed to append to event log: {}", e);
+                }
+            }
+            PendingWrite::Compact(snapshot) => {
+                if let Some(log) = &mut log
+                    && let Err(e) = log.compact(snapshot.sequence_id, &snapshot.state)
+                {
+                    tracing::error!("Failed to compact event log: {}", e);
+                }
+            }
+            PendingWrite::Chat(entries) => {
+                if let Some(chat) = &mut chat
+                    && let Err(e) = chat.append(&entries)
+                {
+                    tracing::error!("Failed to append to chat log: {}", e);
+                }
+            }
+            PendingWrite::Contents(reply) => {
+                let contents = match &log {
+                    Some(log) => log.contents(),
+                    None => Err(io::Error::new(io::ErrorKind::NotFound, "No event log")),
+                };
+                let _ = reply.send(contents);
+            }
+            PendingWrite::Remove { data_dir, room_id } => {
+                drop((log.take(), chat.take()));
+                if let Err(e) = remove_room(&data_dir, &room_id) {
+                    tracing::error!("Cannot delete log for room {}: {}", room_id, e);
+                }
+            }
+        }
+    }
+}
+
+/// Room ids with a log or snapshot in `data_dir`.
+pub fn list_rooms(data_dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(data_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let mut rooms = vec![];
+    for entry in entries {
+        let name = entry?.file_name();
+        let Some(name) = name.to_str() else { continue };
+        if name.ends_with(CHAT_SUFFIX) {
+            continue;
+        }
+        let stem = name
+            .strip_suffix(SNAPSHOT_SUFFIX)
+            .or_else(|| name.strip_suffix(&format!(".{}", LOG_EXT)));
+        if let Some(room_id) = stem.and_then(decode_room_id)
+            && !rooms.contains(&room_id)
+        {
+            rooms.push(room_id);
+        }
+    }
+    rooms.sort();
+    Ok(rooms)
+}
+
+/// Whether `data_dir` has a log or snapshot for the room.
+pub fn has_room(data_dir: &Path, room_id: &str) -> bool {
+    let (log_path, snapshot_path) = paths(data_dir, room_id);
+    log_path.exists() || snapshot_path.exists()
+}
+
+pub fn load_room(data_dir: &Path, room_id: &str) -> io::Result<StoredRoom> {
+    let (log_path, snapshot_path) = paths(data_dir, room_id);
+
+    let snapshot: Option<Snapshot> = match fs::read(&snapshot_path) {
+        Ok(bytes) => Some(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let after = snapshot.as_ref().map_or(0, |s| s.sequence_id);
+
+    let mut events = vec![];
+    match File::open(&log_path) {
+        Ok(file) => {
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                match serde_json::from_str::<LogEntry>(&line) {
+                    Ok(entry) if entry.sequence_id > after => events.push(entry),
+                    Ok(_) => {}
+                    Err(e) => {
+                        // A torn final line from a crash mid-write.
+                        tracing::warn!("Skipping bad log line in room {}: {}", room_id, e);
+                    }
+                }
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let access = match fs::read(side_path(data_dir, room_id, ACCESS_SUFFIX)) {
+        Ok(bytes) => Some(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let sessions = match fs::read(side_path(data_dir, room_id, SESSIONS_SUFFIX)) {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+        Err(e) => return Err(e),
+    };
+    let chat = match File::open(side_path(data_dir, room_id, CHAT_SUFFIX)) {
+        Ok(file) => Some(read_chat(room_id, file)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    Ok(StoredRoom {
+        snapshot,
+        events,
+        access,
+        sessions,
+        chat,
+    })
+}
+
+fn read_chat(room_id: &str, file: File) -> io::Result<Vec<ChatEntry>> {
+    let mut entries = vec![];
+    for line in BufReader::new(file).lines() {
+        match serde_json::from_str(&line?) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!("Skipping bad chat line in room {}: {}", room_id, e),
+        }
+    }
+    Ok(entries)
+}
+
+pub fn save_access(data_dir: &Path, room_id: &str, access: &RoomAccess) -> io::Result<()> {
+    fs::create_dir_all(data_dir)?;
+    fs::write(
+        side_path(data_dir, room_id, ACCESS_SUFFIX),
+        serde_json::to_vec(access)?,
+    )
+}
+
+pub fn save_sessions(
+    data_dir: &Path,
+    room_id: &str,
+    sessions: &HashMap<String, String>,
+) -> io::Result<()> {
+    fs::create_dir_all(data_dir)?;
+    fs::write(
+        side_path(data_dir, room_id, SESSIONS_SUFFIX),
+        serde_json::to_vec(sessions)?,
+    )
+}
+
+/// Deletes all of a room's files.
+pub fn remove_room(data_dir: &Path, room_id: &str) -> io::Result<()> {
+    let (log_path, snapshot_path) = paths(data_dir, room_id);
+    let access_path = side_path(data_dir, room_id, ACCESS_SUFFIX);
+    let sessions_path = side_path(data_dir, room_id, SESSIONS_SUFFIX);
+    let chat_path = side_path(data_dir, room_id, CHAT_SUFFIX);
+    for path in [
+        log_path,
+        snapshot_path,
+        access_path,
+        sessions_path,
+        chat_path,
+    ] {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn paths(data_dir: &Path, room_id: &str) -> (PathBuf, PathBuf) {
+    let name = encode_room_id(room_id);
+    (
+        data_dir.join(format!("{}.{}", name, LOG_EXT)),
+        data_dir.join(format!("{}{}", name, SNAPSHOT_SUFFIX)),
+    )
+}
+
+fn side_path(data_dir: &Path, room_id: &str, suffix: &str) -> PathBuf {
+    data_dir.join(format!("{}{}", encode_room_id(room_id), suffix))
+}
+
+/// Room ids come from clients, so anything but `[A-Za-z0-9-]` is escaped as `_xx`.
+pub(crate) fn encode_room_id(room_id: &str) -> String {
+    let mut out = String::new();
+    for b in room_id.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("_{:02x}", b));
+        }
+    }
+    out
+}
+
+fn decode_room_id(name: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut chars = name.bytes();
+    while let Some(b) = chars.next() {
+        if b == b'_' {
+            let hex = [chars.next()?, chars.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
@@ -70,3 +70,1813 @@ async fn test_websocket_join() {
         panic!("Connection closed unexpectedly");
     }
 }
+
+// --- Authoritative Mode ---
+
+type Ws =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn spawn_server(config: sint_server::ServerConfig) -> std::net::SocketAddr {
+    let app = sint_server::create_app_with_config(config);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    addr
+}
+
+async fn send_json(ws: &mut Ws, value: serde_json::Value) {
+    ws.send(tokio_tungstenite::tungstenite::Message::Text(
+        value.to_string(),
+    ))
+    .await
+    .unwrap();
+}
+
+/// The next message other than a `PresenceChanged`, which most tests don't care about.
+async fn recv_json(ws: &mut Ws) -> serde_json::Value {
+    loop {
+        let msg = recv_any(ws).await;
+        if msg["type"] != "PresenceChanged" {
+            return msg;
+        }
+    }
+}
+
+async fn recv_any(ws: &mut Ws) -> serde_json::Value {
+    use tokio_tungstenite::tungstenite::Message;
+    loop {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+            .await
+            .expect("Timed out waiting for message")
+            .expect("Connection closed unexpectedly")
+            .unwrap();
+        match msg {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            // Heartbeats; tungstenite answers pings by itself.
+            Message::Ping(_) | Message::Pong(_) => {}
+            other => panic!("Expected text message, got {:?}", other),
+        }
+    }
+}
+
+async fn assert_silent(ws: &mut Ws) {
+    let res = tokio::time::timeout(std::time::Duration::from_millis(200), recv_json(ws)).await;
+    assert!(res.is_err(), "Expected no message, got {:?}", res);
+}
+
+async fn join(addr: std::net::SocketAddr, room_id: &str, player_id: &str) -> Ws {
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr))
+        .await
+        .expect("Failed to connect");
+    send_json(
+        &mut ws,
+        serde_json::json!({
+            "type": "Join",
+            "payload": { "room_id": room_id, "player_id": player_id }
+        }),
+    )
+    .await;
+    assert_eq!(recv_json(&mut ws).await["type"], "Welcome");
+    ws
+}
+
+/// Sends `Hello` with `capabilities` and returns the server's answer.
+async fn hello(ws: &mut Ws, protocol_version: u32, capabilities: &[&str]) -> serde_json::Value {
+    send_json(
+        ws,
+        serde_json::json!({
+            "type": "Hello",
+            "payload": { "protocol_version": protocol_version, "capabilities": capabilities }
+        }),
+    )
+    .await;
+    recv_json(ws).await
+}
+
+/// Like `join`, after a `Hello` asking for `capabilities`.
+async fn join_with(
+    addr: std::net::SocketAddr,
+    room_id: &str,
+    player_id: &str,
+    capabilities: &[&str],
+) -> Ws {
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr))
+        .await
+        .expect("Failed to connect");
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION, capabilities).await;
+    assert_eq!(reply["type"], "Hello");
+    send_json(
+        &mut ws,
+        serde_json::json!({
+            "type": "Join",
+            "payload": { "room_id": room_id, "player_id": player_id }
+        }),
+    )
+    .await;
+    assert_eq!(recv_json(&mut ws).await["type"], "Welcome");
+    ws
+}
+
+fn event(player_id: &str, action: sint_core::Action) -> serde_json::Value {
+    let event = sint_core::PlayerEvent {
+        id: uuid::Uuid::new_v4(),
+        player_id: player_id.to_owned(),
+        action,
+    };
+    serde_json::json!({
+        "type": "Event",
+        "payload": { "sequence_id": 0, "data": event }
+    })
+}
+
+fn join_event(player_id: &str) -> serde_json::Value {
+    event(
+        player_id,
+        sint_core::Action::Meta(sint_core::MetaAction::Join {
+            name: player_id.to_owned(),
+        }),
+    )
+}
+
+fn chat_event(player_id: &str, message: &str) -> serde_json::Value {
+    event(
+        player_id,
+        sint_core::Action::Game(sint_core::GameAction::Chat {
+            message: message.to_owned(),
+        }),
+    )
+}
+
+fn authoritative() -> sint_server::ServerConfig {
+    sint_server::ServerConfig {
+        authoritative: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_authoritative_sequences_events() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "seq_room", "P1").await;
+    let mut p2 = join(addr, "seq_room", "P2").await;
+
+    // Clients send sequence_id 0; the server numbers accepted events itself.
+    send_json(&mut p1, join_event("P1")).await;
+    let first = recv_json(&mut p1).await;
+    send_json(&mut p2, join_event("P2")).await;
+    let second = recv_json(&mut p1).await;
+
+    assert_eq!(first["type"], "Event");
+    assert_eq!(first["payload"]["sequence_id"], 1);
+    assert_eq!(first["payload"]["data"]["player_id"], "P1");
+    assert_eq!(second["payload"]["sequence_id"], 2);
+    assert_eq!(second["payload"]["data"]["player_id"], "P2");
+
+    assert_eq!(recv_json(&mut p2).await, first);
+    assert_eq!(recv_json(&mut p2).await, second);
+}
+
+#[tokio::test]
+async fn test_authoritative_rejects_invalid_event_to_sender_only() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "reject_room", "P1").await;
+    let mut p2 = join(addr, "reject_room", "P2").await;
+
+    // P1 has not joined the game, so it cannot act.
+    let illegal = event(
+        "P1",
+        sint_core::Action::Game(sint_core::GameAction::move_to(3)),
+    );
+    send_json(&mut p1, illegal.clone()).await;
+    let error = recv_json(&mut p1).await;
+    assert_eq!(error["type"], "Rejected");
+    assert_eq!(
+        error["payload"]["event_id"],
+        illegal["payload"]["data"]["id"]
+    );
+    assert_eq!(error["payload"]["reason"], "Player not found");
+    assert_silent(&mut p2).await;
+
+    // Garbage is rejected too, and does not consume a sequence number.
+    send_json(
+        &mut p1,
+        serde_json::json!({
+            "type": "Event",
+            "payload": { "sequence_id": 99, "data": { "nonsense": true } }
+        }),
+    )
+    .await;
+    assert_eq!(recv_json(&mut p1).await["type"], "Error");
+    assert_silent(&mut p2).await;
+
+    send_json(&mut p1, join_event("P1")).await;
+    assert_eq!(recv_json(&mut p2).await["payload"]["sequence_id"], 1);
+}
+
+#[tokio::test]
+async fn test_authoritative_sync_comes_from_server() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "sync_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+
+    // Clients may not overwrite the server's state.
+    let forged = sint_core::GameLogic::new_game(vec!["Mallory".to_owned()], 1);
+    send_json(
+        &mut p1,
+        event(
+            "P1",
+            sint_core::Action::Meta(sint_core::MetaAction::FullSync {
+                state_json: serde_json::to_string(&forged).unwrap(),
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(recv_json(&mut p1).await["type"], "Rejected");
+
+    let mut p2 = join(addr, "sync_room", "P2").await;
+    send_json(
+        &mut p2,
+        serde_json::json!({ "type": "SyncRequest", "payload": { "requestor_id": "P2" } }),
+    )
+    .await;
+    let sync = recv_json(&mut p2).await;
+    assert_eq!(sync["payload"]["sequence_id"], 1);
+    let event: sint_core::PlayerEvent =
+        serde_json::from_value(sync["payload"]["data"].clone()).unwrap();
+    let sint_core::Action::Meta(sint_core::MetaAction::FullSync { state_json }) = event.action
+    else {
+        panic!("Expected FullSync, got {:?}", event.action);
+    };
+    let state: sint_core::GameState = serde_json::from_str(&state_json).unwrap();
+    assert!(state.players.contains_key("P1"));
+    assert!(!state.players.contains_key("Mallory"));
+    assert_silent(&mut p1).await;
+}
+
+// --- Persistence ---
+
+fn persistent(dir: &std::path::Path, compact_every: u64) -> sint_server::ServerConfig {
+    sint_server::ServerConfig {
+        data_dir: Some(dir.to_owned()),
+        compact_every,
+        ..authoritative()
+    }
+}
+
+async fn sync_state(ws: &mut Ws) -> (u64, sint_core::GameState) {
+    send_json(
+        ws,
+        serde_json::json!({ "type": "SyncRequest", "payload": { "requestor_id": "test" } }),
+    )
+    .await;
+    let sync = recv_json(ws).await;
+    let event: sint_core::PlayerEvent =
+        serde_json::from_value(sync["payload"]["data"].clone()).unwrap();
+    let sint_core::Action::Meta(sint_core::MetaAction::FullSync { state_json }) = event.action
+    else {
+        panic!("Expected FullSync, got {:?}", event.action);
+    };
+    (
+        sync["payload"]["sequence_id"].as_u64().unwrap(),
+        serde_json::from_str(&state_json).unwrap(),
+    )
+}
+
+/// The room as stored once its writer has put event `sequence_id` on disk.
+async fn stored_room(
+    dir: &std::path::Path,
+    room_id: &str,
+    sequence_id: u64,
+) -> sint_server::storage::StoredRoom {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+        let stored = sint_server::storage::load_room(dir, room_id).unwrap();
+        let on_disk = stored
+            .events
+            .last()
+            .map(|e| e.sequence_id)
+            .unwrap_or_else(|| stored.snapshot.as_ref().map_or(0, |s| s.sequence_id));
+        if on_disk >= sequence_id {
+            return stored;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "Event {} of room {} never reached the disk",
+            sequence_id,
+            room_id
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_rooms_survive_restart() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let mut p1 = join(addr, "evening game", "P1").await;
+    let mut p2 = join(addr, "evening game", "P2").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    send_json(&mut p2, join_event("P2")).await;
+    recv_json(&mut p1).await;
+    stored_room(dir.path(), "evening game", 2).await;
+
+    // A second server on the same directory stands in for the restarted one.
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let rooms: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(rooms["rooms"][0]["id"], "evening game");
+    assert_eq!(rooms["rooms"][0]["player_count"], 2);
+
+    let mut p3 = join(addr, "evening game", "P3").await;
+    let (sequence_id, state) = sync_state(&mut p3).await;
+    assert_eq!(sequence_id, 2);
+    assert!(state.players.contains_key("P1"));
+    assert!(state.players.contains_key("P2"));
+
+    // Numbering continues where the old server stopped.
+    send_json(&mut p3, join_event("P3")).await;
+    assert_eq!(recv_json(&mut p3).await["payload"]["sequence_id"], 3);
+}
+
+#[tokio::test]
+async fn test_log_compacts_into_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let addr = spawn_server(persistent(dir.path(), 2)).await;
+    let mut ws = join(addr, "compact", "P1").await;
+    for msg in [
+        join_event("P1"),
+        chat_event("P1", "a"),
+        chat_event("P1", "b"),
+    ] {
+        send_json(&mut ws, msg).await;
+        recv_json(&mut ws).await;
+    }
+
+    let stored = stored_room(dir.path(), "compact", 3).await;
+    let snapshot = stored.snapshot.expect("No snapshot written");
+    assert_eq!(snapshot.sequence_id, 2);
+    assert_eq!(stored.events.len(), 1);
+    assert_eq!(stored.events[0].sequence_id, 3);
+    // Chat has its own file, which compaction leaves alone.
+    assert_eq!(stored.chat.unwrap().len(), 2);
+
+    let addr = spawn_server(persistent(dir.path(), 2)).await;
+    let mut ws = join(addr, "compact", "P2").await;
+    let (sequence_id, state) = sync_state(&mut ws).await;
+    assert_eq!(sequence_id, 3);
+    assert_eq!(state.chat[0].text, "b");
+    assert_eq!(
+        chat_texts(&chat_page(addr, "compact", "").await),
+        ["a", "b"]
+    );
+}
+
+#[tokio::test]
+async fn test_relay_mode_persists_rooms() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = sint_server::ServerConfig {
+        data_dir: Some(dir.path().to_owned()),
+        ..Default::default()
+    };
+
+    let addr = spawn_server(config.clone()).await;
+    let mut ws = join(addr, "relay", "P1").await;
+    send_json(&mut ws, join_event("P1")).await;
+    recv_json(&mut ws).await;
+    stored_room(dir.path(), "relay", 1).await;
+
+    let addr = spawn_server(config).await;
+    let mut ws = join(addr, "relay", "P2").await;
+    let (_, state) = sync_state(&mut ws).await;
+    assert!(state.players.contains_key("P1"));
+}
+
+// --- Catch-up ---
+
+fn resume(last_sequence_id: u64) -> serde_json::Value {
+    serde_json::json!({ "type": "Resume", "payload": { "last_sequence_id": last_sequence_id } })
+}
+
+#[tokio::test]
+async fn test_resume_replays_missing_events() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "resume_room", "P1").await;
+    for msg in [
+        join_event("P1"),
+        chat_event("P1", "a"),
+        chat_event("P1", "b"),
+    ] {
+        send_json(&mut p1, msg).await;
+        recv_json(&mut p1).await;
+    }
+
+    // A client that saw event 1 before disconnecting.
+    let mut p2 = join(addr, "resume_room", "P2").await;
+    send_json(&mut p2, resume(1)).await;
+    let second = recv_json(&mut p2).await;
+    let third = recv_json(&mut p2).await;
+    assert_eq!(second["payload"]["sequence_id"], 2);
+    assert_eq!(
+        second["payload"]["data"]["action"]["payload"]["message"],
+        "a"
+    );
+    assert_eq!(third["payload"]["sequence_id"], 3);
+
+    // Live events continue after the replay, without duplicates.
+    send_json(&mut p1, chat_event("P1", "c")).await;
+    assert_eq!(recv_json(&mut p2).await["payload"]["sequence_id"], 4);
+    assert_silent(&mut p2).await;
+
+    // Nothing to send to a client that is up to date.
+    send_json(&mut p2, resume(4)).await;
+    assert_silent(&mut p2).await;
+}
+
+#[tokio::test]
+async fn test_resume_beyond_buffer_sends_snapshot() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        resume_buffer: 2,
+        ..authoritative()
+    })
+    .await;
+    let mut p1 = join(addr, "snapshot_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    for message in ["a", "b", "c"] {
+        send_json(&mut p1, chat_event("P1", message)).await;
+        recv_json(&mut p1).await;
+    }
+
+    // Events 3 and 4 are buffered, so resuming from 2 replays them.
+    let mut p2 = join(addr, "snapshot_room", "P2").await;
+    send_json(&mut p2, resume(2)).await;
+    assert_eq!(recv_json(&mut p2).await["payload"]["sequence_id"], 3);
+    assert_eq!(recv_json(&mut p2).await["payload"]["sequence_id"], 4);
+
+    // A fresh client needs events 1 and 2 too, which are gone.
+    let mut p3 = join(addr, "snapshot_room", "P3").await;
+    send_json(&mut p3, resume(0)).await;
+    let snapshot = recv_json(&mut p3).await;
+    assert_eq!(snapshot["payload"]["sequence_id"], 4);
+    let event: sint_core::PlayerEvent =
+        serde_json::from_value(snapshot["payload"]["data"].clone()).unwrap();
+    let sint_core::Action::Meta(sint_core::MetaAction::FullSync { state_json }) = event.action
+    else {
+        panic!("Expected FullSync, got {:?}", event.action);
+    };
+    let state: sint_core::GameState = serde_json::from_str(&state_json).unwrap();
+    assert_eq!(state.chat[0].text, "c");
+    assert_silent(&mut p3).await;
+}
+
+#[tokio::test]
+async fn test_lagging_client_catches_up() {
+    // A channel this small overflows as soon as the reader falls behind.
+    let addr = spawn_server(sint_server::ServerConfig {
+        broadcast_capacity: 1,
+        ..Default::default()
+    })
+    .await;
+    let mut reader = join(addr, "lag_room", "Reader").await;
+    let mut writer = join(addr, "lag_room", "Writer").await;
+
+    let count = 50;
+    for i in 0..count {
+        send_json(&mut writer, chat_event("Writer", &i.to_string())).await;
+    }
+
+    for expected in 1..=count {
+        let msg = recv_json(&mut reader).await;
+        assert_eq!(msg["payload"]["sequence_id"], expected);
+    }
+    assert_silent(&mut reader).await;
+    assert!(scrape(addr).await["sint_broadcast_lagged_total"] >= 1);
+}
+
+// --- Room Lifecycle ---
+
+async fn room_list(addr: std::net::SocketAddr) -> Vec<sint_server::RoomInfo> {
+    let list: sint_server::RoomList = reqwest::get(format!("http://{}/api/rooms", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    list.rooms
+}
+
+#[tokio::test]
+async fn test_room_list_metadata() {
+    let addr = spawn_server(Default::default()).await;
+    let mut p1 = join(addr, "meta_room", "P1").await;
+    let mut p2 = join(addr, "meta_room", "P2").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    send_json(&mut p2, join_event("P2")).await;
+    recv_json(&mut p2).await;
+
+    let rooms = room_list(addr).await;
+    assert_eq!(rooms.len(), 1);
+    let info = &rooms[0];
+    assert_eq!(info.id, "meta_room");
+    assert_eq!(info.players, vec!["P1", "P2"]);
+    assert_eq!(info.player_count, 2);
+    assert_eq!(info.connections, 2);
+    assert_eq!(info.max_players, 6);
+    assert_eq!(info.phase, sint_core::GamePhase::Lobby);
+    assert_eq!(info.layout, sint_core::MapLayout::Star);
+    assert!(!info.boss.is_empty());
+    assert!(info.created_at > 0);
+    assert!(info.last_activity >= info.created_at);
+}
+
+#[tokio::test]
+async fn test_room_rejects_joins_when_full() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        max_players: 2,
+        ..Default::default()
+    })
+    .await;
+    let _p1 = join(addr, "full_room", "P1").await;
+    let p2 = join(addr, "full_room", "P2").await;
+
+    let (mut p3, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    let join_msg = serde_json::json!({
+        "type": "Join",
+        "payload": { "room_id": "full_room", "player_id": "P3" }
+    });
+    send_json(&mut p3, join_msg.clone()).await;
+    let error = recv_json(&mut p3).await;
+    assert_eq!(error["type"], "JoinRejected");
+    assert_eq!(error["payload"]["reason"], "RoomFull");
+
+    // A seat frees up when someone leaves.
+    drop(p2);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    send_json(&mut p3, join_msg).await;
+    assert_eq!(recv_json(&mut p3).await["type"], "Welcome");
+}
+
+#[tokio::test]
+async fn test_rejected_join_keeps_the_current_room() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        max_players: 1,
+        ..Default::default()
+    })
+    .await;
+    let (mut p1, welcome) = join_session(addr, "solo", "P1", None).await;
+    let join_msg = |room_id: &str| {
+        serde_json::json!({
+            "type": "Join",
+            "payload": {
+                "room_id": room_id,
+                "player_id": "P1",
+                "session_token": welcome["payload"]["session_token"],
+            }
+        })
+    };
+    // The socket's own seat counts as free when it rejoins
+    send_json(&mut p1, join_msg("solo")).await;
+    assert_eq!(recv_json(&mut p1).await["type"], "Welcome");
+
+    create_room(addr, serde_json::json!({ "room_id": "locked" })).await;
+    send_json(&mut p1, join_msg("locked")).await;
+    assert_eq!(recv_json(&mut p1).await["type"], "JoinRejected");
+
+    let rooms = room_list(addr).await;
+    let connections = |id: &str| rooms.iter().find(|r| r.id == id).map(|r| r.connections);
+    assert_eq!(connections("solo"), Some(1));
+    assert_eq!(connections("locked"), Some(0));
+}
+
+#[tokio::test]
+async fn test_idle_rooms_expire() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(sint_server::ServerConfig {
+        room_ttl: std::time::Duration::from_millis(300),
+        ..persistent(dir.path(), 100)
+    })
+    .await;
+    let mut ws = join(addr, "idle_room", "P1").await;
+    send_json(&mut ws, join_event("P1")).await;
+    recv_json(&mut ws).await;
+
+    // Rooms with sockets never expire.
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    assert_eq!(room_list(addr).await.len(), 1);
+
+    drop(ws);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(room_list(addr).await.len(), 1);
+
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    assert!(room_list(addr).await.is_empty());
+    assert_eq!(
+        sint_server::storage::list_rooms(dir.path()).unwrap(),
+        vec!["idle_room".to_owned()]
+    );
+    let (status, _) = create_room(addr, serde_json::json!({ "room_id": "idle_room" })).await;
+    assert_eq!(status, reqwest::StatusCode::CONFLICT);
+
+    // Joining loads the game back from its files.
+    let mut ws = join(addr, "idle_room", "P2").await;
+    let (sequence_id, state) = sync_state(&mut ws).await;
+    assert_eq!(sequence_id, 1);
+    assert!(state.players.contains_key("P1"));
+}
+
+// --- Private Rooms ---
+
+async fn create_room(
+    addr: std::net::SocketAddr,
+    body: serde_json::Value,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let resp = reqwest::Client::new()
+        .post(format!("http://{}/api/rooms", addr))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    (resp.status(), resp.json().await.unwrap())
+}
+
+/// Sends a Join with credentials and returns the server's reply.
+async fn try_join(
+    addr: std::net::SocketAddr,
+    room_id: &str,
+    invite_token: Option<&str>,
+    password: Option<&str>,
+) -> serde_json::Value {
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    send_json(
+        &mut ws,
+        serde_json::json!({
+            "type": "Join",
+            "payload": {
+                "room_id": room_id,
+                "player_id": uuid::Uuid::new_v4().to_string(),
+                "invite_token": invite_token,
+                "password": password,
+            }
+        }),
+    )
+    .await;
+    recv_json(&mut ws).await
+}
+
+#[tokio::test]
+async fn test_private_room_requires_credentials() {
+    let addr = spawn_server(Default::default()).await;
+    let (status, created) = create_room(
+        addr,
+        serde_json::json!({ "room_id": "game night", "password": "sinterklaas" }),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(created["room_id"], "game night");
+    let token = created["invite_token"].as_str().unwrap();
+
+    let rejected = |reason: &str| {
+        serde_json::json!({
+            "type": "JoinRejected",
+            "payload": { "room_id": "game night", "reason": reason }
+        })
+    };
+    assert_eq!(
+        try_join(addr, "game night", None, None).await,
+        rejected("CredentialsRequired")
+    );
+    assert_eq!(
+        try_join(addr, "game night", None, Some("pakjesavond")).await,
+        rejected("WrongPassword")
+    );
+    assert_eq!(
+        try_join(addr, "game night", Some("forged"), Some("sinterklaas")).await,
+        rejected("InvalidInviteToken")
+    );
+    assert_eq!(
+        try_join(addr, "game night", Some(token), None).await["type"],
+        "Welcome"
+    );
+    assert_eq!(
+        try_join(addr, "game night", None, Some("sinterklaas")).await["type"],
+        "Welcome"
+    );
+
+    // Listed, but marked private.
+    let rooms = room_list(addr).await;
+    assert_eq!(rooms.len(), 1);
+    assert!(rooms[0].private);
+}
+
+#[tokio::test]
+async fn test_unlisted_room_is_hidden() {
+    let addr = spawn_server(Default::default()).await;
+    let (_, created) = create_room(addr, serde_json::json!({ "unlisted": true })).await;
+    let room_id = created["room_id"].as_str().unwrap();
+    let token = created["invite_token"].as_str().unwrap();
+
+    assert!(room_list(addr).await.is_empty());
+    assert_eq!(
+        try_join(addr, room_id, Some(token), None).await["type"],
+        "Welcome"
+    );
+    assert!(room_list(addr).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_room_conflicts() {
+    let addr = spawn_server(Default::default()).await;
+    let _ws = join(addr, "public", "P1").await;
+
+    let (status, body) = create_room(addr, serde_json::json!({ "room_id": "public" })).await;
+    assert_eq!(status, 409);
+    assert_eq!(body, serde_json::json!({ "error": "RoomExists" }));
+
+    let (status, body) = create_room(addr, serde_json::json!({ "room_id": " " })).await;
+    assert_eq!(status, 400);
+    assert_eq!(body, serde_json::json!({ "error": "InvalidRoomId" }));
+}
+
+#[tokio::test]
+async fn test_private_room_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let (_, created) = create_room(
+        addr,
+        serde_json::json!({ "room_id": "locked", "password": "pw", "unlisted": true }),
+    )
+    .await;
+    let token = created["invite_token"].as_str().unwrap();
+
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    assert!(room_list(addr).await.is_empty());
+    assert_eq!(
+        try_join(addr, "locked", None, None).await["payload"]["reason"],
+        "CredentialsRequired"
+    );
+    assert_eq!(
+        try_join(addr, "locked", Some(token), None).await["type"],
+        "Welcome"
+    );
+    assert_eq!(
+        try_join(addr, "locked", None, Some("pw")).await["type"],
+        "Welcome"
+    );
+}
+
+// --- Player Identity ---
+
+/// Joins as `player_id` with an optional session token and returns the reply.
+async fn join_session(
+    addr: std::net::SocketAddr,
+    room_id: &str,
+    player_id: &str,
+    session_token: Option<&str>,
+) -> (Ws, serde_json::Value) {
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    send_json(
+        &mut ws,
+        serde_json::json!({
+            "type": "Join",
+            "payload": {
+                "room_id": room_id,
+                "player_id": player_id,
+                "session_token": session_token,
+            }
+        }),
+    )
+    .await;
+    let reply = recv_json(&mut ws).await;
+    (ws, reply)
+}
+
+#[tokio::test]
+async fn test_session_token_reclaims_seat() {
+    let addr = spawn_server(Default::default()).await;
+    let (ws, welcome) = join_session(addr, "seat_room", "P1", None).await;
+    assert_eq!(welcome["type"], "Welcome");
+    assert_eq!(welcome["payload"]["player_id"], "P1");
+    let token = welcome["payload"]["session_token"].as_str().unwrap();
+    drop(ws);
+
+    let (_, reply) = join_session(addr, "seat_room", "P1", None).await;
+    assert_eq!(reply["payload"]["reason"], "SeatTaken");
+    let (_, reply) = join_session(addr, "seat_room", "P1", Some("stolen")).await;
+    assert_eq!(reply["payload"]["reason"], "InvalidSessionToken");
+    let (_, reply) = join_session(addr, "seat_room", "server", None).await;
+    assert_eq!(reply["payload"]["reason"], "InvalidPlayerId");
+
+    // A page refresh presents the stored token and gets the same seat back.
+    let (_, reply) = join_session(addr, "seat_room", "P1", Some(token)).await;
+    assert_eq!(reply["type"], "Welcome");
+    assert_eq!(reply["payload"]["session_token"], token);
+}
+
+#[tokio::test]
+async fn test_events_for_other_players_are_rejected() {
+    for config in [Default::default(), authoritative()] {
+        let addr = spawn_server(config).await;
+        let mut p1 = join(addr, "identity_room", "P1").await;
+        let mut p2 = join(addr, "identity_room", "P2").await;
+        send_json(&mut p2, join_event("P2")).await;
+        recv_json(&mut p1).await;
+        recv_json(&mut p2).await;
+
+        send_json(
+            &mut p1,
+            event("P2", sint_core::Action::Game(sint_core::GameAction::Pass)),
+        )
+        .await;
+        let error = recv_json(&mut p1).await;
+        assert_eq!(error["type"], "Rejected");
+        assert_eq!(error["payload"]["reason"], "P1 cannot act as P2");
+        assert_silent(&mut p2).await;
+    }
+}
+
+#[tokio::test]
+async fn test_sessions_survive_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let (_, welcome) = join_session(addr, "saved_seats", "P1", None).await;
+    let token = welcome["payload"]["session_token"].as_str().unwrap();
+
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let (_, reply) = join_session(addr, "saved_seats", "P1", None).await;
+    assert_eq!(reply["payload"]["reason"], "SeatTaken");
+    let (_, reply) = join_session(addr, "saved_seats", "P1", Some(token)).await;
+    assert_eq!(reply["type"], "Welcome");
+}
+
+// --- Admin API ---
+
+const ADMIN_TOKEN: &str = "letmein";
+
+fn with_admin(config: sint_server::ServerConfig) -> sint_server::ServerConfig {
+    sint_server::ServerConfig {
+        admin_token: Some(ADMIN_TOKEN.to_owned()),
+        ..config
+    }
+}
+
+fn admin(
+    method: reqwest::Method,
+    addr: std::net::SocketAddr,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    reqwest::Client::new()
+        .request(method, format!("http://{}/api/admin{}", addr, path))
+        .bearer_auth(ADMIN_TOKEN)
+}
+
+async fn admin_rooms(addr: std::net::SocketAddr) -> Vec<sint_server::admin::AdminRoomInfo> {
+    let resp = admin(reqwest::Method::GET, addr, "/rooms")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.json::<sint_server::admin::AdminRoomList>()
+        .await
+        .unwrap()
+        .rooms
+}
+
+async fn assert_closed(ws: &mut Ws) {
+    let msg = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+        .await
+        .expect("Timed out waiting for close");
+    assert!(
+        matches!(
+            msg,
+            None | Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_))) | Some(Err(_))
+        ),
+        "Expected close, got {:?}",
+        msg
+    );
+}
+
+#[tokio::test]
+async fn test_admin_requires_token() {
+    let addr = spawn_server(Default::default()).await;
+    let resp = admin(reqwest::Method::GET, addr, "/rooms")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+
+    let addr = spawn_server(with_admin(Default::default())).await;
+    let resp = reqwest::get(format!("http://{}/api/admin/rooms", addr))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+    let resp = reqwest::Client::new()
+        .get(format!("http://{}/api/admin/rooms", addr))
+        .bearer_auth("guess")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"], "Unauthorized");
+
+    assert!(admin_rooms(addr).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_admin_inspects_rooms() {
+    let addr = spawn_server(with_admin(authoritative())).await;
+    let (_, created) = create_room(
+        addr,
+        serde_json::json!({ "room_id": "hidden", "unlisted": true }),
+    )
+    .await;
+    let (mut p1, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    send_json(
+        &mut p1,
+        serde_json::json!({
+            "type": "Join",
+            "payload": {
+                "room_id": "hidden",
+                "player_id": "P1",
+                "invite_token": created["invite_token"],
+            }
+        }),
+    )
+    .await;
+    assert_eq!(recv_json(&mut p1).await["type"], "Welcome");
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+
+    // Unlisted rooms are listed for admins, with their sockets.
+    let rooms = admin_rooms(addr).await;
+    assert_eq!(rooms.len(), 1);
+    assert_eq!(rooms[0].info.id, "hidden");
+    assert!(rooms[0].unlisted);
+    assert_eq!(rooms[0].sequence_id, 1);
+    assert_eq!(rooms[0].sockets.len(), 1);
+    assert_eq!(rooms[0].sockets[0].player_id, "P1");
+
+    let resp = admin(reqwest::Method::GET, addr, "/rooms/hidden/state")
+        .send()
+        .await
+        .unwrap();
+    let snapshot: sint_server::storage::Snapshot = resp.json().await.unwrap();
+    assert_eq!(snapshot.sequence_id, 1);
+    assert!(snapshot.state.players.contains_key("P1"));
+
+    let resp = admin(reqwest::Method::GET, addr, "/rooms/hidden/log")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()["content-type"], "application/x-ndjson");
+    let log = resp.text().await.unwrap();
+    let entries: Vec<sint_server::storage::LogEntry> = log
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].data["player_id"], "P1");
+
+    let resp = admin(reqwest::Method::GET, addr, "/rooms/nowhere/state")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_admin_broadcasts_and_kicks() {
+    let addr = spawn_server(with_admin(Default::default())).await;
+    let mut p1 = join(addr, "moderated", "P1").await;
+    let mut p2 = join(addr, "moderated", "P2").await;
+
+    let resp = admin(reqwest::Method::POST, addr, "/rooms/moderated/broadcast")
+        .json(&serde_json::json!({ "message": "Server restarts in 5 minutes" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+    for ws in [&mut p1, &mut p2] {
+        let msg = recv_json(ws).await;
+        assert_eq!(msg["type"], "System");
+        assert_eq!(msg["payload"]["message"], "Server restarts in 5 minutes");
+    }
+
+    let sockets = admin_rooms(addr).await.remove(0).sockets;
+    let p1_id = sockets
+        .iter()
+        .find(|s| s.player_id == "P1")
+        .unwrap()
+        .connection_id;
+    let resp = admin(
+        reqwest::Method::DELETE,
+        addr,
+        &format!("/rooms/moderated/connections/{}", p1_id),
+    )
+    .send()
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 204);
+    let msg = recv_json(&mut p1).await;
+    assert_eq!(msg["type"], "Kicked");
+    assert_eq!(msg["payload"]["reason"], "Kicked");
+    assert_closed(&mut p1).await;
+    assert_silent(&mut p2).await;
+
+    let resp = admin(
+        reqwest::Method::DELETE,
+        addr,
+        &format!("/rooms/moderated/connections/{}", p1_id),
+    )
+    .send()
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 404);
+    assert_eq!(admin_rooms(addr).await[0].sockets.len(), 1);
+}
+
+#[tokio::test]
+async fn test_admin_closes_room() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(with_admin(persistent(dir.path(), 100))).await;
+    let mut p1 = join(addr, "doomed", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+
+    let resp = admin(reqwest::Method::DELETE, addr, "/rooms/doomed")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+    let msg = recv_json(&mut p1).await;
+    assert_eq!(msg["payload"]["reason"], "RoomClosed");
+    assert_closed(&mut p1).await;
+
+    assert!(admin_rooms(addr).await.is_empty());
+    assert!(
+        sint_server::storage::list_rooms(dir.path())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_admin_closes_expired_room() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(with_admin(sint_server::ServerConfig {
+        room_ttl: std::time::Duration::from_millis(100),
+        ..persistent(dir.path(), 100)
+    }))
+    .await;
+    let mut p1 = join(addr, "forgotten", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    drop(p1);
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    assert!(admin_rooms(addr).await.is_empty());
+
+    let resp = admin(reqwest::Method::DELETE, addr, "/rooms/forgotten")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+    assert!(
+        sint_server::storage::list_rooms(dir.path())
+            .unwrap()
+            .is_empty()
+    );
+
+    let resp = admin(reqwest::Method::DELETE, addr, "/rooms/forgotten")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+// --- Metrics ---
+
+/// Samples from `/metrics`, keyed by name and labels.
+async fn scrape(addr: std::net::SocketAddr) -> std::collections::HashMap<String, u64> {
+    let resp = reqwest::get(format!("http://{}/metrics", addr))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.text()
+        .await
+        .unwrap()
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| {
+            let (name, value) = l.rsplit_once(' ').unwrap();
+            (name.to_owned(), value.parse().unwrap())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_metrics_count_traffic() {
+    let addr = spawn_server(Default::default()).await;
+    let mut p1 = join(addr, "metrics \"room\"", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    p1.send(tokio_tungstenite::tungstenite::Message::Text(
+        "not json".to_owned(),
+    ))
+    .await
+    .unwrap();
+    send_json(&mut p1, chat_event("P1", "hi")).await;
+    recv_json(&mut p1).await;
+
+    let metrics = scrape(addr).await;
+    assert_eq!(metrics["sint_rooms"], 1);
+    assert_eq!(metrics["sint_sockets"], 1);
+    assert_eq!(metrics["sint_messages_in_total"], 4);
+    // Welcome and the two events; P1 did not ask for presence.
+    assert_eq!(metrics["sint_messages_out_total"], 3);
+    assert_eq!(metrics["sint_parse_errors_total"], 1);
+    assert_eq!(metrics["sint_broadcast_lagged_total"], 0);
+    assert_eq!(
+        metrics[r#"sint_room_events_total{room="metrics \"room\""}"#],
+        2
+    );
+
+    drop(p1);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(scrape(addr).await["sint_sockets"], 0);
+}
+
+// --- Bots ---
+
+/// Adds bots, as an admin if `admin` is set.
+async fn add_bots(
+    addr: std::net::SocketAddr,
+    room_id: &str,
+    body: serde_json::Value,
+    admin: bool,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let mut req = reqwest::Client::new()
+        .post(format!("http://{}/api/rooms/{}/bots", addr, room_id))
+        .json(&body);
+    if admin {
+        req = req.bearer_auth(ADMIN_TOKEN);
+    }
+    let resp = req.send().await.unwrap();
+    (resp.status(), resp.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_bot_plays_alongside_human() {
+    let addr = spawn_server(with_admin(authoritative())).await;
+    let mut p1 = join(addr, "bot_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    let mut state = sint_server::initial_state();
+
+    let (status, body) = add_bots(
+        addr,
+        "bot_room",
+        serde_json::json!({ "strategy": "Beam", "budget_ms": 50 }),
+        true,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let bot_id = body["player_ids"][0].as_str().unwrap().to_owned();
+    assert_eq!(room_list(addr).await[0].connections, 2);
+
+    // Follow the game like a client, readying P1 whenever the game waits on it, until
+    // the bot makes a move of its own.
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(20);
+    loop {
+        assert!(tokio::time::Instant::now() < deadline, "Bot never moved");
+        let msg = recv_json(&mut p1).await;
+        let played: sint_core::PlayerEvent =
+            serde_json::from_value(msg["payload"]["data"].clone()).unwrap();
+        state = sint_core::GameLogic::apply_action(
+            state,
+            &played.player_id,
+            played.action.clone(),
+            None,
+        )
+        .unwrap();
+        if played.player_id == bot_id
+            && let sint_core::Action::Game(action) = &played.action
+            && !matches!(action, sint_core::GameAction::VoteReady { .. })
+        {
+            assert_eq!(state.phase, sint_core::GamePhase::TacticalPlanning);
+            break;
+        }
+        let me = &state.players["P1"];
+        if !me.is_ready && state.phase != sint_core::GamePhase::TacticalPlanning {
+            send_json(
+                &mut p1,
+                event(
+                    "P1",
+                    sint_core::Action::Game(sint_core::GameAction::VoteReady { ready: true }),
+                ),
+            )
+            .await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_add_bots_errors() {
+    let addr = spawn_server(with_admin(sint_server::ServerConfig {
+        max_players: 2,
+        max_bots: 2,
+        ..Default::default()
+    }))
+    .await;
+    let (status, body) = add_bots(addr, "nowhere", serde_json::json!({}), true).await;
+    assert_eq!(status, 404);
+    assert_eq!(body["error"], "RoomNotFound");
+
+    // Public rooms have no owner, so only an admin may add bots
+    let _p1 = join(addr, "small", "P1").await;
+    let (status, body) = add_bots(addr, "small", serde_json::json!({}), false).await;
+    assert_eq!(status, 403);
+    assert_eq!(body["error"], "Forbidden");
+    for count in [2, u64::MAX] {
+        let (status, body) =
+            add_bots(addr, "small", serde_json::json!({ "count": count }), true).await;
+        assert_eq!(status, 409);
+        assert_eq!(body["error"], "RoomFull");
+    }
+    let (status, _) = add_bots(addr, "small", serde_json::json!({}), true).await;
+    assert_eq!(status, 200);
+
+    let (_, created) = create_room(
+        addr,
+        serde_json::json!({ "room_id": "secret", "password": "pw" }),
+    )
+    .await;
+    for body in [
+        serde_json::json!({}),
+        serde_json::json!({ "password": "pw" }),
+        serde_json::json!({ "invite_token": "guess" }),
+    ] {
+        let (status, _) = add_bots(addr, "secret", body, false).await;
+        assert_eq!(status, 403);
+    }
+    let owner = serde_json::json!({ "invite_token": created["invite_token"] });
+    let (status, _) = add_bots(addr, "secret", owner.clone(), false).await;
+    assert_eq!(status, 200);
+
+    // Two bots run in all; the rejected request leaves no seat behind
+    let (status, body) = add_bots(addr, "secret", owner, false).await;
+    assert_eq!(status, 503);
+    assert_eq!(body["error"], "TooManyBots");
+    assert_eq!(
+        room_list(addr)
+            .await
+            .iter()
+            .find(|r| r.id == "secret")
+            .unwrap()
+            .connections,
+        1
+    );
+}
+
+// --- Presence ---
+
+fn presence(msg: &serde_json::Value) -> (String, bool) {
+    assert_eq!(
+        msg["type"], "PresenceChanged",
+        "Expected presence, got {}",
+        msg
+    );
+    (
+        msg["payload"]["player_id"].as_str().unwrap().to_owned(),
+        msg["payload"]["connected"].as_bool().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_presence_on_join_and_leave() {
+    let addr = spawn_server(Default::default()).await;
+    let mut p1 = join_with(addr, "presence_room", "P1", &["Presence"]).await;
+    assert_eq!(presence(&recv_any(&mut p1).await), ("P1".to_owned(), true));
+
+    // A newcomer hears who is already online; the others hear about the newcomer.
+    let mut p2 = join_with(addr, "presence_room", "P2", &["Presence"]).await;
+    assert_eq!(presence(&recv_any(&mut p2).await), ("P1".to_owned(), true));
+    assert_eq!(presence(&recv_any(&mut p2).await), ("P2".to_owned(), true));
+    assert_eq!(presence(&recv_any(&mut p1).await), ("P2".to_owned(), true));
+
+    drop(p2);
+    assert_eq!(presence(&recv_any(&mut p1).await), ("P2".to_owned(), false));
+}
+
+#[tokio::test]
+async fn test_unresponsive_socket_times_out() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        ping_interval: std::time::Duration::from_millis(50),
+        ping_timeout: std::time::Duration::from_millis(300),
+        ..Default::default()
+    })
+    .await;
+    // Never read again, so the client never answers a ping.
+    let _silent = join(addr, "heartbeat_room", "Silent").await;
+    let mut alive = join_with(addr, "heartbeat_room", "Alive", &["Presence"]).await;
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "Silent socket was never dropped"
+        );
+        if presence(&recv_any(&mut alive).await) == ("Silent".to_owned(), false) {
+            break;
+        }
+    }
+    // Reading answers the pings, which keeps the other socket alive.
+    let res =
+        tokio::time::timeout(std::time::Duration::from_millis(600), recv_json(&mut alive)).await;
+    assert!(res.is_err(), "Expected no message, got {:?}", res);
+    assert_eq!(room_list(addr).await[0].connections, 1);
+}
+
+// --- Handshake ---
+
+#[tokio::test]
+async fn test_hello_negotiates_capabilities() {
+    let wanted = ["Resume", "Authoritative", "Presence", "Compression"];
+    let addr = spawn_server(Default::default()).await;
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION, &wanted).await;
+    assert_eq!(reply["type"], "Hello");
+    assert_eq!(
+        reply["payload"]["protocol_version"],
+        sint_server::PROTOCOL_VERSION
+    );
+    assert_eq!(
+        reply["payload"]["capabilities"],
+        serde_json::json!(["Resume", "Presence"])
+    );
+
+    // Only an authoritative server validates events.
+    let addr = spawn_server(authoritative()).await;
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION, &wanted).await;
+    assert_eq!(
+        reply["payload"]["capabilities"],
+        serde_json::json!(["Resume", "Presence", "Authoritative"])
+    );
+}
+
+#[tokio::test]
+async fn test_hello_rejects_unsupported_version() {
+    let addr = spawn_server(Default::default()).await;
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION + 1, &[]).await;
+    assert_eq!(reply["type"], "HelloRejected");
+    let reason: sint_server::HelloError =
+        serde_json::from_value(reply["payload"]["reason"].clone()).unwrap();
+    assert_eq!(
+        reason,
+        sint_server::HelloError::UnsupportedVersion {
+            min: sint_server::MIN_PROTOCOL_VERSION,
+            max: sint_server::PROTOCOL_VERSION,
+        }
+    );
+
+    // The server hangs up after rejecting.
+    let next = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+        .await
+        .expect("Socket was not closed");
+    assert!(matches!(
+        next,
+        None | Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_hello_after_join_is_an_error() {
+    let addr = spawn_server(Default::default()).await;
+    let mut ws = join(addr, "late_hello_room", "P1").await;
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION, &["Presence"]).await;
+    assert_eq!(reply["type"], "Error");
+}
+
+#[tokio::test]
+async fn test_join_without_hello_gets_no_presence() {
+    let addr = spawn_server(Default::default()).await;
+    let mut legacy = join(addr, "legacy_room", "Old").await;
+    let _p2 = join_with(addr, "legacy_room", "New", &["Presence"]).await;
+
+    let res =
+        tokio::time::timeout(std::time::Duration::from_millis(200), recv_any(&mut legacy)).await;
+    assert!(res.is_err(), "Expected no message, got {:?}", res);
+}
+
+// --- Delta Sync ---
+
+#[tokio::test]
+async fn test_resume_beyond_buffer_sends_delta() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        resume_buffer: 2,
+        ..authoritative()
+    })
+    .await;
+    let mut p1 = join(addr, "delta_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    for message in ["a", "b", "c"] {
+        send_json(&mut p1, chat_event("P1", message)).await;
+        recv_json(&mut p1).await;
+    }
+
+    let mut p2 = join_with(addr, "delta_room", "P2", &["Delta"]).await;
+    send_json(&mut p2, resume(0)).await;
+    let sync = recv_json(&mut p2).await;
+    assert_eq!(sync["type"], "Sync");
+    assert_eq!(sync["payload"]["sequence_id"], 4);
+    let delta: sint_core::delta::StateDelta =
+        serde_json::from_value(sync["payload"]["delta"].clone()).unwrap();
+    let state = sint_server::initial_state().apply_delta(&delta).unwrap();
+    assert_eq!(state.chat[0].text, "c");
+
+    // An explicit sync still sends the whole state, for clients whose delta failed.
+    let (sequence_id, full) = sync_state(&mut p2).await;
+    assert_eq!(sequence_id, 4);
+    assert_eq!(full, state);
+}
+
+#[tokio::test]
+async fn test_delta_from_persisted_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(sint_server::ServerConfig {
+        resume_buffer: 0,
+        ..persistent(dir.path(), 2)
+    })
+    .await;
+    let mut p1 = join_with(addr, "snap_room", "P1", &["Delta"]).await;
+    for msg in [join_event("P1"), chat_event("P1", "a")] {
+        send_json(&mut p1, msg).await;
+        assert_eq!(recv_json(&mut p1).await["type"], "Event");
+    }
+    // Compaction is announced after the event it covers
+    let marker = recv_json(&mut p1).await;
+    assert_eq!(marker["type"], "Snapshot");
+    assert_eq!(marker["payload"]["sequence_id"], 2);
+    let snapshot = stored_room(dir.path(), "snap_room", 2)
+        .await
+        .snapshot
+        .unwrap()
+        .state;
+    assert_eq!(marker["payload"]["hash"], snapshot.snapshot_hash());
+    send_json(&mut p1, chat_event("P1", "b")).await;
+    recv_json(&mut p1).await;
+
+    let delta_after = async |ws: &mut Ws, after: u64| {
+        send_json(ws, resume(after)).await;
+        let sync = recv_json(ws).await;
+        assert_eq!(sync["type"], "Sync");
+        assert_eq!(sync["payload"]["sequence_id"], 3);
+        serde_json::from_value::<sint_core::delta::StateDelta>(sync["payload"]["delta"].clone())
+            .unwrap()
+    };
+    // A client past the snapshot gets only what changed since
+    let delta = delta_after(&mut p1, 2).await;
+    assert_eq!(delta.base, snapshot.snapshot_hash());
+    let state = snapshot.apply_delta(&delta).unwrap();
+    assert_eq!(state.chat[0].text, "b");
+    // One from before it gets a delta from the initial state
+    let delta = delta_after(&mut p1, 0).await;
+    assert_eq!(
+        sint_server::initial_state().apply_delta(&delta).unwrap(),
+        state
+    );
+}
+
+#[tokio::test]
+async fn test_messagepack_frames() {
+    use tokio_tungstenite::tungstenite::Message;
+    let addr = spawn_server(authoritative()).await;
+    let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    // The answer to Hello is JSON, since the client cannot know the encoding yet.
+    let reply = hello(&mut ws, sint_server::PROTOCOL_VERSION, &["MessagePack"]).await;
+    assert_eq!(
+        reply["payload"]["capabilities"],
+        serde_json::json!(["MessagePack"])
+    );
+
+    let join_msg = serde_json::json!({
+        "type": "Join",
+        "payload": { "room_id": "binary_room", "player_id": "P1" }
+    });
+    ws.send(Message::Binary(
+        sint_server::msgpack::to_vec(&join_msg).unwrap(),
+    ))
+    .await
+    .unwrap();
+    // The same broadcast reaches a JSON socket as text
+    let mut text_ws = join(addr, "binary_room", "P2").await;
+    send_json(&mut ws, join_event("P1")).await;
+
+    let mut recv_binary = async || -> serde_json::Value {
+        loop {
+            match tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap()
+            {
+                Message::Binary(bytes) => return sint_server::msgpack::from_slice(&bytes).unwrap(),
+                Message::Ping(_) | Message::Pong(_) => {}
+                other => panic!("Expected binary message, got {:?}", other),
+            }
+        }
+    };
+    assert_eq!(recv_binary().await["type"], "Welcome");
+    let event = recv_binary().await;
+    assert_eq!(event["type"], "Event");
+    assert_eq!(event["payload"]["sequence_id"], 1);
+    assert_eq!(event["payload"]["data"]["player_id"], "P1");
+    assert_eq!(recv_json(&mut text_ws).await, event);
+}
+
+// --- Deployment ---
+
+#[tokio::test]
+async fn test_serves_static_client() {
+    let dist = tempfile::tempdir().unwrap();
+    std::fs::write(dist.path().join("index.html"), "<html>client</html>").unwrap();
+    std::fs::write(dist.path().join("app.js"), "console.log(1)").unwrap();
+    let addr = spawn_server(sint_server::ServerConfig {
+        static_dir: Some(dist.path().to_owned()),
+        ..Default::default()
+    })
+    .await;
+
+    let get = |path: &str| reqwest::get(format!("http://{}{}", addr, path));
+    assert_eq!(
+        get("/app.js").await.unwrap().text().await.unwrap(),
+        "console.log(1)"
+    );
+    // Client-side routes fall back to the app shell.
+    let page = get("/game/some_room").await.unwrap();
+    assert_eq!(page.status(), 200);
+    assert_eq!(page.text().await.unwrap(), "<html>client</html>");
+    // The API still wins over the fallback.
+    assert!(
+        get("/api/rooms")
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+            .contains("rooms")
+    );
+}
+
+#[tokio::test]
+async fn test_cors_allows_configured_origins_only() {
+    let allowed = |addr: std::net::SocketAddr, origin: &'static str| async move {
+        reqwest::Client::new()
+            .get(format!("http://{}/api/rooms", addr))
+            .header("Origin", origin)
+            .send()
+            .await
+            .unwrap()
+            .headers()
+            .get("access-control-allow-origin")
+            .map(|v| v.to_str().unwrap().to_owned())
+    };
+
+    let addr = spawn_server(Default::default()).await;
+    assert_eq!(allowed(addr, "http://elsewhere.example").await, None);
+
+    let addr = spawn_server(sint_server::ServerConfig {
+        cors_origins: vec!["http://play.example".to_owned()],
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(
+        allowed(addr, "http://play.example").await.as_deref(),
+        Some("http://play.example")
+    );
+    assert_eq!(allowed(addr, "http://elsewhere.example").await, None);
+}
+
+#[tokio::test]
+async fn test_rate_limit_drops_excess_messages() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        rate_limit: Some(sint_server::RateLimit {
+            per_second: 0.1,
+            burst: 4,
+        }),
+        ..Default::default()
+    })
+    .await;
+    // Join, the join event and two chats fit the burst; the last two do not.
+    let mut p1 = join(addr, "limited_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    for message in ["a", "b", "c", "d"] {
+        send_json(&mut p1, chat_event("P1", message)).await;
+    }
+    let mut types = vec![];
+    for _ in 0..5 {
+        types.push(
+            recv_json(&mut p1).await["type"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        );
+    }
+    types.sort();
+    assert_eq!(types, ["Event", "Event", "Event", "Rejected", "Rejected"]);
+    assert_eq!(scrape(addr).await["sint_rate_limited_total"], 2);
+}
+
+#[tokio::test]
+async fn test_oversized_message_closes_socket() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        max_message_size: 1024,
+        ..Default::default()
+    })
+    .await;
+    let mut p1 = join(addr, "size_room", "P1").await;
+    send_json(&mut p1, chat_event("P1", &"x".repeat(2048))).await;
+    let next = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            match p1.next().await {
+                Some(Ok(tokio_tungstenite::tungstenite::Message::Ping(_))) => {}
+                other => return other,
+            }
+        }
+    })
+    .await
+    .expect("Socket was not closed");
+    assert!(matches!(
+        next,
+        None | Some(Err(_)) | Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_)))
+    ));
+}
+
+// --- Chat ---
+
+async fn chat_page(addr: std::net::SocketAddr, room_id: &str, query: &str) -> serde_json::Value {
+    let resp = reqwest::get(format!(
+        "http://{}/api/rooms/{}/chat?{}",
+        addr,
+        room_id.replace(' ', "%20"),
+        query
+    ))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.json().await.unwrap()
+}
+
+fn chat_texts(page: &serde_json::Value) -> Vec<String> {
+    page["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_chat_is_stamped_by_the_server() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "chat_room", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    send_json(&mut p1, chat_event("P1", "hello")).await;
+    let event = recv_json(&mut p1).await;
+    let timestamp = event["payload"]["timestamp"].as_u64().unwrap();
+    assert!(timestamp > 1_600_000_000_000);
+
+    let page = chat_page(addr, "chat_room", "").await;
+    let message = &page["messages"][0];
+    assert_eq!(message["text"], "hello");
+    assert_eq!(message["sequence_id"], 2);
+    assert_eq!(message["timestamp"], timestamp);
+    assert_eq!(message["channel"], "Crew");
+    assert_eq!(page["before"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn test_chat_pages_by_channel() {
+    let addr = spawn_server(authoritative()).await;
+    let mut p1 = join(addr, "channels", "P1").await;
+    send_json(&mut p1, join_event("P1")).await;
+    recv_json(&mut p1).await;
+    for message in ["a", "b", "c"] {
+        send_json(&mut p1, chat_event("P1", message)).await;
+        recv_json(&mut p1).await;
+    }
+    // Connected but not in the game
+    let mut watcher = join(addr, "channels", "Watcher").await;
+    send_json(&mut watcher, chat_event("Watcher", "go!")).await;
+    recv_json(&mut watcher).await;
+
+    let page = chat_page(addr, "channels", "channel=Crew&limit=2").await;
+    assert_eq!(chat_texts(&page), ["b", "c"]);
+    let before = page["before"].as_u64().unwrap();
+    let page = chat_page(addr, "channels", &format!("channel=Crew&before={}", before)).await;
+    assert_eq!(chat_texts(&page), ["a"]);
+    assert_eq!(page["before"], serde_json::Value::Null);
+
+    let page = chat_page(addr, "channels", "channel=Spectators").await;
+    assert_eq!(chat_texts(&page), ["go!"]);
+    assert_eq!(chat_texts(&chat_page(addr, "channels", "").await).len(), 4);
+}
+
+#[tokio::test]
+async fn test_private_room_chat_needs_credentials() {
+    let addr = spawn_server(Default::default()).await;
+    let (_, created) = create_room(addr, serde_json::json!({ "room_id": "hush" })).await;
+    let token = created["invite_token"].as_str().unwrap();
+
+    let resp = reqwest::get(format!("http://{}/api/rooms/hush/chat", addr))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+    chat_page(addr, "hush", &format!("invite_token={}", token)).await;
+
+    let resp = reqwest::get(format!("http://{}/api/rooms/nowhere/chat", addr))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_chat_persistence_is_per_room() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let mut tokens = vec![];
+    for (room_id, persist_chat) in [("kept", true), ("forgotten", false)] {
+        let (_, created) = create_room(
+            addr,
+            serde_json::json!({ "room_id": room_id, "persist_chat": persist_chat }),
+        )
+        .await;
+        let token = created["invite_token"].as_str().unwrap().to_owned();
+        let (mut ws, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+        send_json(
+            &mut ws,
+            serde_json::json!({
+                "type": "Join",
+                "payload": { "room_id": room_id, "player_id": "P1", "invite_token": token }
+            }),
+        )
+        .await;
+        recv_json(&mut ws).await;
+        send_json(&mut ws, join_event("P1")).await;
+        recv_json(&mut ws).await;
+        send_json(&mut ws, chat_event("P1", "remember me")).await;
+        recv_json(&mut ws).await;
+        stored_room(dir.path(), room_id, 2).await;
+        tokens.push(token);
+    }
+
+    let addr = spawn_server(persistent(dir.path(), 100)).await;
+    let kept = chat_page(addr, "kept", &format!("invite_token={}", tokens[0])).await;
+    assert_eq!(chat_texts(&kept), ["remember me"]);
+    let forgotten = chat_page(addr, "forgotten", &format!("invite_token={}", tokens[1])).await;
+    assert!(chat_texts(&forgotten).is_empty());
+}
+
+#[tokio::test]
+async fn test_chat_history_is_capped() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = spawn_server(sint_server::ServerConfig {
+        chat_history: 3,
+        ..persistent(dir.path(), 100)
+    })
+    .await;
+    let mut ws = join(addr, "chatty", "P1").await;
+    send_json(&mut ws, join_event("P1")).await;
+    recv_json(&mut ws).await;
+    for message in ["a", "b", "c", "d", "e", "f", "g"] {
+        send_json(&mut ws, chat_event("P1", message)).await;
+        recv_json(&mut ws).await;
+    }
+
+    assert_eq!(
+        chat_texts(&chat_page(addr, "chatty", "").await),
+        ["e", "f", "g"]
+    );
+    // The file was cut back once it passed twice the history
+    let stored = stored_room(dir.path(), "chatty", 8).await;
+    let texts: Vec<String> = stored
+        .chat
+        .unwrap()
+        .into_iter()
+        .map(|e| e.message.text)
+        .collect();
+    assert_eq!(texts, ["e", "f", "g"]);
+}
@@ -1,7 +1,6 @@
 import gymnasium as gym
 import numpy as np
 from gymnasium import spaces
-import sint_core
 import sint_solver
 import json
 import uuid
@@ -28,7 +27,8 @@ class SintEnv(gym.Env):
         self.test_mode = test_mode
         self.max_steps = max_steps
         self.current_steps = 0
-        self.state: Optional[Dict[str, Any]] = None
+        self.game: Optional[sint_solver.Game] = None
+        self._state_dict: Optional[Dict[str, Any]] = None
         self.history: List[Tuple[str, Any]] = []
         self.last_score = 0.0
         self.last_details: Dict[str, float] = {}
@@ -46,6 +46,18 @@ class SintEnv(gym.Env):
         # Total: 8 + 90 + 54 + 49 + 245 + 2 = 448
         self.observation_space = spaces.Box(low=-1000, high=1000000, shape=(448,), dtype=np.float32)
 
+        # Compact tuples (see sint_core.Game.valid_actions) for each action index.
+        self._action_tuples = [self._action_tuple(i) for i in range(46)]
+
+    @property
+    def state(self) -> Optional[Dict[str, Any]]:
+        """The current state as a dict, converted from the Rust game at most once per step."""
+        if self.game is None:
+            return None
+        if self._state_dict is None:
+            self._state_dict = self.game.to_dict()
+        return self._state_dict
+
     def _get_obs(self):
         if not self.state:
             return np.zeros(self.observation_space.shape, dtype=np.float32)
@@ -160,10 +172,10 @@ class SintEnv(gym.Env):
         self.history = []
         self.current_steps = 0
         
-        # Use verify_linear to get initial state
-        result = sint_solver.verify_linear(self.player_ids, self.initial_seed, self.history)
-        self.state = result['final_state']
-        assert self.state is not None, "State should not be None after reset"
+        # The game stays in Rust; apply_linear with no actions scores the initial state
+        self.game = sint_solver.Game(self.player_ids, self.initial_seed)
+        self._state_dict = None
+        result = sint_solver.apply_linear(self.game, [])
         self.last_score = result['rl_score']
         self.last_details = result['rl_details']
         
@@ -184,23 +196,17 @@ class SintEnv(gym.Env):
             
         game_action = self._map_action(action_idx, active_id)
         
-        # We only need to verify the LATEST action against the CURRENT state
-        latest_action = [(active_id, game_action)]
-        
-        # Verify incrementally
-        result = sint_solver.verify_linear(self.player_ids, self.initial_seed, latest_action, initial_state=self.state)
+        # Apply the latest action to the game in place
+        result = sint_solver.apply_linear(self.game, [(active_id, game_action)])
+        self._state_dict = None
         
         # Record the action in a linear history
         self.history.append((active_id, game_action))
-        
-        # Update state
-        self.state = result['final_state']
-        assert self.state is not None, "State should not be None after step"
 
         reward = result['rl_score']
         self.last_details = result['rl_details']
         
-        terminated = self.state['phase'] in ['Victory', 'GameOver']
+        terminated = self.game.is_over
         truncated = self.current_steps >= self.max_steps
         
         # If we timed out without winning or losing, apply a failure penalty
@@ -232,17 +238,13 @@ class SintEnv(gym.Env):
         err_str = str(error)
         return "still have AP" in err_str or "Round advanced" in err_str
 
-    def _actions_equal(self, a1, a2):
-        if a1['type'] != a2['type']:
-            return False
-        p1 = a1.get('payload')
-        p2 = a2.get('payload')
-        if p1 == p2:
-            return True
-        if p1 and p2:
-            # Handle potential type mismatches (e.g. string vs int room_id)
-            return str(p1) == str(p2)
-        return False
+    def _action_tuple(self, idx):
+        """The compact tuple form of `_map_action(idx)`, in the payload order of sint_core."""
+        action = self._map_action(idx, None)
+        payload = action.get('payload', {})
+        if action['type'] == 'Throw':
+            return ('Throw', payload['target_player'], payload['item_index'])
+        return (action['type'], *payload.values())
 
     def _map_action(self, idx, player_id):
         if idx < 10:
@@ -285,13 +287,6 @@ class SintEnv(gym.Env):
         if not active_id:
             return np.zeros(46, dtype=bool)
 
-        masks = np.zeros(46, dtype=bool)
-        valid_actions = sint_core.get_valid_actions(self.state, active_id)
-
-        for i in range(46):
-            act = self._map_action(i, active_id)
-            if any(self._actions_equal(act, va) for va in valid_actions):
-                masks[i] = True
-
-        return masks
+        valid_actions = set(self.game.valid_actions(active_id))
+        return np.array([t in valid_actions for t in self._action_tuples], dtype=bool)
             
\ No newline at end of file
@@ -28,9 +28,9 @@ def main():
             steps += 1
             
             if steps % 10 == 0:
-                print(f"Step {steps}: Hull={env.state.hull_integrity}, Boss HP={env.state.enemy.hp}, Phase={env.state.phase}")
+                print(f"Step {steps}: Hull={env.game.hull}, Boss HP={env.game.enemy_hp}, Phase={env.game.phase}")
         
-        print(f"Finished: Reward={total_reward}, Steps={steps}, Final Phase={env.state.phase}")
+        print(f"Finished: Reward={total_reward}, Steps={steps}, Final Phase={env.game.phase}")
 
 if __name__ == "__main__":
     main()
@@ -0,0 +1,70 @@
+import copy
+import unittest
+import sint_core
+import sint_solver
+
+class TestGame(unittest.TestCase):
+    def setUp(self):
+        self.player_ids = ["P1", "P2"]
+        self.seed = 12345
+
+    def planning_game(self):
+        """A game advanced from the lobby to the first TacticalPlanning phase."""
+        game = sint_solver.Game(self.player_ids, self.seed)
+        sint_solver.apply_linear(game, [])
+        self.assertEqual(game.phase, "TacticalPlanning")
+        return game
+
+    def test_valid_actions_are_tuples_that_apply(self):
+        """Every tuple from valid_actions is accepted by apply."""
+        game = self.planning_game()
+        actions = game.valid_actions("P1")
+        self.assertIn(("Move", 0), actions)
+        for action in actions:
+            self.assertIsInstance(action, tuple)
+            copy.copy(game).apply("P1", action)
+
+    def test_tuple_and_dict_actions_agree(self):
+        """A tuple action has the same effect as its serde dict."""
+        by_tuple = self.planning_game()
+        by_dict = by_tuple.clone()
+        by_tuple.apply("P1", ("Move", 0))
+        by_dict.apply("P1", {"type": "Move", "payload": {"to_room": 0}})
+        self.assertEqual(by_tuple.zobrist, by_dict.zobrist)
+        # In planning the move is only queued
+        self.assertEqual(len(by_tuple.to_dict()['proposal_queue']), 1)
+
+    def test_invalid_action_leaves_game_unchanged(self):
+        """A rejected action raises ValueError and keeps the old state."""
+        game = self.planning_game()
+        before = game.zobrist
+        with self.assertRaises(ValueError):
+            game.apply("P1", ("Bake",))
+        with self.assertRaises(ValueError):
+            game.apply("P1", ("Fly", 3))
+        self.assertEqual(game.zobrist, before)
+
+    def test_apply_linear_matches_verify_linear(self):
+        """apply_linear updates the game in place and scores like verify_linear."""
+        actions = [("P1", ("Move", 0)), ("P2", {"type": "Move", "payload": {"to_room": 0}})]
+        game = sint_solver.Game(self.player_ids, self.seed)
+        result = sint_solver.apply_linear(game, actions)
+        self.assertIsNone(result['error'])
+
+        dict_actions = [
+            ("P1", {"type": "Move", "payload": {"to_room": 0}}),
+            ("P2", {"type": "Move", "payload": {"to_room": 0}}),
+        ]
+        expected = sint_solver.verify_linear(self.player_ids, self.seed, dict_actions)
+        self.assertEqual(result['success'], expected['success'])
+        self.assertEqual(result['rl_score'], expected['rl_score'])
+        self.assertEqual(game.to_dict()['sequence_id'], expected['final_state']['sequence_id'])
+        self.assertEqual(game.player_rooms(), {"P1": 0, "P2": 0})
+
+    def test_games_from_another_module_are_rejected(self):
+        """Each extension module has its own Game class."""
+        with self.assertRaises(TypeError):
+            sint_solver.apply_linear(sint_core.Game(self.player_ids, self.seed), [])
+
+if __name__ == '__main__':
+    unittest.main()
@@ -11,7 +11,9 @@ use pythonize::{depythonize, pythonize};
 #[cfg(feature = "python")]
 use sint_core::logic::GameLogic;
 #[cfg(feature = "python")]
-use sint_core::types::{GameAction, GameState};
+use sint_core::types::{Action, GameAction, GameState};
+#[cfg(feature = "python")]
+use sint_core::{Game, action_from_py};
 #[cfg(feature = "python")]
 use std::collections::HashMap;
 #[cfg(feature = "python")]
@@ -250,14 +252,58 @@ fn verify_linear(
     Ok(py_result.into())
 }
 
+/// Applies `actions_list` (pairs of player id and action tuple or dict) to `game` in
+/// place and returns the RL reward. Same as `verify_linear`, but the state stays in
+/// Rust, so `final_state` and `history` are left out of the result.
+#[cfg(feature = "python")]
+#[pyfunction]
+fn apply_linear(
+    py: Python,
+    mut game: PyRefMut<'_, Game>,
+    actions_list: Bound<'_, PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let mut actions = Vec::new();
+    for item in actions_list.try_iter()? {
+        let (player_id, action): (String, Bound<'_, PyAny>) = item?.extract()?;
+        match action_from_py(&action)? {
+            Action::Game(action) => actions.push((player_id, action)),
+            Action::Meta(_) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Meta actions cannot be replayed",
+                ));
+            }
+        }
+    }
+
+    let result = crate::verification::run_verification_linear(game.state().clone(), actions);
+
+    let dict = PyDict::new(py);
+    dict.set_item("success", result.success)?;
+    dict.set_item(
+        "error",
+        pythonize(py, &result.error)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+    )?;
+    dict.set_item("rl_score", result.rl_score)?;
+    dict.set_item(
+        "rl_details",
+        pythonize(py, &result.rl_details)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+    )?;
+    game.set_state(result.final_state);
+    Ok(dict.into_any().unbind())
+}
+
 #[cfg(feature = "python")]
 #[pymodule]
 fn sint_solver(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(verify_solution, m)?)?;
     m.add_function(wrap_pyfunction!(verify_linear, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_linear, m)?)?;
     m.add_function(wrap_pyfunction!(get_trajectory_log, m)?)?;
     m.add_function(wrap_pyfunction!(compute_score, m)?)?;
     m.add_function(wrap_pyfunction!(compute_score_rhea, m)?)?;
     m.add_function(wrap_pyfunction!(compute_score_rl, m)?)?;
+    m.add_class::<Game>()?;
     Ok(())
 }
@@ -0,0 +1,133 @@
+//! One decision at a time for a single seat in a live game, for bots that play
+//! alongside humans.
+//!
+//! The searches plan for the whole crew, always moving the first player who still
+//! has AP. To plan for one seat, the search starts from a copy of the state in
+//! which everyone else has already voted ready this round.
+
+use crate::scoring::beam::BeamScoringWeights;
+use crate::scoring::rhea::RheaScoringWeights;
+use crate::search::SearchProgress;
+use crate::search::beam::beam_search_from;
+use crate::search::config::{BeamSearchConfig, ParallelismMode, RHEAConfig};
+use crate::search::rhea::rhea_search_from;
+use serde::{Deserialize, Serialize};
+use sint_core::logic::actions::{action_cost, get_valid_actions};
+use sint_core::types::{Action, GameAction, GamePhase, GameState};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BotStrategy {
+    Beam,
+    #[default]
+    Rhea,
+}
+
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    pub strategy: BotStrategy,
+    /// Time the search may take for one action.
+    pub budget: Duration,
+    pub beam_width: usize,
+    pub rhea_horizon: usize,
+    pub rhea_population: usize,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            strategy: BotStrategy::Rhea,
+            budget: Duration::from_millis(500),
+            beam_width: 50,
+            rhea_horizon: 30,
+            rhea_population: 20,
+        }
+    }
+}
+
+/// The next action for `player_id`, or `None` while there is nothing for them to do
+/// (waiting on the others, or the game is over).
+pub fn decide(state: &GameState, player_id: &str, config: &BotConfig) -> Option<GameAction> {
+    let me = state.players.get(player_id)?;
+    if me.is_ready || matches!(state.phase, GamePhase::GameOver | GamePhase::Victory) {
+        return None;
+    }
+    // Outside planning there is nothing to choose; the driver readies everyone too.
+    if state.phase != GamePhase::TacticalPlanning || me.ap <= 0 {
+        return Some(GameAction::VoteReady { ready: true });
+    }
+
+    let mut root = state.clone();
+    for player in root.players.values_mut() {
+        if player.id != player_id {
+            player.is_ready = true;
+        }
+    }
+
+    // Same state, same decision. Kept small: RHEA adds offsets to its seed.
+    let seed = state.zobrist() >> 32;
+    let node = match config.strategy {
+        BotStrategy::Beam => beam_search_from(
+            root,
+            &BeamSearchConfig {
+                players: state.players.len(),
+                seed,
+                width: config.beam_width,
+                steps: usize::MAX,
+                time_limit: config.budget.as_secs(),
+                verbose: false,
+                parallelism: ParallelismMode::Automatic,
+            },
+            config.budget,
+            &BeamScoringWeights::default(),
+            None::<fn(SearchProgress)>,
+        ),
+        BotStrategy::Rhea => rhea_search_from(
+            root,
+            &RHEAConfig {
+                players: state.players.len(),
+                seed,
+                horizon: config.rhea_horizon,
+                // Evolve for as long as the budget allows, then commit one action.
+                generations: usize::MAX,
+                population_size: config.rhea_population,
+                max_steps: 1,
+                time_limit: config.budget.as_secs(),
+                verbose: false,
+            },
+            config.budget,
+            &RheaScoringWeights::default(),
+            None::<fn(SearchProgress)>,
+        ),
+    };
+
+    let history = node.as_ref().map(|n| n.get_history());
+    match history.as_ref().and_then(|h| h.first()) {
+        Some((pid, action)) if pid == player_id => Some(action.clone()),
+        // No plan for this seat within the budget
+        _ => Some(fallback(state, player_id)),
+    }
+}
+
+/// The cheapest valid action that does something for `player_id`, for when there
+/// is no plan to follow. Spends AP one action at a time rather than passing the
+/// whole round; passes only when nothing else is valid.
+pub fn fallback(state: &GameState, player_id: &str) -> GameAction {
+    get_valid_actions(state, player_id)
+        .into_iter()
+        .filter_map(|action| match action {
+            Action::Game(action) => Some(action),
+            Action::Meta(_) => None,
+        })
+        .filter(|action| {
+            !matches!(
+                action,
+                GameAction::Chat { .. }
+                    | GameAction::VoteReady { .. }
+                    | GameAction::Pass
+                    | GameAction::Undo { .. }
+            )
+        })
+        .min_by_key(|action| action_cost(state, player_id, action))
+        .unwrap_or(GameAction::Pass)
+}
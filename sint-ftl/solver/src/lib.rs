@@ -1,4 +1,5 @@
 pub mod bindings;
+pub mod bot;
 pub mod driver;
 pub mod optimization;
 pub mod replay;
@@ -660,10 +660,8 @@ pub fn run_ga(
             });
         }
 
-        let mut scored_pop: Vec<(EvaluationMetrics, Vec<f64>)> = scored_metrics
-            .into_iter()
-            .zip(population.into_iter())
-            .collect();
+        let mut scored_pop: Vec<(EvaluationMetrics, Vec<f64>)> =
+            scored_metrics.into_iter().zip(population).collect();
 
         for (m, _) in scored_pop.iter_mut() {
             m.average(config.seeds.len());
@@ -559,7 +559,7 @@ pub fn score_static(
     }
 
     let projected_hull = state.hull_integrity
-        - fire_damage as i32
+        - fire_damage
         - telegraphed_hull_damage
         - (fire_spread_sources as f64 * weights.fire_spread_projected_damage) as i32;
     // If fires will kill us this round, apply massive penalty but don't prune yet.
@@ -860,12 +860,12 @@ pub fn score_static(
         * hazard_multiplier;
 
     for card in &state.active_situations {
-        if card.id == CardId::Overheating {
-            if let Some(eid) = engine_id {
-                let count = state.players.values().filter(|p| p.room_id == eid).count();
-                details.situations -=
-                    count as f64 * weights.situation_exposure_penalty * hazard_multiplier;
-            }
+        if card.id == CardId::Overheating
+            && let Some(eid) = engine_id
+        {
+            let count = state.players.values().filter(|p| p.room_id == eid).count();
+            details.situations -=
+                count as f64 * weights.situation_exposure_penalty * hazard_multiplier;
         }
     }
 
@@ -1376,12 +1376,8 @@ pub fn score_static(
     let mut item_throw_count = 0;
 
     let player_to_idx = |pid: &PlayerId| -> usize {
-        if pid.starts_with('P') {
-            pid[1..]
-                .parse::<usize>()
-                .unwrap_or(0)
-                .saturating_sub(1)
-                .min(7)
+        if let Some(num) = pid.strip_prefix('P') {
+            num.parse::<usize>().unwrap_or(0).saturating_sub(1).min(7)
         } else {
             0
         }
@@ -1502,7 +1498,7 @@ pub fn score_static(
             }
         }
 
-        if let GameAction::Move { to_room } = act {
+        if let GameAction::Move { to_room, .. } = act {
             player_moves[p_idx].push((idx, *to_room));
         }
     }
@@ -1552,11 +1548,11 @@ pub fn score_static(
                         match action {
                             GameAction::Throw { target_player, .. }
                             | GameAction::Revive { target_player }
-                            | GameAction::FirstAid { target_player } => {
-                                if target_player == &pid_str {
-                                    useful = true;
-                                    break;
-                                }
+                            | GameAction::FirstAid { target_player }
+                                if target_player == &pid_str =>
+                            {
+                                useful = true;
+                                break;
                             }
                             _ => {}
                         }
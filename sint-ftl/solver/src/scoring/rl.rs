@@ -92,16 +92,15 @@ pub fn score_rl(
 
         match action {
             sint_core::types::GameAction::RaiseShields
-            | sint_core::types::GameAction::EvasiveManeuvers => {
+            | sint_core::types::GameAction::EvasiveManeuvers
                 // Reward defensive actions if an attack is telegraphed
                 if current
                     .enemy
                     .next_attack
                     .as_ref()
-                    .is_some_and(|a| a.target_room.is_some())
-                {
-                    details.vitals += weights.defensive_action_reward;
-                }
+                    .is_some_and(|a| a.target_room.is_some()) =>
+            {
+                details.vitals += weights.defensive_action_reward;
             }
             _ => {}
         }
@@ -1,7 +1,6 @@
 use crate::scoring::ScoreDetails;
 use sint_core::types::{GameAction, GameState, PlayerId};
 use smallvec::SmallVec;
-use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 pub mod beam;
@@ -109,54 +108,8 @@ impl std::hash::Hash for SearchNode {
     }
 }
 
-struct DeterministicHasher(u64);
-impl Hasher for DeterministicHasher {
-    fn finish(&self) -> u64 {
-        self.0
-    }
-    fn write(&mut self, bytes: &[u8]) {
-        for &b in bytes {
-            self.0 ^= b as u64;
-            self.0 = self.0.wrapping_mul(0x100000001b3);
-        }
-    }
-}
-
+/// Transposition key for search. Uses the core Zobrist key, which also covers
+/// the deck order and the RNG seed.
 pub fn get_state_signature(state: &GameState) -> u64 {
-    let mut hasher = DeterministicHasher(0xcbf29ce484222325);
-    state.phase.hash(&mut hasher);
-    state.turn_count.hash(&mut hasher);
-    state.hull_integrity.hash(&mut hasher);
-    state.enemy.hp.hash(&mut hasher);
-    state.enemy.next_attack.hash(&mut hasher);
-
-    for p in state.players.values() {
-        p.room_id.hash(&mut hasher);
-        p.hp.hash(&mut hasher);
-        p.ap.hash(&mut hasher);
-        p.is_ready.hash(&mut hasher);
-        p.inventory.hash(&mut hasher);
-        p.status.hash(&mut hasher);
-    }
-
-    for room in state.map.rooms.values() {
-        room.hazards.hash(&mut hasher);
-        room.items.hash(&mut hasher);
-    }
-
-    // Hash active situations fully (sorted by ID for canonicalization)
-    let mut situations: Vec<_> = state.active_situations.iter().collect();
-    situations.sort_by_key(|c| c.id);
-    for card in situations {
-        card.hash(&mut hasher);
-    }
-
-    state.deck.len().hash(&mut hasher);
-    state.discard.len().hash(&mut hasher);
-
-    for prop in &state.proposal_queue {
-        prop.hash(&mut hasher);
-    }
-
-    hasher.finish()
+    state.zobrist()
 }
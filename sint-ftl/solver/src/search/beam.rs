@@ -6,7 +6,7 @@ use crate::search::{SearchNode, SearchProgress, get_state_signature};
 use rayon::prelude::*;
 use sint_core::logic::pathfinding::MapDistances;
 use sint_core::logic::{GameLogic, actions::get_valid_actions};
-use sint_core::types::{Action, GameAction, GamePhase};
+use sint_core::types::{Action, GameAction, GamePhase, GameState};
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -80,8 +80,29 @@ where
     F: Fn(SearchProgress) + Sync + Send,
 {
     let player_ids: Vec<String> = (0..config.players).map(|i| format!("P{}", i + 1)).collect();
-    let initial_state = GameLogic::new_game(player_ids.clone(), config.seed);
+    let initial_state = GameLogic::new_game(player_ids, config.seed);
+    let time_limit = Duration::from_secs(config.time_limit);
+    beam_search_from(
+        initial_state,
+        config,
+        time_limit,
+        weights,
+        progress_callback,
+    )
+}
 
+/// Beam search from an arbitrary state, e.g. a game in progress. `config.players`
+/// and `config.seed` are ignored; `time_limit` replaces `config.time_limit`.
+pub fn beam_search_from<F>(
+    initial_state: GameState,
+    config: &BeamSearchConfig,
+    time_limit: Duration,
+    weights: &BeamScoringWeights,
+    progress_callback: Option<F>,
+) -> Option<SearchNode>
+where
+    F: Fn(SearchProgress) + Sync + Send,
+{
     // Stabilize initial state using Driver
     let initial_driver = GameDriver::new(initial_state);
 
@@ -89,13 +110,15 @@ where
 
     if config.verbose {
         println!(
-            "🚀 Starting Beam Search: Width={}, Seed={}, Players={}, Steps={}, TimeLimit={}s",
-            config.width, config.seed, config.players, config.steps, config.time_limit
+            "🚀 Starting Beam Search: Width={}, Players={}, Steps={}, TimeLimit={:?}",
+            config.width,
+            initial_driver.state.players.len(),
+            config.steps,
+            time_limit
         );
     }
 
     let start_time = Instant::now();
-    let time_limit = Duration::from_secs(config.time_limit);
 
     let start_sig = get_state_signature(&initial_driver.state);
     let mut beam = vec![Arc::new(SearchNode {
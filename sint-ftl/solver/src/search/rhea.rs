@@ -27,13 +27,33 @@ where
 {
     let player_ids: Vec<String> = (0..config.players).map(|i| format!("P{}", i + 1)).collect();
     let initial_state = GameLogic::new_game(player_ids, config.seed);
+    let time_limit = Duration::from_secs(config.time_limit);
+    rhea_search_from(
+        initial_state,
+        config,
+        time_limit,
+        weights,
+        progress_callback,
+    )
+}
 
+/// RHEA from an arbitrary state, e.g. a game in progress. `config.players` is
+/// ignored; `time_limit` replaces `config.time_limit`.
+pub fn rhea_search_from<F>(
+    initial_state: GameState,
+    config: &RHEAConfig,
+    time_limit: Duration,
+    weights: &RheaScoringWeights,
+    progress_callback: Option<F>,
+) -> Option<SearchNode>
+where
+    F: Fn(SearchProgress) + Sync + Send,
+{
     // Stabilize root
-    let root_driver = GameDriver::new(initial_state.clone());
+    let root_driver = GameDriver::new(initial_state);
     let mut current_state = root_driver.state.clone();
 
     let start_time = Instant::now();
-    let time_limit = Duration::from_secs(config.time_limit);
 
     // Construct the trajectory chain as we play
     let mut search_node_chain: Option<Arc<SearchNode>> = Some(Arc::new(SearchNode {
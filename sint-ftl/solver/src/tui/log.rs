@@ -5,6 +5,7 @@ use ratatui::{
     style::{Color, Style},
     widgets::{Block, Borders, List, ListItem, Widget},
 };
+use sint_core::types::{GameAction, PlayerId};
 use std::sync::Arc;
 
 pub struct LogWidget<'a> {
@@ -13,30 +14,51 @@ pub struct LogWidget<'a> {
 
 impl<'a> Widget for LogWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let mut items = Vec::new();
-        let mut title = "Best Trajectory".to_string();
-
-        if let Some(node) = self.current_node {
-            title = format!("Best Trajectory ({} actions)", node.history_len);
-            let history = node.get_recent_history(20);
+        let log = match self.current_node {
+            Some(node) => ActionLogWidget {
+                title: format!("Best Trajectory ({} actions)", node.history_len),
+                first: node.history_len.saturating_sub(20) + 1,
+                entries: node.get_recent_history(20).into_iter().collect(),
+                placeholder: "",
+            },
+            None => ActionLogWidget {
+                title: "Best Trajectory".to_string(),
+                first: 1,
+                entries: vec![],
+                placeholder: "Waiting for data...",
+            },
+        };
+        log.render(area, buf);
+    }
+}
 
-            let start_idx = node.history_len.saturating_sub(20);
+/// Numbered `player -> action` lines, the first one numbered `first`.
+pub struct ActionLogWidget<'a> {
+    pub title: String,
+    pub first: usize,
+    pub entries: Vec<&'a (PlayerId, GameAction)>,
+    /// Shown instead of an empty list.
+    pub placeholder: &'a str,
+}
 
-            for (i, (pid, act)) in history.iter().enumerate() {
-                items.push(ListItem::new(format!(
-                    "{}: {} -> {:?}",
-                    start_idx + i + 1,
-                    pid,
-                    act
-                )));
-            }
-        } else {
-            items.push(ListItem::new("Waiting for data..."));
+impl<'a> Widget for ActionLogWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut items = Vec::new();
+        for (i, (pid, act)) in self.entries.iter().enumerate() {
+            items.push(ListItem::new(format!(
+                "{}: {} -> {:?}",
+                self.first + i,
+                pid,
+                act
+            )));
+        }
+        if items.is_empty() && !self.placeholder.is_empty() {
+            items.push(ListItem::new(self.placeholder));
         }
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(title)
+            .title(self.title)
             .border_style(Style::default().fg(Color::White));
 
         List::new(items).block(block).render(area, buf);
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use sint_core::GameError;
 use sint_core::logic::GameLogic;
 use sint_core::logic::pathfinding::MapDistances;
-use sint_core::types::{Action, GameAction, GamePhase, GameState, ItemType};
+use sint_core::types::{Action, GameAction, GamePhase, GameState, ItemType, RouteMode};
 
 fn get_hazard_emoji(h: &sint_core::types::HazardType) -> &'static str {
     match h {
@@ -136,8 +136,18 @@ impl VerificationResult {
 pub fn parse_game_action(cmd: &str) -> GameAction {
     let cmd = cmd.trim();
     if cmd.starts_with("Move") {
-        let target: u32 = cmd.split_whitespace().nth(1).unwrap().parse().unwrap();
-        GameAction::Move { to_room: target }
+        // "Move 5", optionally followed by "Safest" and/or "force"
+        let parts: Vec<&str> = cmd.split_whitespace().collect();
+        let target: u32 = parts[1].parse().unwrap();
+        GameAction::Move {
+            to_room: target,
+            mode: if parts.contains(&"Safest") {
+                RouteMode::Safest
+            } else {
+                RouteMode::Shortest
+            },
+            force: parts.contains(&"force"),
+        }
     } else if cmd == "Bake" {
         GameAction::Bake
     } else if cmd == "Shoot" {
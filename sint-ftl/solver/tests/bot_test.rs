@@ -0,0 +1,60 @@
+use sint_core::logic::GameLogic;
+use sint_core::types::{Action, GameAction, GamePhase, PlayerStatus};
+use sint_solver::bot::{BotConfig, BotStrategy, decide, fallback};
+use sint_solver::driver::GameDriver;
+use std::time::{Duration, Instant};
+
+fn planning_state() -> sint_core::types::GameState {
+    let player_ids = vec!["P1".to_string(), "P2".to_string(), "P3".to_string()];
+    GameDriver::new(GameLogic::new_game(player_ids, 42)).state
+}
+
+#[test]
+fn test_bot_waits_or_readies_outside_planning() {
+    let lobby = GameLogic::new_game(vec!["P1".to_string()], 42);
+    assert_eq!(lobby.phase, GamePhase::Lobby);
+    let config = BotConfig::default();
+    assert_eq!(
+        decide(&lobby, "P1", &config),
+        Some(GameAction::VoteReady { ready: true })
+    );
+    assert_eq!(decide(&lobby, "Nobody", &config), None);
+
+    let mut state = planning_state();
+    state.players.get_mut("P2").unwrap().is_ready = true;
+    assert_eq!(decide(&state, "P2", &config), None);
+}
+
+#[test]
+fn test_bot_plans_for_its_own_seat() {
+    let state = planning_state();
+    for strategy in [BotStrategy::Beam, BotStrategy::Rhea] {
+        let config = BotConfig {
+            strategy,
+            budget: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        // P2 is not the first player with AP, so the search must not plan for P1.
+        let action = decide(&state, "P2", &config).expect("No decision");
+        assert!(start.elapsed() < Duration::from_secs(5));
+        GameLogic::apply_action(state.clone(), "P2", Action::Game(action.clone()), None)
+            .unwrap_or_else(|e| panic!("{:?} chose invalid {:?}: {}", strategy, action, e));
+    }
+}
+
+#[test]
+fn test_fallback_spends_ap_before_passing() {
+    let state = planning_state();
+    let action = fallback(&state, "P2");
+    assert_ne!(action, GameAction::Pass);
+    let next = GameLogic::apply_action(state.clone(), "P2", Action::Game(action), None)
+        .expect("Fallback should be valid");
+    assert!(next.players["P2"].ap < state.players["P2"].ap);
+
+    let mut fainted = state;
+    let p2 = fainted.players.get_mut("P2").unwrap();
+    p2.status.push(PlayerStatus::Fainted);
+    p2.ap = 0;
+    assert_eq!(fallback(&fainted, "P2"), GameAction::Pass);
+}
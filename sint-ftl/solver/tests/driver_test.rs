@@ -29,7 +29,7 @@ fn test_driver_apply_action() {
     // P1 moves
     let initial_ap = driver.state.players.get("P1").unwrap().ap;
     driver
-        .apply("P1", GameAction::Move { to_room: 1 })
+        .apply("P1", GameAction::move_to(1))
         .expect("Apply failed");
 
     // AP should be reduced
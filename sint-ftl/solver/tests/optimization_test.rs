@@ -13,6 +13,9 @@ fn test_checkpoint_roundtrip() {
         generations: 10,
         population: 5,
         seeds: vec![1, 2, 3],
+        steps: 50,
+        players: 2,
+        time_limit: 5,
         beam_width: 20,
         rhea_horizon: 10,
         rhea_generations: 5,
@@ -134,6 +137,9 @@ fn test_evaluate_batch_resumption_skipping() {
         generations: 1,
         population: 1,
         seeds: vec![123],
+        steps: 50,
+        players: 2,
+        time_limit: 5,
         beam_width: 1,
         rhea_horizon: 1,
         rhea_generations: 1,
@@ -174,6 +180,9 @@ fn test_spsa_resume_skipping() {
             generations: 10,
             population: 1, // SPSA population is pseudo-defined
             seeds: vec![123],
+            steps: 50,
+            players: 2,
+            time_limit: 5,
             beam_width: 1,
             rhea_horizon: 1,
             rhea_generations: 1,
@@ -265,6 +274,9 @@ fn test_history_duplication_on_resume() {
             generations: 10,
             population: 1,
             seeds: vec![123],
+            steps: 50,
+            players: 2,
+            time_limit: 5,
             beam_width: 1,
             rhea_horizon: 1,
             rhea_generations: 1,
@@ -337,6 +349,9 @@ fn test_ga_child_result_loss_on_resume() {
             generations: 1,
             population: 1,
             seeds: vec![123],
+            steps: 50,
+            players: 2,
+            time_limit: 5,
             beam_width: 1,
             rhea_horizon: 1,
             rhea_generations: 1,
@@ -403,6 +418,9 @@ fn test_ga_child_generation_determinism() {
         generations: 10,
         population: 10,
         seeds: vec![12345],
+        steps: 50,
+        players: 2,
+        time_limit: 5,
         beam_width: 1,
         rhea_horizon: 1,
         rhea_generations: 1,
@@ -453,6 +471,9 @@ fn test_ga_population_diversity_preservation() {
         generations: 1,
         population: 20,
         seeds: vec![1],
+        steps: 50,
+        players: 2,
+        time_limit: 5,
         beam_width: 1,
         rhea_horizon: 1,
         rhea_generations: 1,
@@ -530,6 +551,9 @@ fn test_seed_status_preservation_on_resume() {
             generations: 1,
             population: 1,
             seeds: vec![1, 2],
+            steps: 50,
+            players: 2,
+            time_limit: 5,
             beam_width: 1,
             rhea_horizon: 1,
             rhea_generations: 1,
@@ -552,7 +576,7 @@ fn test_seed_status_preservation_on_resume() {
         history: Vec::new(),
     };
     // 2. Simulate fixed run_tui status mapping logic
-    let mut seed_statuses = vec![0u8; 2];
+    let mut seed_statuses = [0u8; 2];
     for res in &checkpoint.seed_results {
         seed_statuses[res.seed_idx] = res.metrics.get_status();
     }
@@ -590,6 +614,9 @@ fn test_ga_resume_collision_corruption() {
             generations: 10,
             population: 5,
             seeds: vec![123],
+            steps: 50,
+            players: 2,
+            time_limit: 5,
             beam_width: 1,
             rhea_horizon: 1,
             rhea_generations: 1,
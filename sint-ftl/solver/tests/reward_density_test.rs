@@ -191,7 +191,7 @@ fn test_extinguish_vs_move_neutral() {
     )
     .total;
 
-    let move_act = GameAction::Move { to_room: 0 };
+    let move_act = GameAction::move_to(0);
     let mut next_state_move =
         GameLogic::apply_action(state.clone(), "P1", Action::Game(move_act.clone()), None).unwrap();
     sint_core::logic::resolution::resolve_proposal_queue(&mut next_state_move, false);
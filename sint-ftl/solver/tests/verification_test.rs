@@ -1,9 +1,17 @@
-use sint_core::types::{GameAction, ItemType};
+use sint_core::types::{GameAction, ItemType, RouteMode};
 use sint_solver::verification::parse_game_action;
 
 #[test]
 fn test_parse_game_action() {
-    assert_eq!(parse_game_action("Move 5"), GameAction::Move { to_room: 5 });
+    assert_eq!(parse_game_action("Move 5"), GameAction::move_to(5));
+    assert_eq!(
+        parse_game_action("Move 5 Safest force"),
+        GameAction::Move {
+            to_room: 5,
+            mode: RouteMode::Safest,
+            force: true,
+        }
+    );
     assert_eq!(parse_game_action("Bake"), GameAction::Bake);
     assert_eq!(parse_game_action("Shoot"), GameAction::Shoot);
     assert_eq!(
@@ -107,7 +115,7 @@ fn test_run_verification_unfinished_round() {
 
     // P1 has 2 AP. This block only provides 1 Move action (1 AP).
     // The round is not finished.
-    let solution = vec![vec![("P1".to_string(), GameAction::Move { to_room: 1 })]];
+    let solution = vec![vec![("P1".to_string(), GameAction::move_to(1))]];
 
     let result = run_verification(state, solution);
 
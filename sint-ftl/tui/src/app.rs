@@ -0,0 +1,433 @@
+//! Everything the terminal client knows and does, apart from drawing and I/O.
+//!
+//! `App` holds the room's `Replica` and what the player has selected. Keys and
+//! server messages go in through `handle_key` and `handle_net`, and the messages
+//! to send back come out, so the whole client can be driven without a terminal
+//! or a socket.
+
+use crate::net::NetEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use sint_core::chat::ChatLog;
+use sint_core::{
+    Action, GameAction, GameLogic, GameState, MetaAction, PlayerEvent, PlayerId, ProposedAction,
+};
+use sint_protocol::replica::Replica;
+use sint_protocol::{Capability, ClientMessage, PROTOCOL_VERSION, ServerMessage};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+/// Sequenced actions kept for the log pane.
+pub const LOG_LEN: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pane {
+    #[default]
+    Actions,
+    Proposals,
+}
+
+pub struct App {
+    pub room_id: String,
+    pub player_id: String,
+    replica: Replica,
+    /// The verified state with our pending events on top. This is what is drawn.
+    pub state: GameState,
+    /// What we can do in `state`, from `get_valid_actions`. Actions that need
+    /// typed input (chat, renaming) are left out; chat has its own key.
+    pub actions: Vec<Action>,
+    pub selected: usize,
+    pub pane: Pane,
+    /// Index into `state.proposal_queue`.
+    pub proposal_selected: usize,
+    /// The chat line being typed, while the input is open.
+    pub input: Option<String>,
+    pub chat: ChatLog,
+    /// The newest sequenced game actions, oldest first.
+    pub log: Vec<(PlayerId, GameAction)>,
+    /// Game actions sequenced since we joined, including those dropped from `log`.
+    pub log_total: usize,
+    pub online: BTreeSet<String>,
+    pub connected: bool,
+    /// From `Welcome`, to reclaim the seat later.
+    pub session_token: Option<String>,
+    /// The last error or announcement, for the status bar.
+    pub status: Option<String>,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(room_id: &str, player_id: &str) -> Self {
+        let mut replica = Replica::new();
+        App {
+            room_id: room_id.to_owned(),
+            player_id: player_id.to_owned(),
+            state: replica.predict(),
+            replica,
+            actions: vec![],
+            selected: 0,
+            pane: Pane::Actions,
+            proposal_selected: 0,
+            input: None,
+            chat: ChatLog::default(),
+            log: vec![],
+            log_total: 0,
+            online: BTreeSet::new(),
+            connected: false,
+            session_token: None,
+            status: None,
+            quit: false,
+        }
+    }
+
+    /// The opening messages: handshake, seat, catch-up and joining the game.
+    pub fn connect(
+        &mut self,
+        invite_token: Option<String>,
+        password: Option<String>,
+        session_token: Option<String>,
+    ) -> Vec<ClientMessage> {
+        let mut out = vec![
+            ClientMessage::Hello {
+                protocol_version: PROTOCOL_VERSION,
+                capabilities: vec![
+                    Capability::Resume,
+                    Capability::Presence,
+                    Capability::Delta,
+                    Capability::MessagePack,
+                ],
+            },
+            ClientMessage::Join {
+                room_id: self.room_id.clone(),
+                player_id: self.player_id.clone(),
+                invite_token,
+                password,
+                session_token,
+            },
+            ClientMessage::Resume {
+                last_sequence_id: self.replica.last_sequence_id(),
+            },
+        ];
+        out.extend(self.perform(Action::Meta(MetaAction::Join {
+            name: self.player_id.clone(),
+        })));
+        out
+    }
+
+    /// Applies `action` optimistically and returns the event to send, or shows
+    /// why it cannot be done.
+    pub fn perform(&mut self, action: Action) -> Option<ClientMessage> {
+        match self.replica.perform(&self.player_id, action) {
+            Ok((event, predicted)) => {
+                self.state = predicted;
+                self.refresh();
+                Some(ClientMessage::Event {
+                    sequence_id: 0,
+                    data: serde_json::to_value(&event).unwrap(),
+                })
+            }
+            Err(e) => {
+                self.status = Some(e.to_string());
+                None
+            }
+        }
+    }
+
+    /// Recomputes the prediction and everything derived from it.
+    fn repredict(&mut self) {
+        self.state = self.replica.predict();
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.actions = GameLogic::get_valid_actions(&self.state, &self.player_id)
+            .into_iter()
+            .filter(|a| {
+                !matches!(
+                    a,
+                    Action::Game(GameAction::Chat { .. })
+                        | Action::Meta(MetaAction::SetName { .. })
+                )
+            })
+            .collect();
+        self.selected = self.selected.min(self.actions.len().saturating_sub(1));
+        self.proposal_selected = self
+            .proposal_selected
+            .min(self.state.proposal_queue.len().saturating_sub(1));
+    }
+
+    pub fn handle_net(&mut self, event: NetEvent) -> Vec<ClientMessage> {
+        match event {
+            NetEvent::Message(msg) => self.handle_server(msg),
+            NetEvent::Unreadable(e) => {
+                self.status = Some(format!("Unreadable message: {}", e));
+                vec![]
+            }
+            NetEvent::Closed(reason) => {
+                // A rejection or kick already left its reason in the status bar
+                if self.connected || reason.is_some() {
+                    self.status = Some(reason.unwrap_or_else(|| "Disconnected.".to_owned()));
+                }
+                self.connected = false;
+                vec![]
+            }
+        }
+    }
+
+    pub fn handle_server(&mut self, msg: ServerMessage) -> Vec<ClientMessage> {
+        match msg {
+            ServerMessage::Event {
+                sequence_id,
+                timestamp,
+                data,
+            } => {
+                let Ok(event) = serde_json::from_value::<PlayerEvent>(data) else {
+                    return vec![];
+                };
+                match self.replica.apply_event(sequence_id, &event) {
+                    Ok(()) => {
+                        self.chat
+                            .record(sequence_id, timestamp, &self.replica.verified().chat);
+                        if let Action::Game(action) = event.action
+                            && !matches!(action, GameAction::Chat { .. })
+                        {
+                            self.log.push((event.player_id, action));
+                            self.log_total += 1;
+                            if self.log.len() > LOG_LEN {
+                                self.log.remove(0);
+                            }
+                        }
+                        self.repredict();
+                    }
+                    Err(e) => self.status = Some(format!("Event {}: {}", sequence_id, e)),
+                }
+            }
+            ServerMessage::Sync { sequence_id, delta } => {
+                if let Err(e) = self.replica.apply_sync(sequence_id, &delta) {
+                    self.status = Some(format!("Delta failed, asking for full state: {}", e));
+                    return vec![ClientMessage::SyncRequest {
+                        requestor_id: self.player_id.clone(),
+                    }];
+                }
+                self.repredict();
+            }
+            ServerMessage::Snapshot { sequence_id, hash } => {
+                self.replica.keep_snapshot(sequence_id, hash);
+            }
+            ServerMessage::Hello { .. } => self.connected = true,
+            ServerMessage::HelloRejected { reason } => {
+                self.status = Some(reason.describe());
+                self.connected = false;
+            }
+            ServerMessage::Welcome { session_token, .. } => {
+                self.connected = true;
+                self.session_token = Some(session_token);
+                // The server follows up with everyone who is online
+                self.online.clear();
+            }
+            ServerMessage::SyncRequest { requestor_id } => {
+                let verified = self.replica.verified();
+                if requestor_id != self.player_id && verified.sequence_id > 0 {
+                    let event = PlayerEvent {
+                        id: Uuid::new_v4(),
+                        player_id: self.player_id.clone(),
+                        action: Action::Meta(MetaAction::FullSync {
+                            state_json: serde_json::to_string(verified).unwrap(),
+                        }),
+                    };
+                    return vec![ClientMessage::Event {
+                        sequence_id: verified.sequence_id,
+                        data: serde_json::to_value(&event).unwrap(),
+                    }];
+                }
+            }
+            ServerMessage::Error { msg } => self.status = Some(msg),
+            ServerMessage::Rejected { event_id, reason } => {
+                self.status = Some(reason);
+                if self.replica.reject(event_id) {
+                    self.repredict();
+                }
+            }
+            ServerMessage::JoinRejected { reason, .. } => {
+                self.status = Some(reason.describe().to_owned());
+                self.connected = false;
+            }
+            ServerMessage::Kicked { reason } => {
+                self.status = Some(reason.describe().to_owned());
+                self.connected = false;
+            }
+            ServerMessage::System { message } => self.status = Some(message),
+            ServerMessage::PresenceChanged {
+                player_id,
+                connected,
+            } => {
+                if connected {
+                    self.online.insert(player_id);
+                } else {
+                    self.online.remove(&player_id);
+                }
+            }
+        }
+        vec![]
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Vec<ClientMessage> {
+        if key.kind != KeyEventKind::Press {
+            return vec![];
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return vec![];
+        }
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Enter => {
+                    let message = self.input.take().unwrap_or_default();
+                    if !message.trim().is_empty() {
+                        return self
+                            .perform(Action::Game(GameAction::Chat { message }))
+                            .into_iter()
+                            .collect();
+                    }
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return vec![];
+        }
+
+        let action = match key.code {
+            KeyCode::Char('q') => {
+                self.quit = true;
+                None
+            }
+            KeyCode::Char('c') | KeyCode::Char('/') => {
+                self.input = Some(String::new());
+                None
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.pane = match self.pane {
+                    Pane::Actions => Pane::Proposals,
+                    Pane::Proposals => Pane::Actions,
+                };
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.step(-1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.step(1);
+                None
+            }
+            KeyCode::Char('r') => {
+                let ready = self
+                    .state
+                    .players
+                    .get(&self.player_id)
+                    .is_some_and(|p| p.is_ready);
+                Some(Action::Game(GameAction::VoteReady { ready: !ready }))
+            }
+            KeyCode::Enter => match self.pane {
+                Pane::Actions => self.actions.get(self.selected).cloned(),
+                Pane::Proposals => self.undo_selected(),
+            },
+            KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace
+                if self.pane == Pane::Proposals =>
+            {
+                self.undo_selected()
+            }
+            _ => None,
+        };
+        action
+            .and_then(|action| self.perform(action))
+            .into_iter()
+            .collect()
+    }
+
+    fn step(&mut self, by: isize) {
+        let (selected, len) = match self.pane {
+            Pane::Actions => (&mut self.selected, self.actions.len()),
+            Pane::Proposals => (&mut self.proposal_selected, self.state.proposal_queue.len()),
+        };
+        if len > 0 {
+            *selected = selected.saturating_add_signed(by).min(len - 1);
+        }
+    }
+
+    /// `Undo` for the selected proposal, if it is ours.
+    fn undo_selected(&mut self) -> Option<Action> {
+        let proposal = self.state.proposal_queue.get(self.proposal_selected)?;
+        if proposal.player_id != self.player_id {
+            self.status = Some("You can only undo your own proposals.".to_owned());
+            return None;
+        }
+        Some(Action::Game(GameAction::Undo {
+            action_id: proposal.id,
+        }))
+    }
+
+    pub fn proposals(&self) -> &[ProposedAction] {
+        &self.state.proposal_queue
+    }
+}
+
+/// A short description of `action` by `player_id`.
+pub fn label(state: &GameState, player_id: &str, action: &Action) -> String {
+    let name = |id: &str| {
+        state
+            .players
+            .get(id)
+            .map_or(id.to_owned(), |p| p.name.clone())
+    };
+    let item = |index: usize| {
+        state
+            .players
+            .get(player_id)
+            .and_then(|p| p.inventory.get(index))
+            .map_or("item".to_owned(), |item| format!("{:?}", item))
+    };
+    let action = match action {
+        Action::Game(action) => action,
+        Action::Meta(MetaAction::Join { .. }) => return "Join".to_owned(),
+        Action::Meta(MetaAction::SetName { name }) => return format!("Rename to {}", name),
+        Action::Meta(MetaAction::SetMapLayout { layout }) => {
+            return format!("Map layout: {:?}", layout);
+        }
+        Action::Meta(MetaAction::FullSync { .. }) => return "Full sync".to_owned(),
+    };
+    match action {
+        GameAction::Move { to_room, .. } => {
+            let room = state
+                .map
+                .rooms
+                .get(to_room)
+                .map_or("?", |r| r.name.as_str());
+            format!("Move to {} ({})", room, to_room)
+        }
+        GameAction::Bake => "Bake Peppernuts".to_owned(),
+        GameAction::Shoot => "Fire Cannons".to_owned(),
+        GameAction::RaiseShields => "Raise Shields".to_owned(),
+        GameAction::EvasiveManeuvers => "Evasive Maneuvers".to_owned(),
+        GameAction::Lookout => "Lookout".to_owned(),
+        GameAction::Interact => "Interact".to_owned(),
+        GameAction::Extinguish => "Extinguish Fire".to_owned(),
+        GameAction::Repair => "Repair Leak".to_owned(),
+        GameAction::PickUp { item_type } => format!("Pick Up {:?}", item_type),
+        GameAction::Drop { item_index } => format!("Drop {}", item(*item_index)),
+        GameAction::Throw {
+            target_player,
+            item_index,
+        } => format!("Throw {} to {}", item(*item_index), name(target_player)),
+        GameAction::Revive { target_player } => format!("Revive {}", name(target_player)),
+        GameAction::FirstAid { target_player } => format!("Heal {}", name(target_player)),
+        GameAction::Chat { message } => format!("Say \"{}\"", message),
+        GameAction::VoteReady { ready: true } => "Ready".to_owned(),
+        GameAction::VoteReady { ready: false } => "Not ready".to_owned(),
+        GameAction::Pass => "End Turn (Pass)".to_owned(),
+        GameAction::Undo { .. } => "Undo".to_owned(),
+    }
+}
@@ -0,0 +1,112 @@
+use clap::Parser;
+use crossterm::{
+    event::Event,
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use sint_protocol::ClientMessage;
+use sint_tui::{app::App, net, ui};
+use std::io;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+/// Every option can also be set through the environment variable named in its help.
+#[derive(Parser, Debug)]
+#[command(version, about = "Play Sint FTL in the terminal")]
+struct Args {
+    /// WebSocket endpoint of a sint-server.
+    #[arg(long, env = "SINT_SERVER", default_value = "ws://localhost:3000/ws")]
+    server: String,
+
+    #[arg(long, env = "SINT_ROOM")]
+    room: String,
+
+    /// Player id, also used as your name in the game.
+    #[arg(long, env = "SINT_PLAYER")]
+    player: String,
+
+    /// Invite token for a private room.
+    #[arg(long, env = "SINT_INVITE_TOKEN", hide_env_values = true)]
+    invite_token: Option<String>,
+
+    /// Password for a private room.
+    #[arg(long, env = "SINT_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Reclaims a seat you held before. Printed when you quit.
+    #[arg(long, env = "SINT_SESSION_TOKEN", hide_env_values = true)]
+    session_token: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    let (out_tx, out_rx) = unbounded_channel();
+    let (net_tx, net_rx) = unbounded_channel();
+    tokio::spawn(net::run(args.server.clone(), out_rx, net_tx));
+
+    let mut app = App::new(&args.room, &args.player);
+    for msg in app.connect(args.invite_token, args.password, args.session_token) {
+        let _ = out_tx.send(msg);
+    }
+
+    // crossterm's reader blocks, so it gets a thread of its own
+    let (term_tx, term_rx) = unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = crossterm::event::read() {
+            if term_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let res = run_app(&mut terminal, &mut app, out_tx, net_rx, term_rx).await;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    if let Some(status) = &app.status {
+        println!("{}", status);
+    }
+    if let Some(token) = &app.session_token {
+        println!(
+            "To rejoin as {}, pass --session-token {}",
+            app.player_id, token
+        );
+    }
+    res
+}
+
+async fn run_app<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    out_tx: UnboundedSender<ClientMessage>,
+    mut net_rx: UnboundedReceiver<net::NetEvent>,
+    mut term_rx: UnboundedReceiver<Event>,
+) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|f| ui::draw(f, app))?;
+
+        let replies = tokio::select! {
+            Some(event) = term_rx.recv() => match event {
+                Event::Key(key) => app.handle_key(key),
+                // Anything else (a resize) just redraws
+                _ => vec![],
+            },
+            Some(event) = net_rx.recv() => app.handle_net(event),
+            else => break,
+        };
+        for msg in replies {
+            let _ = out_tx.send(msg);
+        }
+    }
+    Ok(())
+}
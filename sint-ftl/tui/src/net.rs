@@ -0,0 +1,74 @@
+//! The WebSocket to the server. Messages go out as JSON text; the server's
+//! replies are JSON until MessagePack is negotiated and binary after.
+
+use futures::{SinkExt, StreamExt};
+use sint_protocol::{ClientMessage, ServerMessage, msgpack};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug)]
+pub enum NetEvent {
+    Message(ServerMessage),
+    /// A frame that did not parse as a `ServerMessage`.
+    Unreadable(String),
+    /// The socket is gone, with the reason if there was one.
+    Closed(Option<String>),
+}
+
+/// Connects to `url` and pumps messages until either side hangs up. Dropping the
+/// `outgoing` sender closes the socket.
+pub async fn run(
+    url: String,
+    mut outgoing: UnboundedReceiver<ClientMessage>,
+    incoming: UnboundedSender<NetEvent>,
+) {
+    let ws = match connect_async(url.as_str()).await {
+        Ok((ws, _)) => ws,
+        Err(e) => {
+            let _ = incoming.send(NetEvent::Closed(Some(format!(
+                "Could not connect to {}: {}",
+                url, e
+            ))));
+            return;
+        }
+    };
+    let (mut write, mut read) = ws.split();
+
+    loop {
+        tokio::select! {
+            msg = outgoing.recv() => {
+                let Some(msg) = msg else {
+                    let _ = write.close().await;
+                    return;
+                };
+                let text = serde_json::to_string(&msg).unwrap();
+                if let Err(e) = write.send(Message::Text(text.into())).await {
+                    let _ = incoming.send(NetEvent::Closed(Some(e.to_string())));
+                    return;
+                }
+            }
+            msg = read.next() => {
+                let event = match msg {
+                    Some(Ok(Message::Text(text))) => serde_json::from_str::<ServerMessage>(&text)
+                        .map_or_else(|e| NetEvent::Unreadable(e.to_string()), NetEvent::Message),
+                    Some(Ok(Message::Binary(bytes))) => msgpack::from_slice::<ServerMessage>(&bytes)
+                        .map_or_else(|e| NetEvent::Unreadable(e.to_string()), NetEvent::Message),
+                    Some(Ok(Message::Close(frame))) => NetEvent::Closed(
+                        frame
+                            .map(|f| f.reason.to_string())
+                            .filter(|reason| !reason.is_empty()),
+                    ),
+                    // Pings are answered by tungstenite
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => NetEvent::Closed(Some(e.to_string())),
+                    None => NetEvent::Closed(None),
+                };
+                let closed = matches!(event, NetEvent::Closed(_));
+                if incoming.send(event).is_err() || closed {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::app::{App, Pane, label};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use sint_core::logic::actions::action_cost;
+use sint_core::types::{Action, ChatChannel};
+use sint_solver::tui::{
+    get_player_color, get_player_emoji, log::ActionLogWidget, map::MapWidget,
+    players::PlayersWidget, situations::SituationsWidget,
+};
+
+pub fn draw(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Status
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Keys
+        ])
+        .split(f.area());
+
+    f.render_widget(status_bar(app), chunks[0]);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[1]);
+
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Map
+            Constraint::Length(9), // Chat
+        ])
+        .split(main_chunks[0]);
+
+    let side_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8), // Players
+            Constraint::Length(7), // Situations
+            Constraint::Min(6),    // Actions
+            Constraint::Length(7), // Proposals
+            Constraint::Length(8), // Log
+        ])
+        .split(main_chunks[1]);
+
+    let map = MapWidget {
+        state: Some(&app.state),
+        block: None,
+    };
+    f.render_widget(map, left_chunks[0]);
+    draw_chat(f, app, left_chunks[1]);
+
+    f.render_widget(
+        PlayersWidget {
+            state: Some(&app.state),
+        },
+        side_chunks[0],
+    );
+    f.render_widget(
+        SituationsWidget {
+            state: Some(&app.state),
+        },
+        side_chunks[1],
+    );
+    draw_actions(f, app, side_chunks[2]);
+    draw_proposals(f, app, side_chunks[3]);
+
+    // Only the newest lines fit
+    let shown = (side_chunks[4].height.saturating_sub(2) as usize).min(app.log.len());
+    let log = ActionLogWidget {
+        title: "Log".to_string(),
+        first: app.log_total - shown + 1,
+        entries: app.log[app.log.len() - shown..].iter().collect(),
+        placeholder: "No actions yet",
+    };
+    f.render_widget(log, side_chunks[4]);
+
+    f.render_widget(key_help(app), chunks[2]);
+}
+
+fn status_bar(app: &App) -> Paragraph<'_> {
+    let state = &app.state;
+    let (dot, dot_color) = if app.connected {
+        ("●", Color::Green)
+    } else {
+        ("○", Color::Red)
+    };
+    let mut spans = vec![
+        Span::styled(format!("{} ", dot), Style::default().fg(dot_color)),
+        Span::styled(
+            format!("{} ", app.room_id),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(
+            "| {:?} | Turn {} | Hull {} | {} {}/{} | Online: {}",
+            state.phase,
+            state.turn_count,
+            state.hull_integrity,
+            state.enemy.name,
+            state.enemy.hp,
+            state.enemy.max_hp,
+            app.online.iter().cloned().collect::<Vec<_>>().join(", ")
+        )),
+    ];
+    if let Some(status) = &app.status {
+        spans.push(Span::styled(
+            format!(" | {}", status),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    Paragraph::new(Line::from(spans))
+}
+
+fn key_help(app: &App) -> Paragraph<'static> {
+    let text = if app.input.is_some() {
+        "Enter send | Esc cancel"
+    } else {
+        match app.pane {
+            Pane::Actions => "↑↓ select | Enter do | r ready | Tab proposals | c chat | q quit",
+            Pane::Proposals => "↑↓ select | Enter/x undo | Tab actions | c chat | q quit",
+        }
+    };
+    Paragraph::new(text).style(Style::default().fg(Color::DarkGray))
+}
+
+fn pane_block(title: String, focused: bool) -> Block<'static> {
+    let color = if focused { Color::Yellow } else { Color::White };
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(color))
+}
+
+fn draw_actions(f: &mut Frame, app: &App, area: Rect) {
+    let focused = app.pane == Pane::Actions && app.input.is_none();
+    let ap = app.state.players.get(&app.player_id).map_or(0, |p| p.ap);
+    let items: Vec<ListItem> = app
+        .actions
+        .iter()
+        .map(|action| {
+            let text = label(&app.state, &app.player_id, action);
+            let cost = match action {
+                Action::Game(game) => action_cost(&app.state, &app.player_id, game),
+                Action::Meta(_) => 0,
+            };
+            if cost > 0 {
+                ListItem::new(format!("{} [{} AP]", text, cost))
+            } else {
+                ListItem::new(text)
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block(format!("Actions (AP {})", ap), focused))
+        .highlight_symbol("> ")
+        .highlight_style(highlight(focused));
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_proposals(f: &mut Frame, app: &App, area: Rect) {
+    let focused = app.pane == Pane::Proposals && app.input.is_none();
+    let items: Vec<ListItem> = app
+        .proposals()
+        .iter()
+        .map(|p| {
+            let text = label(&app.state, &p.player_id, &Action::Game(p.action.clone()));
+            let mut style = Style::default().fg(get_player_color(&p.player_id));
+            if p.player_id == app.player_id {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", get_player_emoji(&p.player_id))),
+                Span::styled(format!("{}: ", p.player_id), style),
+                Span::raw(text),
+            ]))
+        })
+        .collect();
+    let title = format!("Proposals ({})", items.len());
+    let list = List::new(items)
+        .block(pane_block(title, focused))
+        .highlight_symbol("> ")
+        .highlight_style(highlight(focused));
+    let mut state = ListState::default().with_selected(Some(app.proposal_selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn highlight(focused: bool) -> Style {
+    if focused {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+}
+
+/// `HH:MM` (UTC) of a Unix millisecond timestamp.
+fn clock(timestamp: u64) -> String {
+    let minutes = timestamp / 60_000 % (24 * 60);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn draw_chat(f: &mut Frame, app: &App, area: Rect) {
+    let block = pane_block("Chat".to_string(), app.input.is_some());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let input_height = u16::from(app.input.is_some());
+    let rows = inner.height.saturating_sub(input_height) as usize;
+    let entries = app.chat.entries();
+    let lines: Vec<Line> = entries[entries.len().saturating_sub(rows)..]
+        .iter()
+        .map(|entry| {
+            let msg = &entry.message;
+            let sender = match msg.channel {
+                ChatChannel::Crew => Span::styled(
+                    format!("{}: ", msg.sender),
+                    Style::default().fg(get_player_color(&msg.sender)),
+                ),
+                ChatChannel::Spectators => Span::styled(
+                    format!("{} (spectator): ", msg.sender),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                ChatChannel::System => Span::styled("* ", Style::default().fg(Color::Cyan)),
+            };
+            let text_style = match msg.channel {
+                ChatChannel::System => Style::default().fg(Color::Cyan),
+                _ => Style::default(),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", clock(msg.timestamp)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                sender,
+                Span::styled(msg.text.clone(), text_style),
+            ])
+        })
+        .collect();
+    let log_area = Rect {
+        height: inner.height.saturating_sub(input_height),
+        ..inner
+    };
+    f.render_widget(Paragraph::new(lines), log_area);
+
+    if let Some(input) = &app.input {
+        let input_area = Rect {
+            y: inner.y + log_area.height,
+            height: input_height,
+            ..inner
+        };
+        f.render_widget(Paragraph::new(format!("> {}_", input)), input_area);
+    }
+}
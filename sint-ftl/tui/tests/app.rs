@@ -0,0 +1,292 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use sint_core::{Action, GameAction, GamePhase, MetaAction, PlayerEvent};
+use sint_protocol::{ClientMessage, ServerMessage};
+use sint_tui::app::{App, Pane};
+use sint_tui::net::{self, NetEvent};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::unbounded_channel;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn press(app: &mut App, codes: &[KeyCode]) -> Vec<ClientMessage> {
+    codes.iter().flat_map(|c| app.handle_key(key(*c))).collect()
+}
+
+fn sent_event(msg: &ClientMessage) -> PlayerEvent {
+    match msg {
+        ClientMessage::Event { data, .. } => serde_json::from_value(data.clone()).unwrap(),
+        other => panic!("Expected Event, got {:?}", other),
+    }
+}
+
+/// Plays the server: sequences `msgs` and hands them back to `app`.
+fn echo(app: &mut App, sequence_id: &mut u64, msgs: Vec<ClientMessage>) {
+    for msg in msgs {
+        if let ClientMessage::Event { data, .. } = msg {
+            *sequence_id += 1;
+            app.handle_server(ServerMessage::Event {
+                sequence_id: *sequence_id,
+                timestamp: 1_700_000_000_000 + *sequence_id,
+                data,
+            });
+        }
+    }
+}
+
+fn joined(player_id: &str) -> (App, u64) {
+    let mut app = App::new("room", player_id);
+    let mut seq = 0;
+    let msgs = app.connect(None, None, None);
+    echo(&mut app, &mut seq, msgs);
+    (app, seq)
+}
+
+#[test]
+fn test_connect_joins_the_game() {
+    let mut app = App::new("room", "P1");
+    let msgs = app.connect(None, Some("secret".to_owned()), None);
+
+    assert!(matches!(msgs[0], ClientMessage::Hello { .. }));
+    assert!(matches!(
+        &msgs[1],
+        ClientMessage::Join { room_id, password: Some(p), .. } if room_id == "room" && p == "secret"
+    ));
+    assert!(matches!(
+        msgs[2],
+        ClientMessage::Resume {
+            last_sequence_id: 0
+        }
+    ));
+    assert_eq!(
+        sent_event(&msgs[3]).action,
+        Action::Meta(MetaAction::Join {
+            name: "P1".to_owned()
+        })
+    );
+    // Shown before the server confirms it
+    assert!(app.state.players.contains_key("P1"));
+    assert!(
+        app.actions
+            .contains(&Action::Game(GameAction::VoteReady { ready: true }))
+    );
+}
+
+#[test]
+fn test_enter_performs_the_selected_action() {
+    let (mut app, _) = joined("P1");
+    let index = app
+        .actions
+        .iter()
+        .position(|a| *a == Action::Game(GameAction::VoteReady { ready: true }))
+        .unwrap();
+    let downs = vec![KeyCode::Down; index];
+
+    assert!(press(&mut app, &downs).is_empty());
+    assert_eq!(app.selected, index);
+    let msgs = press(&mut app, &[KeyCode::Enter]);
+    assert_eq!(
+        sent_event(&msgs[0]).action,
+        Action::Game(GameAction::VoteReady { ready: true })
+    );
+    // The whole crew is ready, so the prediction has already left the lobby
+    assert_ne!(app.state.phase, GamePhase::Lobby);
+}
+
+#[test]
+fn test_chat_is_typed_and_recorded_when_sequenced() {
+    let (mut app, mut seq) = joined("P1");
+
+    assert!(press(&mut app, &[KeyCode::Char('c'), KeyCode::Enter]).is_empty());
+    assert!(app.input.is_none());
+
+    let mut keys = vec![KeyCode::Char('c')];
+    keys.extend("hi q".chars().map(KeyCode::Char));
+    keys.push(KeyCode::Backspace);
+    keys.push(KeyCode::Enter);
+    let msgs = press(&mut app, &keys);
+    assert!(!app.quit);
+    assert_eq!(
+        sent_event(&msgs[0]).action,
+        Action::Game(GameAction::Chat {
+            message: "hi ".to_owned()
+        })
+    );
+    assert!(app.chat.is_empty());
+
+    echo(&mut app, &mut seq, msgs);
+    let entry = &app.chat.entries()[0];
+    assert_eq!(entry.message.text, "hi ");
+    assert_eq!(entry.message.timestamp, 1_700_000_000_000 + seq);
+    // Chat stays out of the action log
+    assert!(app.log.is_empty());
+}
+
+#[test]
+fn test_own_proposals_can_be_undone() {
+    let (mut app, mut seq) = joined("P1");
+    while app.state.phase != GamePhase::TacticalPlanning {
+        let msgs = press(&mut app, &[KeyCode::Char('r')]);
+        assert!(!msgs.is_empty(), "stuck in {:?}", app.state.phase);
+        echo(&mut app, &mut seq, msgs);
+    }
+
+    let index = app
+        .actions
+        .iter()
+        .position(|a| matches!(a, Action::Game(GameAction::Move { .. })))
+        .unwrap();
+    app.selected = index;
+    let msgs = press(&mut app, &[KeyCode::Enter]);
+    echo(&mut app, &mut seq, msgs);
+    assert_eq!(app.proposals().len(), 1);
+    assert_eq!(app.log.len(), app.log_total);
+
+    press(&mut app, &[KeyCode::Tab]);
+    assert_eq!(app.pane, Pane::Proposals);
+    let proposal_id = app.proposals()[0].id;
+    let msgs = press(&mut app, &[KeyCode::Char('x')]);
+    assert_eq!(
+        sent_event(&msgs[0]).action,
+        Action::Game(GameAction::Undo {
+            action_id: proposal_id
+        })
+    );
+    assert!(app.proposals().is_empty());
+}
+
+#[test]
+fn test_rejection_stays_in_the_status_bar() {
+    let (mut app, _) = joined("P1");
+    app.handle_server(ServerMessage::Welcome {
+        room_id: "room".to_owned(),
+        player_id: "P1".to_owned(),
+        session_token: "token".to_owned(),
+    });
+    assert!(app.connected);
+    assert_eq!(app.session_token.as_deref(), Some("token"));
+
+    app.handle_server(ServerMessage::Kicked {
+        reason: sint_protocol::KickReason::Kicked,
+    });
+    app.handle_net(NetEvent::Closed(None));
+    assert!(!app.connected);
+    assert!(app.status.as_deref().unwrap().contains("removed"));
+
+    press(&mut app, &[KeyCode::Char('q')]);
+    assert!(app.quit);
+}
+
+#[test]
+fn test_rejected_event_rolls_back_the_prediction() {
+    let (mut app, mut seq) = joined("P1");
+    let ready = Action::Game(GameAction::VoteReady { ready: true });
+    app.selected = app.actions.iter().position(|a| *a == ready).unwrap();
+    let refused = press(&mut app, &[KeyCode::Enter]);
+    assert_ne!(app.state.phase, GamePhase::Lobby);
+
+    app.handle_server(ServerMessage::Rejected {
+        event_id: sent_event(&refused[0]).id,
+        reason: "Rate limit exceeded".to_owned(),
+    });
+    assert_eq!(app.state.phase, GamePhase::Lobby);
+    assert_eq!(app.status.as_deref(), Some("Rate limit exceeded"));
+
+    // Sent again, it is sequenced like any other
+    app.selected = app.actions.iter().position(|a| *a == ready).unwrap();
+    let msgs = press(&mut app, &[KeyCode::Enter]);
+    echo(&mut app, &mut seq, msgs);
+    assert_ne!(app.state.phase, GamePhase::Lobby);
+}
+
+async fn spawn_server(config: sint_server::ServerConfig) -> std::net::SocketAddr {
+    let app = sint_server::create_app_with_config(config);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    addr
+}
+
+/// Runs `app` against the server at `addr` after sending `msgs`, until `done`.
+async fn play(
+    app: &mut App,
+    addr: std::net::SocketAddr,
+    msgs: Vec<ClientMessage>,
+    done: impl Fn(&App) -> bool,
+) {
+    let (out_tx, out_rx) = unbounded_channel();
+    let (net_tx, mut net_rx) = unbounded_channel();
+    tokio::spawn(net::run(format!("ws://{}/ws", addr), out_rx, net_tx));
+    for msg in msgs {
+        out_tx.send(msg).unwrap();
+    }
+    while !done(app) {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), net_rx.recv())
+            .await
+            .expect("Timed out waiting for the server")
+            .expect("Socket closed");
+        for msg in app.handle_net(event) {
+            out_tx.send(msg).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_plays_against_a_server() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        authoritative: true,
+        ..Default::default()
+    })
+    .await;
+
+    let mut app = App::new("tui_room", "P1");
+    let mut msgs = app.connect(None, None, None);
+    // Typed before the server has answered; it goes out after the join
+    msgs.extend(press(
+        &mut app,
+        &[KeyCode::Char('c'), KeyCode::Char('o'), KeyCode::Enter],
+    ));
+    play(&mut app, addr, msgs, |app| !app.chat.is_empty()).await;
+
+    assert!(app.connected);
+    assert!(app.state.players.contains_key("P1"));
+    assert_eq!(app.chat.entries()[0].message.text, "o");
+    assert!(app.chat.entries()[0].message.timestamp > 0);
+    assert!(app.online.contains("P1"));
+}
+
+#[tokio::test]
+async fn test_server_rejections_roll_back() {
+    let addr = spawn_server(sint_server::ServerConfig {
+        authoritative: true,
+        // Hello, Join, Resume, the join event and one chat
+        rate_limit: Some(sint_server::RateLimit {
+            per_second: 0.01,
+            burst: 5,
+        }),
+        ..Default::default()
+    })
+    .await;
+
+    let mut app = App::new("limited_room", "P1");
+    let mut msgs = app.connect(None, None, None);
+    for text in ['a', 'b', 'c'] {
+        let keys = [KeyCode::Char('c'), KeyCode::Char(text), KeyCode::Enter];
+        msgs.extend(press(&mut app, &keys));
+    }
+    assert_eq!(app.state.chat[0].text, "c");
+
+    // Only "a" is sequenced; the predictions of "b" and "c" go once refused,
+    // which may be before "a" comes back
+    let predicted = |app: &App| app.state.chat.first().map(|m| m.text.clone());
+    play(&mut app, addr, msgs, |app| {
+        !app.chat.is_empty() && predicted(app).as_deref() == Some("a")
+    })
+    .await;
+    let texts: Vec<_> = app.chat.entries().iter().map(|e| &e.message.text).collect();
+    assert_eq!(texts, ["a"]);
+    assert_eq!(app.status.as_deref(), Some("Rate limit exceeded"));
+}